@@ -0,0 +1,162 @@
+// odpic-codegen - Rust source generator for Oracle object types
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::{DataTypeDesc, Snapshot, TypeDesc};
+use anyhow::{anyhow, Result};
+use std::fmt::Write;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+    "unsafe", "use", "where", "while", "yield",
+];
+
+/// Generates Rust source code of all types in `snapshot`.
+///
+/// Each object type becomes a struct whose fields are `Option`s of the
+/// attribute types and each collection type becomes a newtype struct of
+/// `Vec`. Types referred by them must be in `snapshot`.
+pub fn generate(snapshot: &Snapshot) -> Result<String> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// This file is generated by odpic-codegen. Do not edit."
+    )?;
+    writeln!(out)?;
+    writeln!(out, "use odpic::object::OracleObject;")?;
+    for t in &snapshot.types {
+        writeln!(out)?;
+        generate_type(&mut out, snapshot, t)?;
+    }
+    Ok(out)
+}
+
+fn generate_type(out: &mut String, snapshot: &Snapshot, t: &TypeDesc) -> Result<()> {
+    let full_name = t.full_name();
+    let name = rust_type_name(t);
+    if let Some(elem_type) = &t.element_type {
+        writeln!(out, "/// Collection type `{}`", full_name)?;
+        writeln!(out, "#[derive(Clone, Debug, OracleObject)]")?;
+        writeln!(out, "#[oracle(type_name = {:?})]", full_name)?;
+        writeln!(
+            out,
+            "pub struct {}(pub Vec<Option<{}>>);",
+            name,
+            rust_type(snapshot, elem_type)?
+        )?;
+    } else {
+        writeln!(out, "/// Object type `{}`", full_name)?;
+        writeln!(out, "#[derive(Clone, Debug, OracleObject)]")?;
+        writeln!(out, "#[oracle(type_name = {:?})]", full_name)?;
+        writeln!(out, "pub struct {} {{", name)?;
+        for attr in &t.attributes {
+            let field = field_name(&attr.name);
+            if field.trim_start_matches("r#").to_uppercase() != attr.name {
+                writeln!(out, "    #[oracle(rename = {:?})]", attr.name)?;
+            }
+            writeln!(
+                out,
+                "    pub {}: Option<{}>,",
+                field,
+                rust_type(snapshot, &attr.data_type)?
+            )?;
+        }
+        writeln!(out, "}}")?;
+    }
+    Ok(())
+}
+
+fn rust_type(snapshot: &Snapshot, data_type: &DataTypeDesc) -> Result<String> {
+    Ok(match data_type.oracle_type.as_str() {
+        "NUMBER" if data_type.scale == 0 && (1..=9).contains(&data_type.precision) => "i32".into(),
+        "NUMBER" if data_type.scale == 0 && (10..=18).contains(&data_type.precision) => {
+            "i64".into()
+        }
+        // Integers which may not fit in i64 are kept as text.
+        "NUMBER" if data_type.scale == 0 && (19..=38).contains(&data_type.precision) => {
+            "String".into()
+        }
+        "NUMBER" | "NATIVE_DOUBLE" => "f64".into(),
+        "NATIVE_FLOAT" => "f32".into(),
+        "NATIVE_INT" => "i64".into(),
+        "BOOLEAN" => "bool".into(),
+        "VARCHAR" | "NVARCHAR" | "CHAR" | "NCHAR" | "CLOB" | "NCLOB" => "String".into(),
+        "DATE" | "TIMESTAMP" | "TIMESTAMP_TZ" | "TIMESTAMP_LTZ" => "odpic::Timestamp".into(),
+        "INTERVAL_DS" => "odpic::IntervalDS".into(),
+        "INTERVAL_YM" => "odpic::IntervalYM".into(),
+        "OBJECT" => {
+            let name = data_type
+                .object_type
+                .as_ref()
+                .ok_or_else(|| anyhow!("object type name is missing"))?;
+            let t = snapshot
+                .find(name)
+                .ok_or_else(|| anyhow!("type {} is not in the snapshot", name))?;
+            match &t.element_type {
+                Some(elem_type) => format!("Vec<Option<{}>>", rust_type(snapshot, elem_type)?),
+                None => rust_type_name(t),
+            }
+        }
+        _ => "odpic::SqlValue".into(),
+    })
+}
+
+/// Returns the struct name of the type, such as `AddressT` for
+/// `HR.ADDRESS_T` and `PkgRecT` for `HR.PKG.REC_T`.
+pub fn rust_type_name(t: &TypeDesc) -> String {
+    let name = match &t.package_name {
+        Some(package_name) => format!("{}_{}", package_name, t.name),
+        None => t.name.clone(),
+    };
+    let mut rust_name = String::with_capacity(name.len());
+    let mut upper = true;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if rust_name.is_empty() && c.is_ascii_digit() {
+                rust_name.push('T');
+            }
+            if upper {
+                rust_name.push(c.to_ascii_uppercase());
+            } else {
+                rust_name.push(c.to_ascii_lowercase());
+            }
+            upper = c.is_ascii_digit();
+        } else {
+            upper = true;
+        }
+    }
+    rust_name
+}
+
+fn field_name(attr_name: &str) -> String {
+    let mut name: String = attr_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.insert_str(0, "r#");
+    }
+    name
+}
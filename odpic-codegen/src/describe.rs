@@ -0,0 +1,92 @@
+// odpic-codegen - Rust source generator for Oracle object types
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::{AttrDesc, DataTypeDesc, Snapshot, TypeDesc};
+use anyhow::{bail, Result};
+use odpic::object::{ObjectDataType, ObjectType};
+use odpic::Connection;
+use odpic_sys::*;
+
+/// Reads the object types `names` and types nested in them.
+pub fn describe(conn: &Connection, names: &[String]) -> Result<Snapshot> {
+    let mut snapshot = Snapshot::default();
+    let mut queue: Vec<ObjectType> = Vec::new();
+    for name in names {
+        queue.push(conn.object_type(name)?);
+    }
+    while let Some(objtype) = queue.pop() {
+        if snapshot.find(&objtype.full_name()).is_some() {
+            continue;
+        }
+        let mut desc = TypeDesc {
+            schema: objtype.schema().into(),
+            name: objtype.name().into(),
+            package_name: objtype.package_name().map(Into::into),
+            attributes: Vec::new(),
+            element_type: None,
+        };
+        for attr in objtype.attributes() {
+            desc.attributes.push(AttrDesc {
+                name: attr.name().into(),
+                data_type: data_type_desc(attr.data_type(), &mut queue)?,
+            });
+        }
+        if let Some(elem_type) = objtype.element_type() {
+            desc.element_type = Some(data_type_desc(elem_type, &mut queue)?);
+        }
+        snapshot.types.push(desc);
+    }
+    Ok(snapshot)
+}
+
+fn data_type_desc(data_type: &ObjectDataType, queue: &mut Vec<ObjectType>) -> Result<DataTypeDesc> {
+    if let Some(objtype) = &data_type.object_type {
+        queue.push(objtype.clone());
+    }
+    Ok(DataTypeDesc {
+        oracle_type: oracle_type_name(data_type.oracle_type)?.into(),
+        precision: data_type.precision,
+        scale: data_type.scale,
+        object_type: data_type.object_type.as_ref().map(ObjectType::full_name),
+    })
+}
+
+fn oracle_type_name(oracle_type: dpiOracleTypeNum) -> Result<&'static str> {
+    Ok(match oracle_type {
+        DPI_ORACLE_TYPE_VARCHAR => "VARCHAR",
+        DPI_ORACLE_TYPE_NVARCHAR => "NVARCHAR",
+        DPI_ORACLE_TYPE_CHAR => "CHAR",
+        DPI_ORACLE_TYPE_NCHAR => "NCHAR",
+        DPI_ORACLE_TYPE_ROWID => "ROWID",
+        DPI_ORACLE_TYPE_RAW => "RAW",
+        DPI_ORACLE_TYPE_NATIVE_FLOAT => "NATIVE_FLOAT",
+        DPI_ORACLE_TYPE_NATIVE_DOUBLE => "NATIVE_DOUBLE",
+        DPI_ORACLE_TYPE_NATIVE_INT => "NATIVE_INT",
+        DPI_ORACLE_TYPE_NUMBER => "NUMBER",
+        DPI_ORACLE_TYPE_DATE => "DATE",
+        DPI_ORACLE_TYPE_TIMESTAMP => "TIMESTAMP",
+        DPI_ORACLE_TYPE_TIMESTAMP_TZ => "TIMESTAMP_TZ",
+        DPI_ORACLE_TYPE_TIMESTAMP_LTZ => "TIMESTAMP_LTZ",
+        DPI_ORACLE_TYPE_INTERVAL_DS => "INTERVAL_DS",
+        DPI_ORACLE_TYPE_INTERVAL_YM => "INTERVAL_YM",
+        DPI_ORACLE_TYPE_CLOB => "CLOB",
+        DPI_ORACLE_TYPE_NCLOB => "NCLOB",
+        DPI_ORACLE_TYPE_BLOB => "BLOB",
+        DPI_ORACLE_TYPE_BFILE => "BFILE",
+        DPI_ORACLE_TYPE_OBJECT => "OBJECT",
+        DPI_ORACLE_TYPE_BOOLEAN => "BOOLEAN",
+        _ => bail!("unsupported Oracle type {}", oracle_type),
+    })
+}
@@ -0,0 +1,30 @@
+// odpic-codegen - Rust source generator for Oracle object types
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Rust source generator for Oracle object types
+//!
+//! Object types are described by a [`Snapshot`], which is read from
+//! databases with the `online` feature or from JSON files. [`generate`]
+//! emits Rust structs deriving `odpic::object::OracleObject` from it.
+
+mod codegen;
+#[cfg(feature = "online")]
+mod describe;
+mod snapshot;
+
+pub use codegen::{generate, rust_type_name};
+#[cfg(feature = "online")]
+pub use describe::describe;
+pub use snapshot::{AttrDesc, DataTypeDesc, Snapshot, TypeDesc};
@@ -0,0 +1,102 @@
+// odpic-codegen - Rust source generator for Oracle object types
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use anyhow::{bail, Result};
+use odpic_codegen::{generate, Snapshot};
+use std::env;
+use std::fs;
+
+const USAGE: &str = "\
+Usage: odpic-codegen [OPTIONS] TYPE_NAME...
+
+Generates Rust structs for Oracle object types and types nested in them.
+
+Options:
+  --connect USER/PASSWORD@CONNECT_STRING
+                         reads types from the database
+  --snapshot FILE        reads types from a JSON snapshot instead
+  --save-snapshot FILE   writes types read from the database to FILE
+  --output FILE          writes the source code to FILE instead of stdout
+";
+
+#[derive(Default)]
+struct Args {
+    connect: Option<String>,
+    snapshot: Option<String>,
+    save_snapshot: Option<String>,
+    output: Option<String>,
+    type_names: Vec<String>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = Args::default();
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| match iter.next() {
+            Some(value) => Ok(Some(value)),
+            None => bail!("{} requires a value\n\n{}", name, USAGE),
+        };
+        match arg.as_str() {
+            "--connect" => args.connect = value(&arg)?,
+            "--snapshot" => args.snapshot = value(&arg)?,
+            "--save-snapshot" => args.save_snapshot = value(&arg)?,
+            "--output" => args.output = value(&arg)?,
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => bail!("unknown option {}\n\n{}", arg, USAGE),
+            _ => args.type_names.push(arg),
+        }
+    }
+    if args.type_names.is_empty() {
+        bail!("no type names\n\n{}", USAGE);
+    }
+    if args.connect.is_some() == args.snapshot.is_some() {
+        bail!("either --connect or --snapshot is required\n\n{}", USAGE);
+    }
+    Ok(args)
+}
+
+#[cfg(feature = "online")]
+fn read_database(connect: &str, type_names: &[String]) -> Result<Snapshot> {
+    let (user, rest) = connect.split_once('/').unwrap_or((connect, ""));
+    let (password, connect_string) = rest.split_once('@').unwrap_or((rest, ""));
+    let conn = odpic::Connection::connect(user, password, connect_string)?;
+    odpic_codegen::describe(&conn, type_names)
+}
+
+#[cfg(not(feature = "online"))]
+fn read_database(_connect: &str, _type_names: &[String]) -> Result<Snapshot> {
+    bail!("--connect requires the online feature")
+}
+
+fn main() -> Result<()> {
+    let args = parse_args()?;
+    let snapshot = match (&args.connect, &args.snapshot) {
+        (Some(connect), _) => read_database(connect, &args.type_names)?,
+        (_, Some(path)) => Snapshot::load(path)?.subset(&args.type_names)?,
+        _ => unreachable!(),
+    };
+    if let Some(path) = &args.save_snapshot {
+        snapshot.save(path)?;
+    }
+    let code = generate(&snapshot)?;
+    match &args.output {
+        Some(path) => fs::write(path, code)?,
+        None => print!("{}", code),
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+// odpic-codegen - Rust source generator for Oracle object types
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Descriptions of object types, which are saved to and loaded from JSON
+/// files so that code is generated without databases.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub types: Vec<TypeDesc>,
+}
+
+/// Object type or collection type
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeDesc {
+    pub schema: String,
+    pub name: String,
+    /// package name of a PL/SQL type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<AttrDesc>,
+    /// element type of a collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element_type: Option<DataTypeDesc>,
+}
+
+/// Attribute of an object type
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttrDesc {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: DataTypeDesc,
+}
+
+/// Type of an attribute or elements
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataTypeDesc {
+    /// `DPI_ORACLE_TYPE_*` without the prefix such as `NUMBER` and `VARCHAR`
+    pub oracle_type: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub precision: i16,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub scale: i8,
+    /// full name of the object type of nested objects and collections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+}
+
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+impl TypeDesc {
+    /// Returns the name qualified by the schema and the package.
+    pub fn full_name(&self) -> String {
+        match &self.package_name {
+            Some(package_name) => format!("{}.{}.{}", self.schema, package_name, self.name),
+            None => format!("{}.{}", self.schema, self.name),
+        }
+    }
+
+    pub fn is_collection(&self) -> bool {
+        self.element_type.is_some()
+    }
+
+    /// Returns the data types of attributes or of elements.
+    pub fn data_types(&self) -> impl Iterator<Item = &DataTypeDesc> {
+        self.attributes
+            .iter()
+            .map(|attr| &attr.data_type)
+            .chain(self.element_type.as_ref())
+    }
+}
+
+impl Snapshot {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Snapshot> {
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the type by the full name, or by the name without the schema
+    /// when it is unique.
+    pub fn find(&self, name: &str) -> Option<&TypeDesc> {
+        if let Some(t) = self.types.iter().find(|t| t.full_name() == name) {
+            return Some(t);
+        }
+        let mut iter = self.types.iter().filter(|t| t.name == name);
+        match (iter.next(), iter.next()) {
+            (Some(t), None) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// Returns the snapshot of `names` and of types nested in them.
+    pub fn subset(&self, names: &[String]) -> Result<Snapshot> {
+        let mut types: Vec<TypeDesc> = Vec::new();
+        let mut queue: Vec<String> = names.to_vec();
+        while let Some(name) = queue.pop() {
+            let t = self
+                .find(&name)
+                .ok_or_else(|| anyhow!("type {} is not in the snapshot", name))?;
+            if types.iter().any(|u| u.full_name() == t.full_name()) {
+                continue;
+            }
+            queue.extend(t.data_types().filter_map(|dt| dt.object_type.clone()));
+            types.push(t.clone());
+        }
+        Ok(Snapshot { types })
+    }
+}
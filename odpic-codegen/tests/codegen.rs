@@ -0,0 +1,58 @@
+// odpic-codegen - Rust source generator for Oracle object types
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::object::OracleObject;
+use odpic_codegen::{generate, Snapshot};
+use std::fs;
+use std::path::PathBuf;
+
+// The expected output is compiled with the derive macro.
+#[allow(dead_code)]
+mod hr {
+    include!("fixtures/hr.rs");
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/fixtures");
+    path.push(name);
+    path
+}
+
+#[test]
+fn generate_from_snapshot() {
+    let snapshot = Snapshot::load(fixture_path("hr.json")).unwrap();
+    let subset = snapshot.subset(&["PERSON_T".into()]).unwrap();
+    let names: Vec<_> = subset.types.iter().map(|t| t.full_name()).collect();
+    assert_eq!(names, ["HR.PERSON_T", "HR.ADDRESS_LIST_T", "HR.ADDRESS_T"]);
+    let expected = fs::read_to_string(fixture_path("hr.rs")).unwrap();
+    assert_eq!(generate(&subset).unwrap(), expected);
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let snapshot = Snapshot::load(fixture_path("hr.json")).unwrap();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert_eq!(serde_json::from_str::<Snapshot>(&json).unwrap(), snapshot);
+    assert!(snapshot.subset(&["HR.NO_SUCH_T".into()]).is_err());
+}
+
+#[test]
+fn generated_types_are_oracle_objects() {
+    fn assert_object<T: OracleObject>() {}
+    assert_object::<hr::PersonT>();
+    assert_object::<hr::AddressListT>();
+    assert_object::<hr::AddressT>();
+}
@@ -0,0 +1,31 @@
+// This file is generated by odpic-codegen. Do not edit.
+
+use odpic::object::OracleObject;
+
+/// Object type `HR.PERSON_T`
+#[derive(Clone, Debug, OracleObject)]
+#[oracle(type_name = "HR.PERSON_T")]
+pub struct PersonT {
+    pub id: Option<i64>,
+    pub account_no: Option<String>,
+    pub name: Option<String>,
+    pub salary: Option<f64>,
+    pub birthday: Option<odpic::Timestamp>,
+    pub photo: Option<odpic::SqlValue>,
+    pub addresses: Option<Vec<Option<AddressT>>>,
+}
+
+/// Collection type `HR.ADDRESS_LIST_T`
+#[derive(Clone, Debug, OracleObject)]
+#[oracle(type_name = "HR.ADDRESS_LIST_T")]
+pub struct AddressListT(pub Vec<Option<AddressT>>);
+
+/// Object type `HR.ADDRESS_T`
+#[derive(Clone, Debug, OracleObject)]
+#[oracle(type_name = "HR.ADDRESS_T")]
+pub struct AddressT {
+    pub street: Option<String>,
+    #[oracle(rename = "zipCode")]
+    pub zipcode: Option<i32>,
+    pub r#type: Option<String>,
+}
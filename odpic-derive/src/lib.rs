@@ -0,0 +1,433 @@
+// odpic-derive - derive macros for odpic
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Derive macros for [`odpic`].
+//!
+//! Use them through the `derive` feature of `odpic`.
+//!
+//! [`odpic`]: https://docs.rs/odpic
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr, Result};
+
+/// Derives `odpic::object::OracleObject` and `odpic::object::ObjectField`.
+///
+/// A struct with named fields is mapped to an object type. Each field is
+/// mapped to the attribute of the uppercased field name. A newtype struct
+/// of `Vec<T>` is mapped to a collection type (`VARRAY` or nested table).
+///
+/// Container attributes:
+///
+/// * `#[oracle(type_name = "SCHEMA.TYPE_NAME")]` sets `OracleObject::TYPE_NAME`.
+/// * `#[oracle(rename_all = "...")]` changes how field names are mapped to
+///   attribute names: `"UPPERCASE"` (default), `"lowercase"`, `"camelCase"`,
+///   `"PascalCase"` or `"preserve"`.
+///
+/// Field attributes:
+///
+/// * `#[oracle(rename = "NAME")]` sets the attribute name.
+///
+/// Field types must implement `odpic::object::ObjectField`: scalars such
+/// as integers, `String` and `Timestamp`, `Option<T>` for nullable
+/// attributes, `Vec<T>` for nested collections and other types deriving
+/// `OracleObject` for nested objects. Attribute names and types are checked
+/// against the object type on first use.
+///
+/// ```no_run
+/// use odpic::object::{OracleObject, ObjectValue};
+/// use odpic::Connection;
+///
+/// #[derive(Debug, OracleObject)]
+/// #[oracle(type_name = "HR.ADDRESS_T")]
+/// struct Address {
+///     street: String,
+///     #[oracle(rename = "ZIP")]
+///     zip_code: Option<String>,
+/// }
+///
+/// #[derive(Debug, OracleObject)]
+/// #[oracle(type_name = "HR.ADDRESS_LIST_T")]
+/// struct AddressList(Vec<Address>);
+///
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let list = AddressList(vec![Address {
+///     street: "Main St".into(),
+///     zip_code: None,
+/// }]);
+/// let obj = list.to_object(&conn.object_type(AddressList::TYPE_NAME)?)?;
+/// let list = AddressList::from_object(&obj)?;
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_derive(OracleObject, attributes(oracle))]
+pub fn derive_oracle_object(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Uppercase,
+    Lowercase,
+    CamelCase,
+    PascalCase,
+    Preserve,
+}
+
+impl RenameRule {
+    fn from_lit(lit: &LitStr) -> Result<RenameRule> {
+        match lit.value().as_str() {
+            "UPPERCASE" => Ok(RenameRule::Uppercase),
+            "lowercase" => Ok(RenameRule::Lowercase),
+            "camelCase" => Ok(RenameRule::CamelCase),
+            "PascalCase" => Ok(RenameRule::PascalCase),
+            "preserve" => Ok(RenameRule::Preserve),
+            _ => Err(Error::new(
+                lit.span(),
+                "expected UPPERCASE, lowercase, camelCase, PascalCase or preserve",
+            )),
+        }
+    }
+
+    fn apply(self, field: &str) -> String {
+        let field = field.strip_prefix("r#").unwrap_or(field);
+        match self {
+            RenameRule::Uppercase => field.to_uppercase(),
+            RenameRule::Lowercase => field.to_lowercase(),
+            RenameRule::Preserve => field.to_string(),
+            RenameRule::CamelCase | RenameRule::PascalCase => {
+                let mut name = String::with_capacity(field.len());
+                let mut upper = matches!(self, RenameRule::PascalCase);
+                for c in field.chars() {
+                    if c == '_' {
+                        upper = !name.is_empty();
+                    } else if upper {
+                        name.extend(c.to_uppercase());
+                        upper = false;
+                    } else {
+                        name.push(c);
+                    }
+                }
+                name
+            }
+        }
+    }
+}
+
+struct ContainerAttrs {
+    type_name: String,
+    rename_all: RenameRule,
+}
+
+fn container_attrs(input: &DeriveInput) -> Result<ContainerAttrs> {
+    let mut attrs = ContainerAttrs {
+        type_name: String::new(),
+        rename_all: RenameRule::Uppercase,
+    };
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("oracle")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("type_name") {
+                attrs.type_name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("rename_all") {
+                attrs.rename_all = RenameRule::from_lit(&meta.value()?.parse()?)?;
+                Ok(())
+            } else {
+                Err(meta.error("expected type_name or rename_all"))
+            }
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn field_rename(field: &syn::Field) -> Result<Option<String>> {
+    let mut rename = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("oracle")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected rename"))
+            }
+        })?;
+    }
+    Ok(rename)
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "OracleObject cannot be derived for generic types",
+        ));
+    }
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "OracleObject can be derived only for structs",
+            ))
+        }
+    };
+    let attrs = container_attrs(input)?;
+    let ident = &input.ident;
+    let rust_name = ident.to_string();
+    let type_name = &attrs.type_name;
+    let methods = match &data.fields {
+        Fields::Named(fields) => {
+            let mut names = Vec::new();
+            let mut idents = Vec::new();
+            let mut types = Vec::new();
+            for field in &fields.named {
+                let field_ident = field.ident.as_ref().unwrap();
+                names.push(match field_rename(field)? {
+                    Some(name) => name,
+                    None => attrs.rename_all.apply(&field_ident.to_string()),
+                });
+                idents.push(field_ident);
+                types.push(&field.ty);
+            }
+            quote! {
+                fn check_object_type(objtype: &::odpic::object::ObjectType) -> ::odpic::Result<()> {
+                    if objtype.is_collection() {
+                        return Err(::odpic::Error::InvalidConversion(format!(
+                            "{} cannot be mapped to collection type {}",
+                            #rust_name,
+                            objtype.full_name()
+                        )));
+                    }
+                    #(
+                        <#types as ::odpic::object::ObjectField>::check_data_type(
+                            objtype.expect_attribute(#names)?.data_type(),
+                        )?;
+                    )*
+                    Ok(())
+                }
+
+                fn to_object_value(&self) -> ::odpic::Result<::odpic::object::ObjectValue> {
+                    Ok(::odpic::object::ObjectValue::Object {
+                        type_name: #type_name.to_string(),
+                        attributes: vec![
+                            #((
+                                #names.to_string(),
+                                ::odpic::object::ObjectField::to_field_value(&self.#idents)?,
+                            ),)*
+                        ],
+                    })
+                }
+
+                fn from_object_value(
+                    value: ::odpic::object::ObjectValue,
+                ) -> ::odpic::Result<Self> {
+                    #[allow(unused_mut)]
+                    let mut value = value.into_object()?;
+                    Ok(#ident {
+                        #(
+                            #idents: ::odpic::object::ObjectField::from_field_value(
+                                value.take_attribute(#names),
+                            )?,
+                        )*
+                    })
+                }
+            }
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let ty = &fields.unnamed[0].ty;
+            quote! {
+                fn check_object_type(objtype: &::odpic::object::ObjectType) -> ::odpic::Result<()> {
+                    <#ty as ::odpic::object::ObjectField>::check_data_type(&objtype.into())
+                }
+
+                fn to_object_value(&self) -> ::odpic::Result<::odpic::object::ObjectValue> {
+                    let elements = ::odpic::object::ObjectField::to_field_value(&self.0)?
+                        .into_elements()?;
+                    Ok(::odpic::object::ObjectValue::Collection {
+                        type_name: #type_name.to_string(),
+                        elements,
+                    })
+                }
+
+                fn from_object_value(
+                    value: ::odpic::object::ObjectValue,
+                ) -> ::odpic::Result<Self> {
+                    Ok(#ident(::odpic::object::ObjectField::from_field_value(value)?))
+                }
+            }
+        }
+        _ => return Err(Error::new_spanned(
+            &input.ident,
+            "OracleObject can be derived only for structs with named fields and newtype structs",
+        )),
+    };
+    Ok(quote! {
+        impl ::odpic::object::OracleObject for #ident {
+            const TYPE_NAME: &'static str = #type_name;
+
+            #methods
+        }
+
+        impl ::odpic::object::ObjectField for #ident {
+            fn check_data_type(data_type: &::odpic::object::ObjectDataType) -> ::odpic::Result<()> {
+                match &data_type.object_type {
+                    Some(objtype) => {
+                        <Self as ::odpic::object::OracleObject>::check_object_type(objtype)
+                    }
+                    None => Err(::odpic::Error::InvalidConversion(format!(
+                        "{} cannot be mapped to Oracle type {}",
+                        #rust_name,
+                        data_type.oracle_type
+                    ))),
+                }
+            }
+
+            fn to_field_value(&self) -> ::odpic::Result<::odpic::object::ObjectValue> {
+                ::odpic::object::OracleObject::to_object_value(self)
+            }
+
+            fn from_field_value(
+                value: ::odpic::object::ObjectValue,
+            ) -> ::odpic::Result<Self> {
+                if value.is_null() {
+                    return Err(::odpic::Error::NullValue);
+                }
+                <Self as ::odpic::object::OracleObject>::from_object_value(value)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn expand_err(input: DeriveInput) -> String {
+        match expand(&input) {
+            Ok(_) => panic!("{} is expanded", input.ident),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn rename_rules() {
+        let cases = [
+            (RenameRule::Uppercase, "UNIT_PRICE"),
+            (RenameRule::Lowercase, "unit_price"),
+            (RenameRule::CamelCase, "unitPrice"),
+            (RenameRule::PascalCase, "UnitPrice"),
+            (RenameRule::Preserve, "unit_price"),
+        ];
+        for (rule, name) in cases {
+            assert_eq!(rule.apply("unit_price"), name);
+        }
+        assert_eq!(RenameRule::Uppercase.apply("r#type"), "TYPE");
+        assert_eq!(RenameRule::CamelCase.apply("_id"), "id");
+        assert_eq!(RenameRule::PascalCase.apply("id"), "Id");
+        assert!(RenameRule::from_lit(&parse_quote!("snake_case")).is_err());
+    }
+
+    #[test]
+    fn expand_structs() {
+        let tokens = expand(&parse_quote! {
+            #[oracle(type_name = "HR.ADDRESS_T", rename_all = "camelCase")]
+            struct Address {
+                street_name: String,
+                #[oracle(rename = "ZIP")]
+                zip_code: Option<String>,
+            }
+        })
+        .unwrap()
+        .to_string();
+        assert!(tokens.contains("const TYPE_NAME : & 'static str = \"HR.ADDRESS_T\""));
+        assert!(tokens.contains("expect_attribute (\"streetName\")"));
+        assert!(tokens.contains("expect_attribute (\"ZIP\")"));
+        assert!(!tokens.contains("\"ZIP_CODE\""));
+
+        let tokens = expand(&parse_quote! {
+            #[oracle(type_name = "HR.ADDRESS_LIST_T")]
+            struct AddressList(Vec<Address>);
+        })
+        .unwrap()
+        .to_string();
+        assert!(tokens.contains("ObjectValue :: Collection"));
+        assert!(tokens.contains("< Vec < Address > as :: odpic :: object :: ObjectField >"));
+    }
+
+    #[test]
+    fn reject_unsupported_types() {
+        let only_structs = "OracleObject can be derived only for structs";
+        let named_or_newtype =
+            "OracleObject can be derived only for structs with named fields and newtype structs";
+        assert_eq!(
+            expand_err(parse_quote!(
+                enum E {
+                    A,
+                }
+            )),
+            only_structs
+        );
+        assert_eq!(expand_err(parse_quote!(union U { a: u8 })), only_structs);
+        assert_eq!(
+            expand_err(parse_quote!(
+                struct S<T> {
+                    t: T,
+                }
+            )),
+            "OracleObject cannot be derived for generic types"
+        );
+        assert_eq!(
+            expand_err(parse_quote!(
+                struct S(i32, i32);
+            )),
+            named_or_newtype
+        );
+        assert_eq!(
+            expand_err(parse_quote!(
+                struct S;
+            )),
+            named_or_newtype
+        );
+    }
+
+    #[test]
+    fn reject_invalid_attributes() {
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[oracle(rename_all = "kebab-case")]
+                struct S { a: i32 }
+            }),
+            "expected UPPERCASE, lowercase, camelCase, PascalCase or preserve"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                #[oracle(name = "S")]
+                struct S { a: i32 }
+            }),
+            "expected type_name or rename_all"
+        );
+        assert_eq!(
+            expand_err(parse_quote! {
+                struct S { #[oracle(rename_all = "lowercase")] a: i32 }
+            }),
+            "expected rename"
+        );
+    }
+}
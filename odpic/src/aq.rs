@@ -0,0 +1,1522 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Oracle Advanced Queuing (AQ)
+//!
+//! [`Queue`] enqueues [`MsgProps`] and dequeues [`Message`]s with RAW or
+//! object payloads.
+//!
+//! ```no_run
+//! # use odpic::Connection;
+//! # use odpic::aq::{DeqOptions, MsgProps, Payload, Wait};
+//! # fn f(conn: &Connection) -> odpic::Result<()> {
+//! let queue = conn.queue("RAW_QUEUE")?;
+//! let props = MsgProps::new(b"hello".to_vec())
+//!     .priority(1)
+//!     .correlation("greeting");
+//! let msg_id = queue.enqueue(&props)?;
+//! conn.commit()?;
+//!
+//! queue.set_deq_options(&DeqOptions::new().wait(Wait::Seconds(5)))?;
+//! for msg in queue.dequeue_many(10)? {
+//!     if let Payload::Raw(bytes) = &msg.payload {
+//!         println!("{:?}: {:?}", msg.msg_id, bytes);
+//!     }
+//! }
+//! conn.commit()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ffi::*;
+use crate::object::{Object, ObjectType};
+use crate::{Connection, Context, Error, Result, Timestamp};
+use std::fmt;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// ((uint32_t) -1) in dpi.h, which bindgen doesn't generate.
+const DPI_DEQ_WAIT_FOREVER: u32 = u32::MAX;
+
+/// Dequeue mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeqMode {
+    /// reads messages without locking them
+    Browse,
+    /// reads and locks messages
+    Locked,
+    /// reads and removes messages. This is the default.
+    Remove,
+    /// removes messages without reading their payloads
+    RemoveNoData,
+}
+
+impl DeqMode {
+    fn to_dpi(self) -> dpiDeqMode {
+        match self {
+            DeqMode::Browse => DPI_MODE_DEQ_BROWSE,
+            DeqMode::Locked => DPI_MODE_DEQ_LOCKED,
+            DeqMode::Remove => DPI_MODE_DEQ_REMOVE,
+            DeqMode::RemoveNoData => DPI_MODE_DEQ_REMOVE_NO_DATA,
+        }
+    }
+}
+
+/// Position of messages to be dequeued
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeqNavigation {
+    /// the first available message, which refreshes the snapshot of the
+    /// queue
+    FirstMsg,
+    /// the first message of the next transaction group
+    NextTransaction,
+    /// the next message. This is the default.
+    NextMsg,
+}
+
+impl DeqNavigation {
+    fn to_dpi(self) -> dpiDeqNavigation {
+        match self {
+            DeqNavigation::FirstMsg => DPI_DEQ_NAV_FIRST_MSG,
+            DeqNavigation::NextTransaction => DPI_DEQ_NAV_NEXT_TRANSACTION,
+            DeqNavigation::NextMsg => DPI_DEQ_NAV_NEXT_MSG,
+        }
+    }
+}
+
+/// Whether enqueue and dequeue are parts of the current transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    /// in an autonomous transaction
+    Immediate,
+    /// in the current transaction. This is the default.
+    OnCommit,
+}
+
+impl Visibility {
+    fn to_dpi(self) -> dpiVisibility {
+        match self {
+            Visibility::Immediate => DPI_VISIBILITY_IMMEDIATE,
+            Visibility::OnCommit => DPI_VISIBILITY_ON_COMMIT,
+        }
+    }
+}
+
+/// Delivery mode of messages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryMode {
+    /// This is the default.
+    Persistent,
+    Buffered,
+    /// only for dequeue
+    PersistentOrBuffered,
+}
+
+impl DeliveryMode {
+    fn to_dpi(self) -> dpiMessageDeliveryMode {
+        match self {
+            DeliveryMode::Persistent => DPI_MODE_MSG_PERSISTENT,
+            DeliveryMode::Buffered => DPI_MODE_MSG_BUFFERED,
+            DeliveryMode::PersistentOrBuffered => DPI_MODE_MSG_PERSISTENT_OR_BUFFERED,
+        }
+    }
+
+    fn from_dpi(mode: dpiMessageDeliveryMode) -> Result<DeliveryMode> {
+        match mode {
+            DPI_MODE_MSG_PERSISTENT => Ok(DeliveryMode::Persistent),
+            DPI_MODE_MSG_BUFFERED => Ok(DeliveryMode::Buffered),
+            DPI_MODE_MSG_PERSISTENT_OR_BUFFERED => Ok(DeliveryMode::PersistentOrBuffered),
+            _ => Err(Error::UnsupportedType(format!("delivery mode {}", mode))),
+        }
+    }
+}
+
+/// State of dequeued messages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageState {
+    Ready,
+    /// The delay hasn't passed yet.
+    Waiting,
+    Processed,
+    /// moved to the exception queue
+    Expired,
+}
+
+impl MessageState {
+    fn from_dpi(state: dpiMessageState) -> Result<MessageState> {
+        match state {
+            DPI_MSG_STATE_READY => Ok(MessageState::Ready),
+            DPI_MSG_STATE_WAITING => Ok(MessageState::Waiting),
+            DPI_MSG_STATE_PROCESSED => Ok(MessageState::Processed),
+            DPI_MSG_STATE_EXPIRED => Ok(MessageState::Expired),
+            _ => Err(Error::UnsupportedType(format!("message state {}", state))),
+        }
+    }
+}
+
+/// Time to wait for messages on dequeue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wait {
+    /// `DPI_DEQ_WAIT_NO_WAIT`
+    NoWait,
+    /// `DPI_DEQ_WAIT_FOREVER`. This is the default.
+    Forever,
+    Seconds(u32),
+}
+
+impl Wait {
+    fn to_dpi(self) -> u32 {
+        match self {
+            Wait::NoWait => DPI_DEQ_WAIT_NO_WAIT,
+            Wait::Forever => DPI_DEQ_WAIT_FOREVER,
+            Wait::Seconds(secs) => secs.min(DPI_DEQ_WAIT_FOREVER - 1),
+        }
+    }
+}
+
+/// Enqueue options set by [`Queue::set_enq_options`]
+///
+/// Options which are not set are left unchanged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnqOptions {
+    visibility: Option<Visibility>,
+    delivery_mode: Option<DeliveryMode>,
+    transformation: Option<String>,
+}
+
+impl EnqOptions {
+    pub fn new() -> EnqOptions {
+        EnqOptions::default()
+    }
+
+    pub fn visibility(mut self, visibility: Visibility) -> EnqOptions {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    pub fn delivery_mode(mut self, mode: DeliveryMode) -> EnqOptions {
+        self.delivery_mode = Some(mode);
+        self
+    }
+
+    /// Sets the transformation applied to messages before enqueue.
+    pub fn transformation(mut self, name: &str) -> EnqOptions {
+        self.transformation = Some(name.into());
+        self
+    }
+}
+
+/// Dequeue options set by [`Queue::set_deq_options`]
+///
+/// Options which are not set are left unchanged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeqOptions {
+    mode: Option<DeqMode>,
+    navigation: Option<DeqNavigation>,
+    visibility: Option<Visibility>,
+    wait: Option<Wait>,
+    condition: Option<String>,
+    consumer_name: Option<String>,
+    correlation: Option<String>,
+    msg_id: Option<Vec<u8>>,
+    transformation: Option<String>,
+    delivery_mode: Option<DeliveryMode>,
+}
+
+impl DeqOptions {
+    pub fn new() -> DeqOptions {
+        DeqOptions::default()
+    }
+
+    pub fn mode(mut self, mode: DeqMode) -> DeqOptions {
+        self.mode = Some(mode);
+        self
+    }
+
+    pub fn navigation(mut self, navigation: DeqNavigation) -> DeqOptions {
+        self.navigation = Some(navigation);
+        self
+    }
+
+    pub fn visibility(mut self, visibility: Visibility) -> DeqOptions {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    pub fn wait(mut self, wait: Wait) -> DeqOptions {
+        self.wait = Some(wait);
+        self
+    }
+
+    /// Sets the condition, a boolean expression like the `WHERE` clause,
+    /// which messages must satisfy. An empty string clears it.
+    pub fn condition(mut self, condition: &str) -> DeqOptions {
+        self.condition = Some(condition.into());
+        self
+    }
+
+    /// Sets the consumer of multi-consumer queues. An empty string clears
+    /// it.
+    pub fn consumer_name(mut self, name: &str) -> DeqOptions {
+        self.consumer_name = Some(name.into());
+        self
+    }
+
+    /// Sets the correlation which messages must match, in which `%` and
+    /// `_` are wildcards. An empty string clears it.
+    pub fn correlation(mut self, correlation: &str) -> DeqOptions {
+        self.correlation = Some(correlation.into());
+        self
+    }
+
+    /// Sets the id of the message to be dequeued. An empty id clears it.
+    pub fn msg_id(mut self, msg_id: &[u8]) -> DeqOptions {
+        self.msg_id = Some(msg_id.into());
+        self
+    }
+
+    /// Sets the transformation applied to messages after dequeue.
+    pub fn transformation(mut self, name: &str) -> DeqOptions {
+        self.transformation = Some(name.into());
+        self
+    }
+
+    pub fn delivery_mode(mut self, mode: DeliveryMode) -> DeqOptions {
+        self.delivery_mode = Some(mode);
+        self
+    }
+}
+
+/// Payload of messages
+#[derive(Clone, Debug)]
+pub enum Payload {
+    /// payload of RAW queues
+    Raw(Vec<u8>),
+    /// payload of queues whose payload type is an object type
+    Object(Object),
+}
+
+impl From<Vec<u8>> for Payload {
+    fn from(bytes: Vec<u8>) -> Payload {
+        Payload::Raw(bytes)
+    }
+}
+
+impl From<&[u8]> for Payload {
+    fn from(bytes: &[u8]) -> Payload {
+        Payload::Raw(bytes.into())
+    }
+}
+
+impl From<Object> for Payload {
+    fn from(obj: Object) -> Payload {
+        Payload::Object(obj)
+    }
+}
+
+/// Message properties and a payload to be enqueued
+///
+/// Properties which are not set have the defaults of Oracle. The payload is
+/// a [`Payload`] for [`Queue`] and `T` for [`JsonQueue`].
+#[derive(Clone, Debug)]
+pub struct MsgProps<P = Payload> {
+    payload: P,
+    priority: Option<i32>,
+    delay: Option<i32>,
+    expiration: Option<i32>,
+    correlation: Option<String>,
+    exception_queue: Option<String>,
+    original_msg_id: Option<Vec<u8>>,
+    recipients: Vec<String>,
+}
+
+impl MsgProps {
+    pub fn new<P: Into<Payload>>(payload: P) -> MsgProps {
+        MsgProps::with_payload(payload.into())
+    }
+}
+
+impl<P> MsgProps<P> {
+    /// Creates message properties with a payload of any type, such as
+    /// payloads of [`JsonQueue`].
+    pub fn with_payload(payload: P) -> MsgProps<P> {
+        MsgProps {
+            payload,
+            priority: None,
+            delay: None,
+            expiration: None,
+            correlation: None,
+            exception_queue: None,
+            original_msg_id: None,
+            recipients: Vec::new(),
+        }
+    }
+
+    pub fn payload(&self) -> &P {
+        &self.payload
+    }
+
+    /// Sets the priority. Smaller numbers have higher priority. The default
+    /// is 0.
+    pub fn priority(mut self, priority: i32) -> MsgProps<P> {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Sets the number of seconds before the message is available for
+    /// dequeue. The default is 0.
+    pub fn delay(mut self, seconds: i32) -> MsgProps<P> {
+        self.delay = Some(seconds);
+        self
+    }
+
+    /// Sets the number of seconds for which the message is available for
+    /// dequeue after the delay. It is moved to the exception queue after
+    /// that. The default is -1, which means no expiration.
+    pub fn expiration(mut self, seconds: i32) -> MsgProps<P> {
+        self.expiration = Some(seconds);
+        self
+    }
+
+    pub fn correlation(mut self, correlation: &str) -> MsgProps<P> {
+        self.correlation = Some(correlation.into());
+        self
+    }
+
+    /// Sets the queue to which the message is moved when it expires or
+    /// fails to be dequeued too many times.
+    pub fn exception_queue(mut self, name: &str) -> MsgProps<P> {
+        self.exception_queue = Some(name.into());
+        self
+    }
+
+    pub fn original_msg_id(mut self, msg_id: &[u8]) -> MsgProps<P> {
+        self.original_msg_id = Some(msg_id.into());
+        self
+    }
+
+    /// Sets recipients overriding subscribers of the queue.
+    pub fn recipients<I, S>(mut self, names: I) -> MsgProps<P>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.recipients = names.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Dequeued message
+///
+/// The payload is a [`Payload`] for [`Queue`] and `Result<T>` for
+/// [`JsonQueue`].
+#[derive(Clone, Debug)]
+pub struct Message<P = Payload> {
+    pub msg_id: Vec<u8>,
+    pub original_msg_id: Vec<u8>,
+    pub correlation: String,
+    pub exception_queue: String,
+    pub priority: i32,
+    pub delay: i32,
+    pub expiration: i32,
+    /// number of attempts to dequeue the message
+    pub num_attempts: i32,
+    pub enq_time: Timestamp,
+    pub state: MessageState,
+    pub delivery_mode: DeliveryMode,
+    pub payload: P,
+}
+
+/// Owned reference to [`dpiMsgProps`]
+struct PropsHandle {
+    ctxt: &'static Context,
+    handle: *mut dpiMsgProps,
+}
+
+impl PropsHandle {
+    fn new<P>(conn: &Connection, props: &MsgProps<P>) -> Result<PropsHandle> {
+        let ctxt = conn.ctxt();
+        let mut handle = ptr::null_mut();
+        chkerr!(ctxt, dpiConn_newMsgProps(conn.handle(), &mut handle));
+        let h = PropsHandle { ctxt, handle };
+        if let Some(priority) = props.priority {
+            chkerr!(ctxt, dpiMsgProps_setPriority(handle, priority));
+        }
+        if let Some(delay) = props.delay {
+            chkerr!(ctxt, dpiMsgProps_setDelay(handle, delay));
+        }
+        if let Some(expiration) = props.expiration {
+            chkerr!(ctxt, dpiMsgProps_setExpiration(handle, expiration));
+        }
+        if let Some(correlation) = &props.correlation {
+            set_str(ctxt, correlation.as_bytes(), |p, l| unsafe {
+                dpiMsgProps_setCorrelation(handle, p, l)
+            })?;
+        }
+        if let Some(name) = &props.exception_queue {
+            set_str(ctxt, name.as_bytes(), |p, l| unsafe {
+                dpiMsgProps_setExceptionQ(handle, p, l)
+            })?;
+        }
+        if let Some(msg_id) = &props.original_msg_id {
+            set_str(ctxt, msg_id, |p, l| unsafe {
+                dpiMsgProps_setOriginalMsgId(handle, p, l)
+            })?;
+        }
+        if !props.recipients.is_empty() {
+            let mut recipients: Vec<_> = props
+                .recipients
+                .iter()
+                .map(|name| dpiMsgRecipient {
+                    name: name.as_ptr() as *const c_char,
+                    nameLength: name.len() as u32,
+                })
+                .collect();
+            chkerr!(
+                ctxt,
+                dpiMsgProps_setRecipients(handle, recipients.as_mut_ptr(), recipients.len() as u32)
+            );
+        }
+        Ok(h)
+    }
+
+    fn bytes<F>(&self, get: F) -> Result<Vec<u8>>
+    where
+        F: FnOnce(*mut dpiMsgProps, *mut *const c_char, *mut u32) -> i32,
+    {
+        let mut ptr = ptr::null();
+        let mut len = 0;
+        chkerr!(self.ctxt, get(self.handle, &mut ptr, &mut len));
+        Ok(to_bytes(ptr, len).to_vec())
+    }
+
+    fn string<F>(&self, get: F) -> Result<String>
+    where
+        F: FnOnce(*mut dpiMsgProps, *mut *const c_char, *mut u32) -> i32,
+    {
+        Ok(String::from_utf8_lossy(&self.bytes(get)?).into_owned())
+    }
+
+    fn i32<F>(&self, get: F) -> Result<i32>
+    where
+        F: FnOnce(*mut dpiMsgProps, *mut i32) -> i32,
+    {
+        let mut value = 0;
+        chkerr!(self.ctxt, get(self.handle, &mut value));
+        Ok(value)
+    }
+
+    fn msg_id(&self) -> Result<Vec<u8>> {
+        self.bytes(|h, p, l| unsafe { dpiMsgProps_getMsgId(h, p, l) })
+    }
+
+    fn to_message<P>(&self, payload: P) -> Result<Message<P>> {
+        let mut enq_time = dpiTimestamp::default();
+        chkerr!(
+            self.ctxt,
+            dpiMsgProps_getEnqTime(self.handle, &mut enq_time)
+        );
+        let mut state = 0;
+        chkerr!(self.ctxt, dpiMsgProps_getState(self.handle, &mut state));
+        let mut delivery_mode = 0;
+        chkerr!(
+            self.ctxt,
+            dpiMsgProps_getDeliveryMode(self.handle, &mut delivery_mode)
+        );
+        Ok(Message {
+            msg_id: self.msg_id()?,
+            original_msg_id: self
+                .bytes(|h, p, l| unsafe { dpiMsgProps_getOriginalMsgId(h, p, l) })?,
+            correlation: self.string(|h, p, l| unsafe { dpiMsgProps_getCorrelation(h, p, l) })?,
+            exception_queue: self
+                .string(|h, p, l| unsafe { dpiMsgProps_getExceptionQ(h, p, l) })?,
+            priority: self.i32(|h, v| unsafe { dpiMsgProps_getPriority(h, v) })?,
+            delay: self.i32(|h, v| unsafe { dpiMsgProps_getDelay(h, v) })?,
+            expiration: self.i32(|h, v| unsafe { dpiMsgProps_getExpiration(h, v) })?,
+            num_attempts: self.i32(|h, v| unsafe { dpiMsgProps_getNumAttempts(h, v) })?,
+            enq_time: enq_time.into(),
+            state: MessageState::from_dpi(state)?,
+            delivery_mode: DeliveryMode::from_dpi(delivery_mode)?,
+            payload,
+        })
+    }
+}
+
+impl Drop for PropsHandle {
+    fn drop(&mut self) {
+        unsafe { dpiMsgProps_release(self.handle) };
+    }
+}
+
+fn to_bytes<'a>(ptr: *const c_char, len: u32) -> &'a [u8] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(ptr as *const u8, len as usize) }
+    }
+}
+
+fn set_str<F>(ctxt: &Context, value: &[u8], set: F) -> Result<()>
+where
+    F: FnOnce(*const c_char, u32) -> i32,
+{
+    chkerr!(
+        ctxt,
+        set(value.as_ptr() as *const c_char, value.len() as u32)
+    );
+    Ok(())
+}
+
+// Kind of payloads, which decides how they are set and got
+#[derive(Debug)]
+enum PayloadKind {
+    Raw,
+    Object(ObjectType),
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    Json,
+}
+
+/// Queue of Oracle Advanced Queuing
+///
+/// This holds a reference to [`dpiQueue`], which is released when it is
+/// dropped. Payloads are RAW or objects of the payload type.
+#[derive(Debug)]
+pub struct Queue<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiQueue,
+    kind: PayloadKind,
+}
+
+impl<'conn> Queue<'conn> {
+    /// Creates a queue whose payloads are RAW when `payload_type` is `None`
+    /// or objects of `payload_type` otherwise.
+    pub fn new(
+        conn: &'conn Connection,
+        name: &str,
+        payload_type: Option<&ObjectType>,
+    ) -> Result<Queue<'conn>> {
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            conn.ctxt(),
+            dpiConn_newQueue(
+                conn.handle(),
+                name.as_ptr() as *const c_char,
+                name.len() as u32,
+                payload_type.map_or(ptr::null_mut(), ObjectType::handle),
+                &mut handle
+            )
+        );
+        let kind = match payload_type {
+            Some(objtype) => PayloadKind::Object(objtype.clone()),
+            None => PayloadKind::Raw,
+        };
+        Ok(Queue { conn, handle, kind })
+    }
+
+    pub fn handle(&self) -> *mut dpiQueue {
+        self.handle
+    }
+
+    pub fn connection(&self) -> &'conn Connection {
+        self.conn
+    }
+
+    /// Returns the payload type, which is `None` for RAW queues.
+    pub fn payload_type(&self) -> Option<&ObjectType> {
+        match &self.kind {
+            PayloadKind::Object(objtype) => Some(objtype),
+            _ => None,
+        }
+    }
+
+    /// Changes the enqueue options of the queue.
+    pub fn set_enq_options(&self, options: &EnqOptions) -> Result<()> {
+        let ctxt = self.conn.ctxt();
+        let mut handle = ptr::null_mut();
+        // The reference is owned by the queue.
+        chkerr!(ctxt, dpiQueue_getEnqOptions(self.handle, &mut handle));
+        if let Some(visibility) = options.visibility {
+            chkerr!(
+                ctxt,
+                dpiEnqOptions_setVisibility(handle, visibility.to_dpi())
+            );
+        }
+        if let Some(mode) = options.delivery_mode {
+            chkerr!(ctxt, dpiEnqOptions_setDeliveryMode(handle, mode.to_dpi()));
+        }
+        if let Some(name) = &options.transformation {
+            set_str(ctxt, name.as_bytes(), |p, l| unsafe {
+                dpiEnqOptions_setTransformation(handle, p, l)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Changes the dequeue options of the queue.
+    pub fn set_deq_options(&self, options: &DeqOptions) -> Result<()> {
+        let ctxt = self.conn.ctxt();
+        let mut handle = ptr::null_mut();
+        // The reference is owned by the queue.
+        chkerr!(ctxt, dpiQueue_getDeqOptions(self.handle, &mut handle));
+        if let Some(mode) = options.mode {
+            chkerr!(ctxt, dpiDeqOptions_setMode(handle, mode.to_dpi()));
+        }
+        if let Some(navigation) = options.navigation {
+            chkerr!(
+                ctxt,
+                dpiDeqOptions_setNavigation(handle, navigation.to_dpi())
+            );
+        }
+        if let Some(visibility) = options.visibility {
+            chkerr!(
+                ctxt,
+                dpiDeqOptions_setVisibility(handle, visibility.to_dpi())
+            );
+        }
+        if let Some(wait) = options.wait {
+            chkerr!(ctxt, dpiDeqOptions_setWait(handle, wait.to_dpi()));
+        }
+        if let Some(mode) = options.delivery_mode {
+            chkerr!(ctxt, dpiDeqOptions_setDeliveryMode(handle, mode.to_dpi()));
+        }
+        let strings = [
+            (&options.condition, dpiDeqOptions_setCondition as SetStr),
+            (&options.consumer_name, dpiDeqOptions_setConsumerName),
+            (&options.correlation, dpiDeqOptions_setCorrelation),
+            (&options.transformation, dpiDeqOptions_setTransformation),
+        ];
+        for (value, set) in strings {
+            if let Some(value) = value {
+                set_str(ctxt, value.as_bytes(), |p, l| unsafe { set(handle, p, l) })?;
+            }
+        }
+        if let Some(msg_id) = &options.msg_id {
+            set_str(ctxt, msg_id, |p, l| unsafe {
+                dpiDeqOptions_setMsgId(handle, p, l)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn set_payload(&self, h: &PropsHandle, payload: &Payload) -> Result<()> {
+        let ctxt = self.conn.ctxt();
+        match (payload, &self.kind) {
+            (Payload::Raw(bytes), PayloadKind::Raw) => set_str(ctxt, bytes, |p, l| unsafe {
+                dpiMsgProps_setPayloadBytes(h.handle, p, l)
+            }),
+            (Payload::Object(obj), PayloadKind::Object(objtype))
+                if obj.object_type().full_name() == objtype.full_name() =>
+            {
+                chkerr!(ctxt, dpiMsgProps_setPayloadObject(h.handle, obj.handle()));
+                Ok(())
+            }
+            _ => Err(Error::InvalidOperation(format!(
+                "payload doesn't match the payload type {}",
+                self.payload_type()
+                    .map_or("RAW".into(), ObjectType::full_name)
+            ))),
+        }
+    }
+
+    fn get_payload(&self, h: &PropsHandle) -> Result<Payload> {
+        let mut obj = ptr::null_mut();
+        let mut ptr = ptr::null();
+        let mut len = 0;
+        chkerr!(
+            h.ctxt,
+            dpiMsgProps_getPayload(h.handle, &mut obj, &mut ptr, &mut len)
+        );
+        match (obj.is_null(), &self.kind) {
+            (true, _) => Ok(Payload::Raw(to_bytes(ptr, len).to_vec())),
+            (false, PayloadKind::Object(objtype)) => {
+                Ok(Payload::Object(unsafe { Object::from_raw(objtype, obj)? }))
+            }
+            (false, _) => Err(Error::InvalidOperation(
+                "object payload is dequeued from a RAW queue".into(),
+            )),
+        }
+    }
+
+    fn enqueue_with<P, F>(&self, props: &MsgProps<P>, set_payload: F) -> Result<Vec<u8>>
+    where
+        F: Fn(&PropsHandle, &P) -> Result<()>,
+    {
+        let h = PropsHandle::new(self.conn, props)?;
+        set_payload(&h, &props.payload)?;
+        chkerr!(self.conn.ctxt(), dpiQueue_enqOne(self.handle, h.handle));
+        h.msg_id()
+    }
+
+    fn enqueue_many_with<P, F>(&self, props: &[MsgProps<P>], set_payload: F) -> Result<Vec<Vec<u8>>>
+    where
+        F: Fn(&PropsHandle, &P) -> Result<()>,
+    {
+        let handles = props
+            .iter()
+            .map(|props| {
+                let h = PropsHandle::new(self.conn, props)?;
+                set_payload(&h, &props.payload)?;
+                Ok(h)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut raw_handles: Vec<_> = handles.iter().map(|h| h.handle).collect();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiQueue_enqMany(
+                self.handle,
+                raw_handles.len() as u32,
+                raw_handles.as_mut_ptr()
+            )
+        );
+        handles.iter().map(PropsHandle::msg_id).collect()
+    }
+
+    fn dequeue_with<P, F>(&self, get_payload: F) -> Result<Option<Message<P>>>
+    where
+        F: Fn(&PropsHandle) -> Result<P>,
+    {
+        let ctxt = self.conn.ctxt();
+        let mut handle = ptr::null_mut();
+        chkerr!(ctxt, dpiQueue_deqOne(self.handle, &mut handle));
+        if handle.is_null() {
+            return Ok(None);
+        }
+        let h = PropsHandle { ctxt, handle };
+        h.to_message(get_payload(&h)?).map(Some)
+    }
+
+    fn dequeue_many_with<P, F>(&self, max_messages: u32, get_payload: F) -> Result<Vec<Message<P>>>
+    where
+        F: Fn(&PropsHandle) -> Result<P>,
+    {
+        let ctxt = self.conn.ctxt();
+        let mut raw_handles = vec![ptr::null_mut(); max_messages as usize];
+        let mut num = max_messages;
+        chkerr!(
+            ctxt,
+            dpiQueue_deqMany(self.handle, &mut num, raw_handles.as_mut_ptr())
+        );
+        let handles: Vec<_> = raw_handles[..num as usize]
+            .iter()
+            .map(|&handle| PropsHandle { ctxt, handle })
+            .collect();
+        handles
+            .iter()
+            .map(|h| h.to_message(get_payload(h)?))
+            .collect()
+    }
+
+    /// Enqueues a message and returns its message id.
+    pub fn enqueue(&self, props: &MsgProps) -> Result<Vec<u8>> {
+        self.enqueue_with(props, |h, payload| self.set_payload(h, payload))
+    }
+
+    /// Enqueues messages in a round-trip and returns their message ids.
+    pub fn enqueue_many(&self, props: &[MsgProps]) -> Result<Vec<Vec<u8>>> {
+        self.enqueue_many_with(props, |h, payload| self.set_payload(h, payload))
+    }
+
+    /// Dequeues a message. It returns `None` when no messages are available
+    /// within the wait time of the dequeue options.
+    pub fn dequeue(&self) -> Result<Option<Message>> {
+        self.dequeue_with(|h| self.get_payload(h))
+    }
+
+    /// Dequeues up to `max_messages` messages in a round-trip.
+    pub fn dequeue_many(&self, max_messages: u32) -> Result<Vec<Message>> {
+        self.dequeue_many_with(max_messages, |h| self.get_payload(h))
+    }
+}
+
+type SetStr = unsafe extern "C" fn(*mut dpiDeqOptions, *const c_char, u32) -> i32;
+
+impl Drop for Queue<'_> {
+    fn drop(&mut self) {
+        unsafe { dpiQueue_release(self.handle) };
+    }
+}
+
+impl Connection {
+    /// Creates a queue whose payloads are RAW.
+    pub fn queue(&self, name: &str) -> Result<Queue<'_>> {
+        Queue::new(self, name, None)
+    }
+
+    /// Creates a queue whose payloads are objects of `payload_type`.
+    pub fn object_queue(&self, name: &str, payload_type: &ObjectType) -> Result<Queue<'_>> {
+        Queue::new(self, name, Some(payload_type))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod json_queue {
+    use super::{
+        DeqOptions, Dequeue, EnqOptions, Message, MsgProps, PayloadKind, PropsHandle, Queue,
+    };
+    use crate::ffi::*;
+    use crate::{Connection, Error, JsonValue, Result};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::marker::PhantomData;
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    /// Queue whose payloads are JSON
+    ///
+    /// Payloads of type `T` are serialized to JSON text on enqueue and
+    /// deserialized from JSON values on dequeue. Oracle-specific scalars
+    /// are deserialized as [`JsonValue`] serializes them. Payloads of
+    /// dequeued messages are errors when they cannot be deserialized. Such
+    /// messages are dequeued all the same and removed by the commit as
+    /// others are.
+    ///
+    /// ```no_run
+    /// # use odpic::Connection;
+    /// # use odpic::aq::{JsonQueue, MsgProps};
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Serialize, Deserialize)]
+    /// struct OrderPlaced {
+    ///     order_id: u64,
+    ///     amount: f64,
+    /// }
+    ///
+    /// # fn f(conn: &Connection) -> odpic::Result<()> {
+    /// let queue = JsonQueue::<OrderPlaced>::new(conn, "ORDER_EVENTS")?;
+    /// queue.enqueue(&MsgProps::with_payload(OrderPlaced { order_id: 1, amount: 9.5 }))?;
+    /// conn.commit()?;
+    ///
+    /// if let Some(msg) = queue.dequeue()? {
+    ///     match msg.payload {
+    ///         Ok(order) => println!("{} (attempts: {})", order.order_id, msg.num_attempts),
+    ///         Err(err) => eprintln!("invalid message {:?}: {}", msg.msg_id, err),
+    ///     }
+    /// }
+    /// conn.commit()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[derive(Debug)]
+    pub struct JsonQueue<'conn, T> {
+        queue: Queue<'conn>,
+        phantom: PhantomData<fn(T) -> T>,
+    }
+
+    impl<'conn, T> JsonQueue<'conn, T>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        pub fn new(conn: &'conn Connection, name: &str) -> Result<JsonQueue<'conn, T>> {
+            let mut handle = ptr::null_mut();
+            chkerr!(
+                conn.ctxt(),
+                dpiConn_newJsonQueue(
+                    conn.handle(),
+                    name.as_ptr() as *const c_char,
+                    name.len() as u32,
+                    &mut handle
+                )
+            );
+            Ok(JsonQueue {
+                queue: Queue {
+                    conn,
+                    handle,
+                    kind: PayloadKind::Json,
+                },
+                phantom: PhantomData,
+            })
+        }
+
+        pub fn handle(&self) -> *mut dpiQueue {
+            self.queue.handle
+        }
+
+        pub fn connection(&self) -> &'conn Connection {
+            self.queue.conn
+        }
+
+        /// Changes the enqueue options of the queue.
+        pub fn set_enq_options(&self, options: &EnqOptions) -> Result<()> {
+            self.queue.set_enq_options(options)
+        }
+
+        /// Changes the dequeue options of the queue.
+        pub fn set_deq_options(&self, options: &DeqOptions) -> Result<()> {
+            self.queue.set_deq_options(options)
+        }
+
+        /// Enqueues a message and returns its message id.
+        pub fn enqueue(&self, props: &MsgProps<T>) -> Result<Vec<u8>> {
+            self.queue
+                .enqueue_with(props, |h, payload| self.set_payload(h, payload))
+        }
+
+        /// Enqueues messages in a round-trip and returns their message ids.
+        pub fn enqueue_many(&self, props: &[MsgProps<T>]) -> Result<Vec<Vec<u8>>> {
+            self.queue
+                .enqueue_many_with(props, |h, payload| self.set_payload(h, payload))
+        }
+
+        /// Dequeues a message. It returns `None` when no messages are
+        /// available within the wait time of the dequeue options.
+        pub fn dequeue(&self) -> Result<Option<Message<Result<T>>>> {
+            self.queue.dequeue_with(|h| Ok(get_payload(h)))
+        }
+
+        /// Dequeues up to `max_messages` messages in a round-trip.
+        pub fn dequeue_many(&self, max_messages: u32) -> Result<Vec<Message<Result<T>>>> {
+            self.queue
+                .dequeue_many_with(max_messages, |h| Ok(get_payload(h)))
+        }
+
+        fn set_payload(&self, h: &PropsHandle, payload: &T) -> Result<()> {
+            let text = serde_json::to_vec(payload).map_err(|err| {
+                Error::InvalidConversion(format!("failed to serialize a payload: {}", err))
+            })?;
+            let ctxt = self.queue.conn.ctxt();
+            let mut json = ptr::null_mut();
+            chkerr!(ctxt, dpiConn_newJson(self.queue.conn.handle(), &mut json));
+            // The message properties hold their own reference.
+            let res = (|| {
+                chkerr!(
+                    ctxt,
+                    dpiJson_setFromText(json, text.as_ptr() as *const c_char, text.len() as u64, 0)
+                );
+                chkerr!(ctxt, dpiMsgProps_setPayloadJson(h.handle, json));
+                Ok(())
+            })();
+            unsafe { dpiJson_release(json) };
+            res
+        }
+    }
+
+    impl<T> Dequeue for JsonQueue<'_, T>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        type Payload = Result<T>;
+
+        fn commit(&self) -> Result<()> {
+            self.queue.conn.commit()
+        }
+
+        fn set_deq_options(&self, options: &DeqOptions) -> Result<()> {
+            JsonQueue::set_deq_options(self, options)
+        }
+
+        fn dequeue_many(&self, max_messages: u32) -> Result<Vec<Message<Result<T>>>> {
+            JsonQueue::dequeue_many(self, max_messages)
+        }
+    }
+
+    fn get_payload<T: DeserializeOwned>(h: &PropsHandle) -> Result<T> {
+        let mut json = ptr::null_mut();
+        // The reference is owned by the message properties.
+        chkerr!(h.ctxt, dpiMsgProps_getPayloadJson(h.handle, &mut json));
+        if json.is_null() {
+            return Err(Error::NullValue);
+        }
+        let mut node = ptr::null_mut();
+        chkerr!(
+            h.ctxt,
+            dpiJson_getValue(json, DPI_JSON_OPT_NUMBER_AS_STRING, &mut node)
+        );
+        from_json_value(unsafe { &JsonValue::from_dpi_node(&*node)? })
+    }
+
+    // Deserializes a payload via serde_json::Value, in which NUMBERs out of
+    // the range of i64 and u64 are f64.
+    fn from_json_value<T: DeserializeOwned>(value: &JsonValue) -> Result<T> {
+        let value = serde_json::to_value(value).map_err(|err| {
+            Error::InvalidConversion(format!("failed to convert a payload: {}", err))
+        })?;
+        serde_json::from_value(value).map_err(|err| {
+            Error::InvalidConversion(format!("failed to deserialize a payload: {}", err))
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::vector::BinaryVector;
+        use crate::Timestamp;
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Order {
+            id: u64,
+            delta: i64,
+            total: f64,
+            at: String,
+            tags: Vec<String>,
+        }
+
+        fn order(id: &str, delta: &str, total: &str) -> JsonValue {
+            JsonValue::Object(vec![
+                ("id".into(), JsonValue::Number(id.into())),
+                ("delta".into(), JsonValue::Number(delta.into())),
+                ("total".into(), JsonValue::Number(total.into())),
+                (
+                    "at".into(),
+                    JsonValue::Timestamp(
+                        Timestamp::new(2024, 1, 2, 3, 4, 5).and_nanosecond(500000000),
+                    ),
+                ),
+                ("tags".into(), vec![JsonValue::from("a")].into()),
+            ])
+        }
+
+        #[test]
+        fn deserialize_payloads() {
+            let value = order(
+                "18446744073709551615",
+                "-9223372036854775808",
+                "123456789012345678901234567890",
+            );
+            assert_eq!(
+                from_json_value::<Order>(&value).unwrap(),
+                Order {
+                    id: u64::MAX,
+                    delta: i64::MIN,
+                    total: 1.2345678901234568e29,
+                    at: "2024-01-02T03:04:05.500000000".into(),
+                    tags: vec!["a".into()],
+                }
+            );
+        }
+
+        #[test]
+        fn deserialization_errors() {
+            for value in [
+                // out of the range of u64
+                order("18446744073709551616", "0", "0"),
+                // not an integer
+                order("1.5", "0", "0"),
+                order("1", "0", "0").get("tags").unwrap().clone(),
+            ] {
+                assert!(matches!(
+                    from_json_value::<Order>(&value),
+                    Err(Error::InvalidConversion(msg)) if msg.starts_with("failed to deserialize")
+                ));
+            }
+            assert!(matches!(
+                from_json_value::<Order>(&JsonValue::Vector(BinaryVector::new(vec![1]).into())),
+                Err(Error::InvalidConversion(msg)) if msg.starts_with("failed to convert")
+            ));
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use json_queue::JsonQueue;
+
+/// Queues from which [`Consumer`] dequeues messages
+pub trait Dequeue {
+    /// type of payloads of dequeued messages
+    type Payload;
+
+    /// Commits the transaction in which messages were dequeued.
+    fn commit(&self) -> Result<()>;
+
+    fn set_deq_options(&self, options: &DeqOptions) -> Result<()>;
+
+    fn dequeue_many(&self, max_messages: u32) -> Result<Vec<Message<Self::Payload>>>;
+}
+
+impl Dequeue for Queue<'_> {
+    type Payload = Payload;
+
+    fn commit(&self) -> Result<()> {
+        self.conn.commit()
+    }
+
+    fn set_deq_options(&self, options: &DeqOptions) -> Result<()> {
+        Queue::set_deq_options(self, options)
+    }
+
+    fn dequeue_many(&self, max_messages: u32) -> Result<Vec<Message>> {
+        Queue::dequeue_many(self, max_messages)
+    }
+}
+
+/// When [`Consumer`] commits dequeued messages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitMode {
+    /// Messages are dequeued in batches and each batch is committed when
+    /// the next batch is requested. This is the default.
+    PerBatch,
+    /// Messages are dequeued one by one and each message is committed when
+    /// the next message is requested. Batches contain one message.
+    PerMessage,
+    /// The consumer never commits.
+    Manual,
+}
+
+/// Handle to stop [`Consumer`] from another thread
+#[derive(Clone, Debug, Default)]
+pub struct StopHandle(Arc<AtomicBool>);
+
+impl StopHandle {
+    /// Requests the consumer to stop. It stops before its next dequeue.
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+type RetryHook<'a, P> = Box<dyn FnMut(Message<P>) -> Result<()> + 'a>;
+
+/// Blocking consumer iterating over batches of dequeued messages
+///
+/// Each call of [`Iterator::next`] commits the previous batch as configured
+/// by [`CommitMode`] and dequeues the next one with the configured wait.
+/// The iteration ends when the queue is empty with [`Wait::NoWait`] or when
+/// the consumer is stopped by its [`StopHandle`]. The last batch is
+/// committed at the end of the iteration but not when the consumer is
+/// dropped before that. Errors end the iteration.
+///
+/// Messages dequeued more than the retry threshold, usually because
+/// transactions processing them were rolled back, are passed to the hook
+/// set by [`Consumer::on_retry_exceeded`] instead of batches and committed
+/// with them. Oracle moves such messages to the exception queue only after
+/// the `max_retries` of the queue.
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # use odpic::aq::{Consumer, Wait};
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let queue = conn.queue("RAW_QUEUE")?;
+/// let mut consumer = Consumer::new(&queue)
+///     .batch_size(100)
+///     .wait(Wait::Seconds(5))
+///     .on_retry_exceeded(3, |msg| {
+///         eprintln!("giving up {:?}", msg.msg_id);
+///         Ok(())
+///     });
+/// let stop = consumer.stop_handle();
+/// ctrlc_like_handler(move || stop.stop());
+/// for batch in &mut consumer {
+///     for msg in batch? {
+///         println!("{:?}", msg.payload);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// # fn ctrlc_like_handler<F: FnOnce() + Send + 'static>(_f: F) {}
+/// ```
+pub struct Consumer<'a, Q: Dequeue> {
+    queue: &'a Q,
+    batch_size: u32,
+    wait: Wait,
+    commit_mode: CommitMode,
+    max_attempts: Option<i32>,
+    retry_hook: Option<RetryHook<'a, Q::Payload>>,
+    stop: StopHandle,
+    started: bool,
+    pending: bool,
+    done: bool,
+}
+
+impl<'a, Q: Dequeue> Consumer<'a, Q> {
+    pub fn new(queue: &'a Q) -> Consumer<'a, Q> {
+        Consumer {
+            queue,
+            batch_size: 100,
+            wait: Wait::Forever,
+            commit_mode: CommitMode::PerBatch,
+            max_attempts: None,
+            retry_hook: None,
+            stop: StopHandle::default(),
+            started: false,
+            pending: false,
+            done: false,
+        }
+    }
+
+    /// Sets the maximum number of messages in a batch. The default is 100.
+    pub fn batch_size(mut self, size: u32) -> Consumer<'a, Q> {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// Sets the time to wait for messages. The default is
+    /// [`Wait::Forever`], with which the consumer cannot stop until a
+    /// message arrives. Use [`Wait::Seconds`] to check the stop handle
+    /// periodically.
+    pub fn wait(mut self, wait: Wait) -> Consumer<'a, Q> {
+        self.wait = wait;
+        self
+    }
+
+    pub fn commit_mode(mut self, mode: CommitMode) -> Consumer<'a, Q> {
+        self.commit_mode = mode;
+        self
+    }
+
+    /// Sets a hook called with messages whose number of attempts exceeds
+    /// `max_attempts`. An error returned by the hook ends the iteration
+    /// without committing.
+    pub fn on_retry_exceeded<F>(mut self, max_attempts: i32, hook: F) -> Consumer<'a, Q>
+    where
+        F: FnMut(Message<Q::Payload>) -> Result<()> + 'a,
+    {
+        self.max_attempts = Some(max_attempts);
+        self.retry_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Returns a handle to stop the consumer.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    /// Commits the current batch unless the commit mode is
+    /// [`CommitMode::Manual`]. Call this when breaking out of the
+    /// iteration early.
+    pub fn commit(&mut self) -> Result<()> {
+        if self.pending && self.commit_mode != CommitMode::Manual {
+            self.queue.commit()?;
+        }
+        self.pending = false;
+        Ok(())
+    }
+
+    fn next_batch(&mut self) -> Result<Option<Vec<Message<Q::Payload>>>> {
+        if !self.started {
+            self.queue
+                .set_deq_options(&DeqOptions::new().wait(self.wait))?;
+            self.started = true;
+        }
+        let batch_size = match self.commit_mode {
+            CommitMode::PerMessage => 1,
+            _ => self.batch_size,
+        };
+        loop {
+            self.commit()?;
+            if self.stop.is_stopped() {
+                return Ok(None);
+            }
+            let messages = self.queue.dequeue_many(batch_size)?;
+            if messages.is_empty() {
+                if self.wait == Wait::NoWait {
+                    return Ok(None);
+                }
+                continue;
+            }
+            self.pending = true;
+            let mut batch = Vec::with_capacity(messages.len());
+            for msg in messages {
+                match (&mut self.retry_hook, self.max_attempts) {
+                    (Some(hook), Some(max)) if msg.num_attempts > max => hook(msg)?,
+                    _ => batch.push(msg),
+                }
+            }
+            if !batch.is_empty() {
+                return Ok(Some(batch));
+            }
+        }
+    }
+}
+
+impl<Q: Dequeue> Iterator for Consumer<'_, Q> {
+    type Item = Result<Vec<Message<Q::Payload>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let res = self.next_batch().transpose();
+        if !matches!(res, Some(Ok(_))) {
+            self.done = true;
+        }
+        res
+    }
+}
+
+impl<Q: Dequeue> fmt::Debug for Consumer<'_, Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("batch_size", &self.batch_size)
+            .field("wait", &self.wait)
+            .field("commit_mode", &self.commit_mode)
+            .field("max_attempts", &self.max_attempts)
+            .field("stop", &self.stop)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deq_options() {
+        assert_eq!(
+            DeqOptions::new(),
+            DeqOptions {
+                mode: None,
+                navigation: None,
+                visibility: None,
+                wait: None,
+                condition: None,
+                consumer_name: None,
+                correlation: None,
+                msg_id: None,
+                transformation: None,
+                delivery_mode: None,
+            }
+        );
+        let options = DeqOptions::new()
+            .mode(DeqMode::Browse)
+            .mode(DeqMode::Locked)
+            .navigation(DeqNavigation::FirstMsg)
+            .visibility(Visibility::Immediate)
+            .wait(Wait::Seconds(10))
+            .condition("priority > 1")
+            .consumer_name("C1")
+            .correlation("ORD%")
+            .msg_id(&[1, 2])
+            .transformation("T")
+            .delivery_mode(DeliveryMode::Buffered);
+        assert_eq!(
+            options,
+            DeqOptions {
+                mode: Some(DeqMode::Locked),
+                navigation: Some(DeqNavigation::FirstMsg),
+                visibility: Some(Visibility::Immediate),
+                wait: Some(Wait::Seconds(10)),
+                condition: Some("priority > 1".into()),
+                consumer_name: Some("C1".into()),
+                correlation: Some("ORD%".into()),
+                msg_id: Some(vec![1, 2]),
+                transformation: Some("T".into()),
+                delivery_mode: Some(DeliveryMode::Buffered),
+            }
+        );
+        // Empty values are kept to clear options.
+        assert_eq!(DeqOptions::new().condition("").condition, Some("".into()));
+    }
+
+    #[test]
+    fn enq_options() {
+        assert_eq!(
+            EnqOptions::new(),
+            EnqOptions {
+                visibility: None,
+                delivery_mode: None,
+                transformation: None,
+            }
+        );
+        assert_eq!(
+            EnqOptions::new()
+                .visibility(Visibility::OnCommit)
+                .delivery_mode(DeliveryMode::Persistent)
+                .transformation("T"),
+            EnqOptions {
+                visibility: Some(Visibility::OnCommit),
+                delivery_mode: Some(DeliveryMode::Persistent),
+                transformation: Some("T".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn msg_props() {
+        let props = MsgProps::with_payload(7u32);
+        assert_eq!(*props.payload(), 7);
+        assert_eq!(props.priority, None);
+        assert_eq!(props.delay, None);
+        assert_eq!(props.expiration, None);
+        assert_eq!(props.correlation, None);
+        assert_eq!(props.exception_queue, None);
+        assert_eq!(props.original_msg_id, None);
+        assert!(props.recipients.is_empty());
+
+        let props = props
+            .priority(-1)
+            .delay(5)
+            .expiration(60)
+            .correlation("ORD1")
+            .exception_queue("EXC_Q")
+            .original_msg_id(&[3])
+            .recipients(["A", "B"])
+            .recipients(vec![String::from("C")]);
+        assert_eq!(props.priority, Some(-1));
+        assert_eq!(props.delay, Some(5));
+        assert_eq!(props.expiration, Some(60));
+        assert_eq!(props.correlation.as_deref(), Some("ORD1"));
+        assert_eq!(props.exception_queue.as_deref(), Some("EXC_Q"));
+        assert_eq!(props.original_msg_id, Some(vec![3]));
+        assert_eq!(props.recipients, ["C"]);
+    }
+
+    #[test]
+    fn wait_to_dpi() {
+        assert_eq!(Wait::NoWait.to_dpi(), DPI_DEQ_WAIT_NO_WAIT);
+        assert_eq!(Wait::Forever.to_dpi(), u32::MAX);
+        assert_eq!(Wait::Seconds(0).to_dpi(), 0);
+        assert_eq!(Wait::Seconds(30).to_dpi(), 30);
+        // Seconds never mean forever.
+        assert_eq!(Wait::Seconds(u32::MAX).to_dpi(), u32::MAX - 1);
+    }
+
+    #[test]
+    fn enum_mappings() {
+        assert_eq!(DeqMode::Browse.to_dpi(), DPI_MODE_DEQ_BROWSE);
+        assert_eq!(DeqMode::Locked.to_dpi(), DPI_MODE_DEQ_LOCKED);
+        assert_eq!(DeqMode::Remove.to_dpi(), DPI_MODE_DEQ_REMOVE);
+        assert_eq!(DeqMode::RemoveNoData.to_dpi(), DPI_MODE_DEQ_REMOVE_NO_DATA);
+        assert_eq!(DeqNavigation::FirstMsg.to_dpi(), DPI_DEQ_NAV_FIRST_MSG);
+        assert_eq!(
+            DeqNavigation::NextTransaction.to_dpi(),
+            DPI_DEQ_NAV_NEXT_TRANSACTION
+        );
+        assert_eq!(DeqNavigation::NextMsg.to_dpi(), DPI_DEQ_NAV_NEXT_MSG);
+        assert_eq!(Visibility::Immediate.to_dpi(), DPI_VISIBILITY_IMMEDIATE);
+        assert_eq!(Visibility::OnCommit.to_dpi(), DPI_VISIBILITY_ON_COMMIT);
+
+        for mode in [
+            DeliveryMode::Persistent,
+            DeliveryMode::Buffered,
+            DeliveryMode::PersistentOrBuffered,
+        ] {
+            assert_eq!(DeliveryMode::from_dpi(mode.to_dpi()).unwrap(), mode);
+        }
+        assert_eq!(DeliveryMode::Buffered.to_dpi(), DPI_MODE_MSG_BUFFERED);
+        assert!(matches!(
+            DeliveryMode::from_dpi(0xff),
+            Err(Error::UnsupportedType(_))
+        ));
+
+        let states = [
+            (DPI_MSG_STATE_READY, MessageState::Ready),
+            (DPI_MSG_STATE_WAITING, MessageState::Waiting),
+            (DPI_MSG_STATE_PROCESSED, MessageState::Processed),
+            (DPI_MSG_STATE_EXPIRED, MessageState::Expired),
+        ];
+        for (state, expected) in states {
+            assert_eq!(MessageState::from_dpi(state).unwrap(), expected);
+        }
+        assert!(matches!(
+            MessageState::from_dpi(0xff),
+            Err(Error::UnsupportedType(_))
+        ));
+    }
+}
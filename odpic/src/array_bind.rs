@@ -0,0 +1,186 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::sql_type::widen_sql_type;
+use crate::var::Var;
+use crate::{BindIndex, Error, FromSql, Result, SqlType, Statement, ToSql, ToSqlNull};
+use std::marker::PhantomData;
+
+/// Handle of a PL/SQL associative array (`TABLE OF ... INDEX BY PLS_INTEGER`)
+/// bound as OUT or IN OUT
+///
+/// It is returned by [`Statement::bind_array_out`] and
+/// [`Statement::bind_array_inout`] and is used to get elements after
+/// execution by [`Statement::out_array`].
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// // create package pkg as
+/// //   type num_array is table of number index by pls_integer;
+/// //   procedure double(nums in out num_array);
+/// //   procedure names(ids in num_array, names out dbms_sql.varchar2_table);
+/// // end;
+/// let mut stmt = conn.prepare("begin pkg.double(:1); end;")?;
+/// let nums = stmt.bind_array_inout(1, &[1i64, 2, 3], 10)?;
+/// stmt.execute()?;
+/// assert_eq!(stmt.out_array(&nums)?, vec![2, 4, 6]);
+///
+/// let mut stmt = conn.prepare("begin pkg.names(:1, :2); end;")?;
+/// stmt.bind_array(1, &[7369i64, 7499])?;
+/// let names = stmt.bind_array_out_with_size::<Option<String>, _>(2, 10, 30)?;
+/// stmt.execute()?;
+/// println!("{:?}", stmt.out_array(&names)?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ArrayBind<T> {
+    key: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for ArrayBind<T> {
+    fn clone(&self) -> Self {
+        ArrayBind {
+            key: self.key.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+// Returns the element type which fits all `values`.
+fn element_type<T: ToSql + ToSqlNull>(values: &[T], min_size: u32) -> Result<SqlType> {
+    let mut sql_type = T::sql_null_type();
+    sql_type.size = sql_type.size.max(min_size);
+    let mut only_nulls = true;
+    for (idx, value) in values.iter().enumerate() {
+        let t = value.sql_type();
+        if !widen_sql_type(&mut sql_type, &mut only_nulls, &t, value.is_null()) {
+            return Err(Error::InvalidConversion(format!(
+                "element {} is {:?} but the array is {:?}",
+                idx, t, sql_type
+            )));
+        }
+    }
+    Ok(sql_type)
+}
+
+impl Statement<'_> {
+    /// Binds `values` to an IN PL/SQL associative array.
+    pub fn bind_array<T, I>(&mut self, index: I, values: &[T]) -> Result<()>
+    where
+        T: ToSql + ToSqlNull,
+        I: BindIndex,
+    {
+        let len = u32::try_from(values.len())
+            .map_err(|_| Error::InvalidOperation("too many array elements".into()))?;
+        let var = self.new_array_var(values, element_type(values, 0)?, len)?;
+        self.bind_var(index, var)
+    }
+
+    /// Binds an OUT PL/SQL associative array which receives up to
+    /// `max_array_size` elements. Execution fails with
+    /// [`Error::ArraySizeExceeded`] when PL/SQL returns more elements.
+    pub fn bind_array_out<T, I>(&mut self, index: I, max_array_size: u32) -> Result<ArrayBind<T>>
+    where
+        T: FromSql,
+        I: BindIndex,
+    {
+        self.bind_array_out_with_size(index, max_array_size, T::sql_type().size)
+    }
+
+    /// Binds an OUT PL/SQL associative array whose elements are `size` bytes.
+    pub fn bind_array_out_with_size<T, I>(
+        &mut self,
+        index: I,
+        max_array_size: u32,
+        size: u32,
+    ) -> Result<ArrayBind<T>>
+    where
+        T: FromSql,
+        I: BindIndex,
+    {
+        let var = Var::new_array(
+            self.connection(),
+            T::sql_type().with_size(size),
+            max_array_size,
+        )?;
+        self.bind_array_var(index, var)
+    }
+
+    /// Binds an IN OUT PL/SQL associative array initialized with `values`
+    /// which receives up to `max_array_size` elements.
+    pub fn bind_array_inout<T, I>(
+        &mut self,
+        index: I,
+        values: &[T],
+        max_array_size: u32,
+    ) -> Result<ArrayBind<T>>
+    where
+        T: ToSql + ToSqlNull + FromSql,
+        I: BindIndex,
+    {
+        let sql_type = element_type(values, <T as FromSql>::sql_type().size)?;
+        let var = self.new_array_var(values, sql_type, max_array_size)?;
+        self.bind_array_var(index, var)
+    }
+
+    /// Gets elements of the OUT or IN OUT PL/SQL associative array after
+    /// execution.
+    pub fn out_array<T: FromSql>(&self, bind: &ArrayBind<T>) -> Result<Vec<T>> {
+        let var = self.bound_var_by_key(&bind.key)?;
+        let num = var.num_elements()?;
+        if num > var.max_array_size() {
+            return Err(Error::InvalidOperation(format!(
+                "{} elements are returned at {} but the max array size is {}",
+                num,
+                bind.key,
+                var.max_array_size()
+            )));
+        }
+        (0..num).map(|pos| T::from_sql(&var.get(pos)?)).collect()
+    }
+
+    fn new_array_var<T: ToSql>(
+        &self,
+        values: &[T],
+        sql_type: SqlType,
+        max_array_size: u32,
+    ) -> Result<Var> {
+        if values.len() > max_array_size as usize {
+            return Err(Error::InvalidOperation(format!(
+                "{} elements exceed the max array size {}",
+                values.len(),
+                max_array_size
+            )));
+        }
+        let mut var = Var::new_array(self.connection(), sql_type, max_array_size.max(1))?;
+        for (pos, value) in (0..).zip(values) {
+            var.set(pos, value)?;
+        }
+        var.set_num_elements(values.len() as u32)?;
+        Ok(var)
+    }
+
+    fn bind_array_var<T, I: BindIndex>(&mut self, index: I, var: Var) -> Result<ArrayBind<T>> {
+        let key = index.key();
+        self.bind_var_with_direction(index, var, true)?;
+        Ok(ArrayBind {
+            key,
+            _marker: PhantomData,
+        })
+    }
+}
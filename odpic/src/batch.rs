@@ -0,0 +1,495 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::sql_type::widen_sql_type;
+use crate::var::Var;
+use crate::{DpiError, Error, Result, SqlType, Statement, ToSql};
+use std::iter::Peekable;
+use std::mem;
+use std::ptr;
+use std::result;
+use std::slice;
+
+const DEFAULT_MAX_ROWS_PER_CHUNK: u32 = 10_000;
+const DEFAULT_MAX_BYTES_PER_CHUNK: usize = 16 * 1024 * 1024;
+
+/// Options of [`Statement::execute_many`]
+///
+/// ```no_run
+/// # use odpic::{BatchOptions, Connection, ToSql};
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let rows: Vec<[&dyn ToSql; 2]> = vec![[&1, &"a"], [&2, &"b"]];
+/// let mut stmt = conn.prepare("insert into t values (:1, :2)")?;
+/// let report = stmt.execute_many(
+///     &rows,
+///     &BatchOptions::new().batch_errors(true).array_dml_row_counts(true),
+/// )?;
+/// for err in &report.errors {
+///     println!("row {}: {}", err.offset, err.error);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchOptions {
+    batch_errors: bool,
+    array_dml_row_counts: bool,
+    max_rows_per_chunk: u32,
+    max_bytes_per_chunk: usize,
+}
+
+impl BatchOptions {
+    pub fn new() -> BatchOptions {
+        BatchOptions {
+            batch_errors: false,
+            array_dml_row_counts: false,
+            max_rows_per_chunk: DEFAULT_MAX_ROWS_PER_CHUNK,
+            max_bytes_per_chunk: DEFAULT_MAX_BYTES_PER_CHUNK,
+        }
+    }
+
+    /// Continues execution after rows fail and reports their errors,
+    /// using `DPI_MODE_EXEC_BATCH_ERRORS`. Otherwise execution stops at the
+    /// first error.
+    pub fn batch_errors(mut self, enable: bool) -> BatchOptions {
+        self.batch_errors = enable;
+        self
+    }
+
+    /// Reports the number of rows affected by each input row, using
+    /// `DPI_MODE_EXEC_ARRAY_DML_ROWCOUNTS`.
+    pub fn array_dml_row_counts(mut self, enable: bool) -> BatchOptions {
+        self.array_dml_row_counts = enable;
+        self
+    }
+
+    /// Sets the maximum number of rows sent in a round-trip. The default is
+    /// 10,000.
+    pub fn max_rows_per_chunk(mut self, rows: u32) -> BatchOptions {
+        self.max_rows_per_chunk = rows.max(1);
+        self
+    }
+
+    /// Sets the estimated maximum size of variables allocated for a chunk.
+    /// A chunk ends early when its rows exceed this. The default is 16 MiB.
+    pub fn max_bytes_per_chunk(mut self, bytes: usize) -> BatchOptions {
+        self.max_bytes_per_chunk = bytes;
+        self
+    }
+
+    fn mode(&self) -> dpiExecMode {
+        let mut mode = DPI_MODE_EXEC_DEFAULT;
+        if self.batch_errors {
+            mode |= DPI_MODE_EXEC_BATCH_ERRORS;
+        }
+        if self.array_dml_row_counts {
+            mode |= DPI_MODE_EXEC_ARRAY_DML_ROWCOUNTS;
+        }
+        mode
+    }
+}
+
+impl Default for BatchOptions {
+    fn default() -> BatchOptions {
+        BatchOptions::new()
+    }
+}
+
+/// Error of a row in [`BatchReport`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchError {
+    /// 0-based offset of the row in all rows passed to
+    /// [`Statement::execute_many`]
+    pub offset: u64,
+    pub error: DpiError,
+}
+
+/// Failure of [`Statement::execute_many`], returned as [`Error::Batch`]
+#[derive(Debug)]
+pub struct BatchFailure {
+    /// 0-based offset of the row causing the error in all rows passed to
+    /// [`Statement::execute_many`]. It is the number of rows when the
+    /// error occurred after all rows were executed.
+    pub offset: u64,
+    pub error: Error,
+    /// report of rows executed before the error. Rows before
+    /// [`BatchReport::num_rows`] were executed and the others weren't.
+    pub report: BatchReport,
+}
+
+/// Outcome of [`Statement::execute_many`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    /// number of rows executed
+    pub num_rows: u64,
+    /// errors of failed rows when [`BatchOptions::batch_errors`] is enabled
+    pub errors: Vec<BatchError>,
+    /// numbers of rows affected by each row when
+    /// [`BatchOptions::array_dml_row_counts`] is enabled
+    pub row_counts: Vec<u64>,
+}
+
+impl BatchReport {
+    /// Returns true when no row failed.
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+// Type of a value and whether it is null
+type ValueType = (SqlType, bool);
+
+fn value_type<V: ToSql>(value: &V) -> ValueType {
+    (value.sql_type(), value.is_null())
+}
+
+// Types and sizes of bind variables for rows in a chunk
+#[derive(Debug)]
+struct ChunkColumns {
+    types: Vec<SqlType>,
+    // whether all values in each column are null
+    only_nulls: Vec<bool>,
+    bytes_per_row: usize,
+}
+
+impl ChunkColumns {
+    fn new(row: &[ValueType]) -> ChunkColumns {
+        let types: Vec<_> = row.iter().map(|(t, _)| *t).collect();
+        let bytes_per_row = types.iter().map(element_size).sum();
+        ChunkColumns {
+            types,
+            only_nulls: row.iter().map(|(_, is_null)| *is_null).collect(),
+            bytes_per_row,
+        }
+    }
+
+    // Returns the columns widened to hold a row of values of `row` types or
+    // an error when types don't match. Null values match any types.
+    fn widen(&self, row: &[ValueType], offset: u64) -> Result<ChunkColumns> {
+        if row.len() != self.types.len() {
+            return Err(Error::InvalidOperation(format!(
+                "row {} has {} values but the first row has {}",
+                offset,
+                row.len(),
+                self.types.len()
+            )));
+        }
+        let mut types = self.types.clone();
+        let mut only_nulls = self.only_nulls.clone();
+        let columns = types.iter_mut().zip(&mut only_nulls).zip(row);
+        for (col, ((t, only_nulls), (sql_type, is_null))) in columns.enumerate() {
+            if !widen_sql_type(t, only_nulls, sql_type, *is_null) {
+                return Err(Error::InvalidConversion(format!(
+                    "value at column {} of row {} is {:?} but the column is {:?}",
+                    col + 1,
+                    offset,
+                    sql_type,
+                    t
+                )));
+            }
+        }
+        let bytes_per_row = types.iter().map(element_size).sum();
+        Ok(ChunkColumns {
+            types,
+            only_nulls,
+            bytes_per_row,
+        })
+    }
+}
+
+fn element_size(sql_type: &SqlType) -> usize {
+    mem::size_of::<dpiData>() + sql_type.size as usize
+}
+
+// Result whose error has the offset of the row causing it
+type RowResult<T> = result::Result<T, (u64, Error)>;
+
+// Takes rows of the next chunk from `rows`. `offset` is the offset of the
+// first row in all rows. Errors are returned with the offsets of the rows
+// causing them.
+fn next_chunk<I, R, V>(
+    rows: &mut Peekable<I>,
+    offset: u64,
+    options: &BatchOptions,
+) -> RowResult<Option<(Vec<R>, ChunkColumns)>>
+where
+    I: Iterator<Item = R>,
+    R: AsRef<[V]>,
+    V: ToSql,
+{
+    let mut chunk = Vec::new();
+    let mut columns: Option<ChunkColumns> = None;
+    while let Some(row) = rows.peek() {
+        let row_types: Vec<_> = row.as_ref().iter().map(value_type).collect();
+        let row_offset = offset + chunk.len() as u64;
+        let widened = match &columns {
+            Some(columns) => columns
+                .widen(&row_types, row_offset)
+                .map_err(|err| (row_offset, err))?,
+            None => ChunkColumns::new(&row_types),
+        };
+        let bytes = widened.bytes_per_row * (chunk.len() + 1);
+        if !chunk.is_empty()
+            && (chunk.len() >= options.max_rows_per_chunk as usize
+                || bytes > options.max_bytes_per_chunk)
+        {
+            break;
+        }
+        columns = Some(widened);
+        chunk.push(rows.next().unwrap());
+    }
+    Ok(columns.map(|columns| (chunk, columns)))
+}
+
+impl Statement<'_> {
+    /// Executes the statement for each row by array DML.
+    ///
+    /// Values in each row are bound by position. Rows are sent in chunks
+    /// limited by [`BatchOptions::max_rows_per_chunk`] and
+    /// [`BatchOptions::max_bytes_per_chunk`]. Variables of each chunk are
+    /// sized to fit the largest value in each column. Values in a column
+    /// must be of the same Oracle and native types except nulls, so
+    /// timestamps with and without time zones cannot be mixed.
+    ///
+    /// Errors are returned as [`Error::Batch`], which tells the row causing
+    /// the error and the report of rows executed before it.
+    pub fn execute_many<I, R, V>(&mut self, rows: I, options: &BatchOptions) -> Result<BatchReport>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[V]>,
+        V: ToSql,
+    {
+        let mut report = BatchReport::default();
+        let mut rows = rows.into_iter().peekable();
+        let res = (|| {
+            while let Some((chunk, columns)) = next_chunk(&mut rows, report.num_rows, options)? {
+                self.execute_chunk(&chunk, &columns, options, &mut report)?;
+            }
+            Ok(())
+        })();
+        match res {
+            Ok(()) => Ok(report),
+            Err((offset, error)) => Err(Error::Batch(Box::new(BatchFailure {
+                offset,
+                error,
+                report,
+            }))),
+        }
+    }
+
+    // On errors `report` is updated with rows executed before them.
+    fn execute_chunk<R, V>(
+        &mut self,
+        chunk: &[R],
+        columns: &ChunkColumns,
+        options: &BatchOptions,
+        report: &mut BatchReport,
+    ) -> RowResult<()>
+    where
+        R: AsRef<[V]>,
+        V: ToSql,
+    {
+        let conn = self.connection();
+        let ctxt = conn.ctxt();
+        let base = report.num_rows;
+        let num_rows = chunk.len() as u32;
+        let at = |offset: u64| move |err| (offset, err);
+        let mut vars = columns
+            .types
+            .iter()
+            .map(|t| Var::new(conn, *t, num_rows))
+            .collect::<Result<Vec<_>>>()
+            .map_err(at(base))?;
+        for (pos, row) in (0..).zip(chunk) {
+            for (var, value) in vars.iter_mut().zip(row.as_ref()) {
+                var.set(pos, value).map_err(at(base + pos as u64))?;
+            }
+        }
+        for (pos, var) in (1..).zip(vars) {
+            self.bind_var(pos as usize, var).map_err(at(base))?;
+        }
+        self.reserve_out_vars(num_rows).map_err(at(base))?;
+        if unsafe { dpiStmt_executeMany(self.handle(), options.mode(), num_rows) } != DPI_SUCCESS {
+            // Rows before the row at the error offset were executed.
+            let err = ctxt.last_error();
+            let offset = base + (err.offset as u64).min(num_rows as u64);
+            report.num_rows = offset;
+            return Err((offset, Error::DpiError(err)));
+        }
+        report.num_rows += num_rows as u64;
+        let end = report.num_rows;
+        (|| {
+            self.collect_returned(num_rows, base > 0)?;
+            if options.batch_errors {
+                let mut count = 0;
+                chkerr!(ctxt, dpiStmt_getBatchErrorCount(self.handle(), &mut count));
+                if count > 0 {
+                    let mut errors = vec![dpiErrorInfo::default(); count as usize];
+                    chkerr!(
+                        ctxt,
+                        dpiStmt_getBatchErrors(self.handle(), count, errors.as_mut_ptr())
+                    );
+                    report.errors.extend(errors.iter().map(|info| BatchError {
+                        offset: base + info.offset as u64,
+                        error: unsafe { DpiError::from_dpi(info) },
+                    }));
+                }
+            }
+            if options.array_dml_row_counts {
+                let mut count = 0;
+                let mut counts = ptr::null_mut();
+                chkerr!(
+                    ctxt,
+                    dpiStmt_getRowCounts(self.handle(), &mut count, &mut counts)
+                );
+                if count > 0 {
+                    report.row_counts.extend_from_slice(unsafe {
+                        slice::from_raw_parts(counts, count as usize)
+                    });
+                }
+            }
+            Ok(())
+        })()
+        .map_err(at(end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Timestamp;
+
+    fn types(columns: &ChunkColumns) -> Vec<(dpiOracleTypeNum, u32)> {
+        columns
+            .types
+            .iter()
+            .map(|t| (t.oracle_type, t.size))
+            .collect()
+    }
+
+    #[test]
+    fn widen_columns() {
+        let columns = ChunkColumns::new(&[value_type(&1), value_type(&"ab")]);
+        let widened = columns
+            .widen(&[value_type(&2), value_type(&"abcd")], 1)
+            .unwrap();
+        assert_eq!(
+            types(&widened),
+            [(DPI_ORACLE_TYPE_NUMBER, 0), (DPI_ORACLE_TYPE_VARCHAR, 4)]
+        );
+        assert_eq!(widened.bytes_per_row, columns.bytes_per_row + 2);
+        let narrower = widened
+            .widen(&[value_type(&3), value_type(&"a")], 2)
+            .unwrap();
+        assert_eq!(types(&narrower), types(&widened));
+
+        let err = columns
+            .widen(&[value_type(&1), value_type(&1)], 7)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidConversion(msg) if msg.contains("row 7")));
+        let err = columns.widen(&[value_type(&1)], 8).unwrap_err();
+        assert!(matches!(err, Error::InvalidOperation(msg) if msg.contains("row 8")));
+    }
+
+    #[test]
+    fn widen_timestamp_columns() {
+        let ts = Timestamp::new(2024, 1, 2, 3, 4, 5);
+        let utc = ts.and_tz_offset(0, 0);
+        let columns = ChunkColumns::new(&[value_type(&ts), value_type(&utc)]);
+        assert_eq!(
+            types(&columns),
+            [
+                (DPI_ORACLE_TYPE_TIMESTAMP, 0),
+                (DPI_ORACLE_TYPE_TIMESTAMP_TZ, 0)
+            ]
+        );
+
+        // Mixed timestamps aren't widened to TIMESTAMP WITH TIME ZONE.
+        let err = columns
+            .widen(&[value_type(&utc), value_type(&utc)], 1)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidConversion(msg) if msg.contains("column 1 of row 1")));
+        let err = columns
+            .widen(&[value_type(&ts), value_type(&ts)], 2)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidConversion(msg) if msg.contains("column 2 of row 2")));
+
+        // Nulls match either.
+        let null = None::<Timestamp>;
+        let widened = columns
+            .widen(&[value_type(&null), value_type(&null)], 3)
+            .unwrap();
+        assert_eq!(types(&widened), types(&columns));
+        let columns = ChunkColumns::new(&[value_type(&null), value_type(&None::<&str>)]);
+        let widened = columns
+            .widen(&[value_type(&utc), value_type(&1)], 4)
+            .unwrap();
+        assert_eq!(
+            types(&widened),
+            [
+                (DPI_ORACLE_TYPE_TIMESTAMP_TZ, 0),
+                (DPI_ORACLE_TYPE_NUMBER, 1)
+            ]
+        );
+        assert!(widened
+            .widen(&[value_type(&ts), value_type(&null)], 5)
+            .is_err());
+    }
+
+    fn chunk_sizes<R, V>(rows: Vec<R>, options: &BatchOptions) -> RowResult<Vec<usize>>
+    where
+        R: AsRef<[V]>,
+        V: ToSql,
+    {
+        let mut rows = rows.into_iter().peekable();
+        let mut sizes = Vec::new();
+        let mut offset = 0;
+        while let Some((chunk, _)) = next_chunk(&mut rows, offset, options)? {
+            offset += chunk.len() as u64;
+            sizes.push(chunk.len());
+        }
+        Ok(sizes)
+    }
+
+    #[test]
+    fn split_chunks() {
+        let rows = vec![[1]; 5];
+        let options = BatchOptions::new();
+        assert_eq!(chunk_sizes(rows.clone(), &options).unwrap(), [5]);
+        assert!(chunk_sizes(Vec::<[i32; 1]>::new(), &options)
+            .unwrap()
+            .is_empty());
+        let options = BatchOptions::new().max_rows_per_chunk(2);
+        assert_eq!(chunk_sizes(rows, &options).unwrap(), [2, 2, 1]);
+
+        // Chunks end before rows widening variables beyond the limit.
+        let row_size = ChunkColumns::new(&[value_type(&"x")]).bytes_per_row;
+        let options = BatchOptions::new().max_bytes_per_chunk(row_size * 2);
+        let rows = vec![["x"], ["x"], ["x"], ["xx"]];
+        assert_eq!(chunk_sizes(rows, &options).unwrap(), [2, 1, 1]);
+
+        // A row larger than the limit makes a chunk by itself.
+        let options = BatchOptions::new().max_bytes_per_chunk(1);
+        assert_eq!(chunk_sizes(vec![["x"], ["y"]], &options).unwrap(), [1, 1]);
+
+        // Errors tell offsets of rows in all rows.
+        let options = BatchOptions::new().max_rows_per_chunk(2);
+        let rows = vec![vec![1], vec![2], vec![3], vec![4, 5]];
+        let (offset, err) = chunk_sizes(rows, &options).unwrap_err();
+        assert_eq!(offset, 3);
+        assert!(matches!(err, Error::InvalidOperation(_)));
+    }
+}
@@ -0,0 +1,293 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::object::ObjectType;
+use crate::{Context, Error, Result, ScrollableStatement, Statement};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const UTF8: &str = "UTF-8\0";
+
+/// Connection to an Oracle database
+///
+/// This holds a reference to [`dpiConn`], which is released when it is
+/// dropped.
+#[derive(Debug)]
+pub struct Connection {
+    ctxt: &'static Context,
+    handle: *mut dpiConn,
+    temp_lobs: Arc<AtomicUsize>,
+    // object types keyed by SCHEMA.NAME or SCHEMA.PACKAGE.NAME
+    object_types: RefCell<HashMap<String, ObjectType>>,
+    // the session schema got when the current schema isn't set by the client
+    session_schema: RefCell<Option<String>>,
+}
+
+impl Connection {
+    /// Creates a standalone connection.
+    pub fn connect(username: &str, password: &str, connect_string: &str) -> Result<Connection> {
+        Connection::create(username, password, connect_string, DPI_MODE_CREATE_THREADED)
+    }
+
+    /// Creates a standalone connection in events mode, which
+    /// [subscriptions](crate::subscr) require.
+    pub fn connect_with_events(
+        username: &str,
+        password: &str,
+        connect_string: &str,
+    ) -> Result<Connection> {
+        Connection::create(
+            username,
+            password,
+            connect_string,
+            DPI_MODE_CREATE_THREADED | DPI_MODE_CREATE_EVENTS,
+        )
+    }
+
+    fn create(
+        username: &str,
+        password: &str,
+        connect_string: &str,
+        create_mode: dpiCreateMode,
+    ) -> Result<Connection> {
+        let ctxt = Context::get()?;
+        let mut common_params = dpiCommonCreateParams::default();
+        let mut conn_params = dpiConnCreateParams::default();
+        chkerr!(
+            ctxt,
+            dpiContext_initCommonCreateParams(ctxt.handle(), &mut common_params)
+        );
+        chkerr!(
+            ctxt,
+            dpiContext_initConnCreateParams(ctxt.handle(), &mut conn_params)
+        );
+        common_params.createMode |= create_mode;
+        common_params.encoding = UTF8.as_ptr() as *const c_char;
+        common_params.nencoding = UTF8.as_ptr() as *const c_char;
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiConn_create(
+                ctxt.handle(),
+                username.as_ptr() as *const c_char,
+                username.len() as u32,
+                password.as_ptr() as *const c_char,
+                password.len() as u32,
+                connect_string.as_ptr() as *const c_char,
+                connect_string.len() as u32,
+                &common_params,
+                &mut conn_params,
+                &mut handle
+            )
+        );
+        Ok(Connection {
+            ctxt,
+            handle,
+            temp_lobs: Arc::new(AtomicUsize::new(0)),
+            object_types: RefCell::new(HashMap::new()),
+            session_schema: RefCell::new(None),
+        })
+    }
+
+    /// Creates a connection from a handle, adding a reference to it.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid connection handle.
+    pub unsafe fn from_raw(handle: *mut dpiConn) -> Result<Connection> {
+        let ctxt = Context::get()?;
+        chkerr!(ctxt, dpiConn_addRef(handle));
+        Ok(Connection {
+            ctxt,
+            handle,
+            temp_lobs: Arc::new(AtomicUsize::new(0)),
+            object_types: RefCell::new(HashMap::new()),
+            session_schema: RefCell::new(None),
+        })
+    }
+
+    pub fn handle(&self) -> *mut dpiConn {
+        self.handle
+    }
+
+    pub(crate) fn ctxt(&self) -> &'static Context {
+        self.ctxt
+    }
+
+    pub(crate) fn temp_lob_counter(&self) -> &Arc<AtomicUsize> {
+        &self.temp_lobs
+    }
+
+    /// Returns the number of [`TempLob`]s created via this connection and
+    /// not freed yet.
+    ///
+    /// [`TempLob`]: crate::lob::TempLob
+    pub fn temp_lob_count(&self) -> usize {
+        self.temp_lobs.load(Ordering::Relaxed)
+    }
+
+    /// Gets the object type `name`, such as `SCHEMA.TYPE_NAME`.
+    ///
+    /// Types are cached in the connection, keyed by names normalized as
+    /// the database does: unquoted identifiers are in upper case and names
+    /// without schema are in the current schema. So the database is looked up
+    /// only for the first call per type.
+    pub fn object_type(&self, name: &str) -> Result<ObjectType> {
+        let mut parts = split_object_name(name)?;
+        if parts.len() == 1 {
+            parts.insert(0, self.current_schema()?);
+        }
+        if let Some(objtype) = self.cached_object_type(&parts.join(".")) {
+            return Ok(objtype);
+        }
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            self.ctxt,
+            dpiConn_getObjectType(
+                self.handle,
+                name.as_ptr() as *const c_char,
+                name.len() as u32,
+                &mut handle
+            )
+        );
+        // dpiConn_getObjectType returns a reference owned by the caller,
+        // and ObjectType::from_raw caches the type by its full name.
+        let objtype = unsafe { ObjectType::from_raw(self, handle) };
+        unsafe { dpiObjectType_release(handle) };
+        let objtype = objtype?;
+        self.cache_object_type(parts.join("."), &objtype);
+        Ok(objtype)
+    }
+
+    /// Clears the cache of object types, such as after types are altered.
+    ///
+    /// This must be also called after the current schema is changed by
+    /// `ALTER SESSION SET CURRENT_SCHEMA`.
+    pub fn clear_object_type_cache(&self) {
+        self.object_types.borrow_mut().clear();
+        self.session_schema.borrow_mut().take();
+    }
+
+    // Returns the schema set by dpiConn_setCurrentSchema() or, when it isn't
+    // set, the one of the session.
+    fn current_schema(&self) -> Result<String> {
+        let mut ptr = ptr::null();
+        let mut len = 0;
+        chkerr!(
+            self.ctxt,
+            dpiConn_getCurrentSchema(self.handle, &mut ptr, &mut len)
+        );
+        if !ptr.is_null() && len > 0 {
+            let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
+            return Ok(String::from_utf8_lossy(bytes).into_owned());
+        }
+        if let Some(schema) = self.session_schema.borrow().as_ref() {
+            return Ok(schema.clone());
+        }
+        let schema: String = self
+            .prepare("select sys_context('userenv', 'current_schema') from dual")?
+            .query()?
+            .next()
+            .ok_or_else(|| Error::InvalidOperation("no current schema".into()))??
+            .get(0)?;
+        *self.session_schema.borrow_mut() = Some(schema.clone());
+        Ok(schema)
+    }
+
+    pub(crate) fn cached_object_type(&self, name: &str) -> Option<ObjectType> {
+        self.object_types.borrow().get(name).cloned()
+    }
+
+    pub(crate) fn cache_object_type(&self, name: String, objtype: &ObjectType) {
+        self.object_types.borrow_mut().insert(name, objtype.clone());
+    }
+
+    /// Prepares a statement.
+    pub fn prepare(&self, sql: &str) -> Result<Statement<'_>> {
+        Statement::new(self, sql)
+    }
+
+    /// Prepares a query with a scrollable cursor.
+    pub fn prepare_scrollable(&self, sql: &str) -> Result<ScrollableStatement<'_>> {
+        ScrollableStatement::new(self, sql)
+    }
+
+    pub fn commit(&self) -> Result<()> {
+        chkerr!(self.ctxt, dpiConn_commit(self.handle));
+        Ok(())
+    }
+
+    pub fn rollback(&self) -> Result<()> {
+        chkerr!(self.ctxt, dpiConn_rollback(self.handle));
+        Ok(())
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe { dpiConn_release(self.handle) };
+    }
+}
+
+// Splits an object type name into its parts, in upper case unless quoted.
+fn split_object_name(name: &str) -> Result<Vec<String>> {
+    let invalid = || Error::InvalidOperation(format!("invalid object type name {:?}", name));
+    let mut parts = Vec::new();
+    let mut rest = name.trim();
+    loop {
+        let part = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').ok_or_else(invalid)?;
+            rest = quoted[end + 1..].trim_start();
+            quoted[..end].to_string()
+        } else {
+            let end = rest.find('.').unwrap_or(rest.len());
+            let part = rest[..end].trim_end();
+            rest = &rest[end..];
+            part.to_uppercase()
+        };
+        if part.is_empty() || parts.len() == 3 {
+            return Err(invalid());
+        }
+        parts.push(part);
+        match rest.strip_prefix('.') {
+            Some(next) => rest = next.trim_start(),
+            None if rest.is_empty() => return Ok(parts),
+            None => return Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_object_names() {
+        assert_eq!(split_object_name("addr_t").unwrap(), ["ADDR_T"]);
+        assert_eq!(split_object_name("hr.Addr_T").unwrap(), ["HR", "ADDR_T"]);
+        assert_eq!(
+            split_object_name("\"hr\" . pkg.\"a.b\"").unwrap(),
+            ["hr", "PKG", "a.b"]
+        );
+        for name in ["", "hr.", ".t", "\"t", "\"\"", "a.b.c.d", "\"t\"x"] {
+            assert!(split_object_name(name).is_err(), "{:?}", name);
+        }
+    }
+}
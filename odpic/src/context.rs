@@ -0,0 +1,84 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::{DpiError, Error, Result};
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::OnceLock;
+
+/// ODPI-C context shared in the process
+///
+/// The context is created by the first call to [`Context::get`] and lives
+/// until the process exits. All errors raised by ODPI-C are retrieved via
+/// the context.
+#[derive(Debug)]
+pub struct Context {
+    handle: *mut dpiContext,
+}
+
+// The context handle is thread-safe. See "Threading and Thread Safety" in
+// the ODPI-C documentation.
+unsafe impl Send for Context {}
+unsafe impl Sync for Context {}
+
+const DRIVER_NAME: &str = concat!("odpic : ", env!("CARGO_PKG_VERSION"), "\0");
+
+static CONTEXT: OnceLock<std::result::Result<Context, DpiError>> = OnceLock::new();
+
+impl Context {
+    /// Gets the context, creating it at the first call.
+    pub fn get() -> Result<&'static Context> {
+        CONTEXT
+            .get_or_init(|| {
+                let mut handle = ptr::null_mut();
+                let mut err = dpiErrorInfo::default();
+                let mut params = dpiContextCreateParams {
+                    defaultDriverName: DRIVER_NAME.as_ptr() as *const c_char,
+                    ..Default::default()
+                };
+                if unsafe {
+                    dpiContext_createWithParams(
+                        DPI_MAJOR_VERSION,
+                        DPI_MINOR_VERSION,
+                        &mut params,
+                        &mut handle,
+                        &mut err,
+                    )
+                } == DPI_SUCCESS
+                {
+                    Ok(Context { handle })
+                } else {
+                    Err(unsafe { DpiError::from_dpi(&err) })
+                }
+            })
+            .as_ref()
+            .map_err(|err| Error::DpiError(err.clone()))
+    }
+
+    pub fn handle(&self) -> *mut dpiContext {
+        self.handle
+    }
+
+    /// Gets the error raised by the last ODPI-C function call in the
+    /// current thread.
+    pub fn last_error(&self) -> DpiError {
+        let mut err = dpiErrorInfo::default();
+        unsafe {
+            dpiContext_getError(self.handle, &mut err);
+            DpiError::from_dpi(&err)
+        }
+    }
+}
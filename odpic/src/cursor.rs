@@ -0,0 +1,134 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::var::Var;
+use crate::{BindIndex, Result, Rows, SqlType, Statement};
+use std::ptr;
+
+/// Result set returned from PL/SQL
+///
+/// This is a REF CURSOR got by [`Statement::cursor`] or an implicit result
+/// got by [`Statement::implicit_results`]. It holds its own reference to the
+/// statement handle, so it may outlive the statement which returned it.
+/// It cannot outlive the connection.
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let mut stmt = conn.prepare("begin open :1 for select * from emp; end;")?;
+/// stmt.bind_cursor(1)?;
+/// stmt.execute()?;
+/// if let Some(mut cursor) = stmt.cursor(1)? {
+///     for row in cursor.rows()? {
+///         println!("{:?}", row?.values());
+///     }
+/// }
+///
+/// let mut stmt = conn.prepare(
+///     "declare c sys_refcursor; begin open c for select * from dept; dbms_sql.return_result(c); end;",
+/// )?;
+/// stmt.execute()?;
+/// for mut cursor in stmt.implicit_results()? {
+///     for row in cursor.rows()? {
+///         println!("{:?}", row?.values());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A cursor borrows the connection:
+///
+/// ```compile_fail
+/// # use odpic::{Connection, Cursor};
+/// fn outlive_connection() -> odpic::Result<Option<Cursor<'static>>> {
+///     let conn = Connection::connect("scott", "tiger", "localhost/orclpdb")?;
+///     let mut stmt = conn.prepare("begin open :1 for select * from emp; end;")?;
+///     stmt.bind_cursor(1)?;
+///     stmt.execute()?;
+///     stmt.cursor(1)
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Cursor<'conn> {
+    stmt: Statement<'conn>,
+}
+
+impl<'conn> Cursor<'conn> {
+    pub fn statement(&self) -> &Statement<'conn> {
+        &self.stmt
+    }
+
+    /// Sets the number of rows fetched in a round-trip. It must be set before
+    /// [`Cursor::rows`].
+    pub fn set_fetch_array_size(&mut self, size: u32) -> Result<()> {
+        self.stmt.set_fetch_array_size(size)
+    }
+
+    /// Returns an iterator of rows in the result set.
+    pub fn rows(&mut self) -> Result<Rows<'_>> {
+        self.stmt.rows()
+    }
+}
+
+impl<'conn> Statement<'conn> {
+    /// Binds an OUT variable which receives a REF CURSOR.
+    pub fn bind_cursor<I: BindIndex>(&mut self, index: I) -> Result<()> {
+        let sql_type = SqlType::new(DPI_ORACLE_TYPE_STMT, DPI_NATIVE_TYPE_STMT, 0);
+        let var = Var::new(self.connection(), sql_type, 1)?;
+        self.bind_var(index, var)
+    }
+
+    /// Gets the REF CURSOR returned in the variable bound by
+    /// [`Statement::bind_cursor`] after execution. It is `None` when the
+    /// cursor is null.
+    pub fn cursor<I: BindIndex>(&self, index: I) -> Result<Option<Cursor<'conn>>> {
+        let data = self.bound_var(index)?.data(0);
+        if data.isNull != 0 {
+            return Ok(None);
+        }
+        let handle = unsafe { data.value.asStmt };
+        let stmt = unsafe { Statement::from_cursor(self.connection(), handle)? };
+        Ok(Some(Cursor { stmt }))
+    }
+
+    /// Gets the next implicit result returned by `DBMS_SQL.RETURN_RESULT`
+    /// after execution.
+    pub fn next_implicit_result(&self) -> Result<Option<Cursor<'conn>>> {
+        let conn = self.connection();
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            conn.ctxt(),
+            dpiStmt_getImplicitResult(self.handle(), &mut handle)
+        );
+        if handle.is_null() {
+            return Ok(None);
+        }
+        // dpiStmt_getImplicitResult returns a reference owned by the caller.
+        let stmt = unsafe { Statement::from_cursor(conn, handle) };
+        unsafe { dpiStmt_release(handle) };
+        Ok(Some(Cursor { stmt: stmt? }))
+    }
+
+    /// Gets all remaining implicit results.
+    pub fn implicit_results(&self) -> Result<Vec<Cursor<'conn>>> {
+        let mut cursors = Vec::new();
+        while let Some(cursor) = self.next_implicit_result()? {
+            cursors.push(cursor);
+        }
+        Ok(cursors)
+    }
+}
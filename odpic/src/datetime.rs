@@ -0,0 +1,230 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic_sys::*;
+
+/// Timestamp value
+///
+/// This corresponds to [`dpiTimestamp`]. `tz_hour_offset` and
+/// `tz_minute_offset` are meaningful only when `has_tz` is true, as for
+/// values of `TIMESTAMP WITH TIME ZONE`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timestamp {
+    pub year: i16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    pub tz_hour_offset: i8,
+    pub tz_minute_offset: i8,
+    /// whether the timestamp has a time zone offset, which may be `+00:00`
+    pub has_tz: bool,
+}
+
+impl Timestamp {
+    /// Creates a timestamp without fractional seconds and time zone.
+    pub fn new(year: i16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Timestamp {
+        Timestamp {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the timestamp with the specified fractional seconds.
+    pub fn and_nanosecond(self, nanosecond: u32) -> Timestamp {
+        Timestamp { nanosecond, ..self }
+    }
+
+    /// Returns the timestamp with the specified time zone offset.
+    pub fn and_tz_offset(self, tz_hour_offset: i8, tz_minute_offset: i8) -> Timestamp {
+        Timestamp {
+            tz_hour_offset,
+            tz_minute_offset,
+            has_tz: true,
+            ..self
+        }
+    }
+
+    /// Returns the timestamp without time zone.
+    pub fn without_tz(self) -> Timestamp {
+        Timestamp {
+            tz_hour_offset: 0,
+            tz_minute_offset: 0,
+            has_tz: false,
+            ..self
+        }
+    }
+
+    /// Returns the time zone offset in minutes.
+    pub fn tz_offset_minutes(&self) -> i32 {
+        self.tz_hour_offset as i32 * 60 + self.tz_minute_offset as i32
+    }
+
+    /// Returns the timestamp whose date and time fields are moved by
+    /// `minutes`. The time zone offset is left unchanged.
+    pub(crate) fn add_minutes(self, minutes: i32) -> Timestamp {
+        let days = days_from_civil(self.year as i32, self.month as u32, self.day as u32);
+        let total = days * 1440 + self.hour as i64 * 60 + self.minute as i64 + minutes as i64;
+        let (year, month, day) = civil_from_days(total.div_euclid(1440));
+        let minute_of_day = total.rem_euclid(1440);
+        Timestamp {
+            year: year as i16,
+            month: month as u8,
+            day: day as u8,
+            hour: (minute_of_day / 60) as u8,
+            minute: (minute_of_day % 60) as u8,
+            ..self
+        }
+    }
+}
+
+// The time zone offset is kept but `has_tz` is false. Set it for values of
+// types with time zone.
+impl From<dpiTimestamp> for Timestamp {
+    fn from(ts: dpiTimestamp) -> Timestamp {
+        Timestamp {
+            year: ts.year,
+            month: ts.month,
+            day: ts.day,
+            hour: ts.hour,
+            minute: ts.minute,
+            second: ts.second,
+            nanosecond: ts.fsecond,
+            tz_hour_offset: ts.tzHourOffset,
+            tz_minute_offset: ts.tzMinuteOffset,
+            has_tz: false,
+        }
+    }
+}
+
+impl From<Timestamp> for dpiTimestamp {
+    fn from(ts: Timestamp) -> dpiTimestamp {
+        dpiTimestamp {
+            year: ts.year,
+            month: ts.month,
+            day: ts.day,
+            hour: ts.hour,
+            minute: ts.minute,
+            second: ts.second,
+            fsecond: ts.nanosecond,
+            tzHourOffset: ts.tz_hour_offset,
+            tzMinuteOffset: ts.tz_minute_offset,
+        }
+    }
+}
+
+/// Interval day to second value
+///
+/// This corresponds to [`dpiIntervalDS`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IntervalDS {
+    pub days: i32,
+    pub hours: i32,
+    pub minutes: i32,
+    pub seconds: i32,
+    pub nanoseconds: i32,
+}
+
+impl IntervalDS {
+    pub fn new(days: i32, hours: i32, minutes: i32, seconds: i32, nanoseconds: i32) -> IntervalDS {
+        IntervalDS {
+            days,
+            hours,
+            minutes,
+            seconds,
+            nanoseconds,
+        }
+    }
+}
+
+impl From<dpiIntervalDS> for IntervalDS {
+    fn from(it: dpiIntervalDS) -> IntervalDS {
+        IntervalDS::new(it.days, it.hours, it.minutes, it.seconds, it.fseconds)
+    }
+}
+
+impl From<IntervalDS> for dpiIntervalDS {
+    fn from(it: IntervalDS) -> dpiIntervalDS {
+        dpiIntervalDS {
+            days: it.days,
+            hours: it.hours,
+            minutes: it.minutes,
+            seconds: it.seconds,
+            fseconds: it.nanoseconds,
+        }
+    }
+}
+
+/// Interval year to month value
+///
+/// This corresponds to [`dpiIntervalYM`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IntervalYM {
+    pub years: i32,
+    pub months: i32,
+}
+
+impl IntervalYM {
+    pub fn new(years: i32, months: i32) -> IntervalYM {
+        IntervalYM { years, months }
+    }
+}
+
+impl From<dpiIntervalYM> for IntervalYM {
+    fn from(it: dpiIntervalYM) -> IntervalYM {
+        IntervalYM::new(it.years, it.months)
+    }
+}
+
+impl From<IntervalYM> for dpiIntervalYM {
+    fn from(it: IntervalYM) -> dpiIntervalYM {
+        dpiIntervalYM {
+            years: it.years,
+            months: it.months,
+        }
+    }
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar.
+// See http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year as i32, month, day)
+}
@@ -0,0 +1,228 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::BatchFailure;
+use odpic_sys::*;
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::io;
+use std::os::raw::c_char;
+use std::result;
+use std::slice;
+
+/// Error information returned by ODPI-C
+///
+/// This is an owned counterpart of [`dpiErrorInfo`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DpiError {
+    /// Oracle error code, such as `1` for `ORA-00001`
+    pub code: i32,
+    /// parse error offset
+    pub offset: u32,
+    pub message: String,
+    /// name of the ODPI-C function which failed
+    pub fn_name: String,
+    /// action which was being performed in the function
+    pub action: String,
+    pub sql_state: String,
+    pub is_recoverable: bool,
+    pub is_warning: bool,
+}
+
+impl DpiError {
+    /// Creates an owned error from error information filled by ODPI-C.
+    ///
+    /// # Safety
+    ///
+    /// Pointers in `info` must be valid, as they are just after ODPI-C fills
+    /// them.
+    pub unsafe fn from_dpi(info: &dpiErrorInfo) -> DpiError {
+        unsafe fn to_string(ptr: *const c_char) -> String {
+            if ptr.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        }
+        let message = if info.message.is_null() {
+            String::new()
+        } else {
+            let bytes =
+                slice::from_raw_parts(info.message as *const u8, info.messageLength as usize);
+            String::from_utf8_lossy(bytes).into_owned()
+        };
+        DpiError {
+            code: info.code,
+            offset: info.offset,
+            message,
+            fn_name: to_string(info.fnName),
+            action: to_string(info.action),
+            sql_state: to_string(info.sqlState),
+            is_recoverable: info.isRecoverable != 0,
+            is_warning: info.isWarning != 0,
+        }
+    }
+}
+
+impl fmt::Display for DpiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> result::Result<(), fmt::Error> {
+        write!(f, "{} ({}: {})", self.message, self.fn_name, self.action)
+    }
+}
+
+impl error::Error for DpiError {}
+
+/// Errors returned by this crate
+#[derive(Debug)]
+pub enum Error {
+    /// ODPI-C function failed.
+    DpiError(DpiError),
+    /// I/O error
+    IoError(io::Error),
+    /// The operation is not allowed for the value or in the current state.
+    InvalidOperation(String),
+    /// A null value is converted to a type other than `Option<T>`.
+    NullValue,
+    /// A value cannot be converted to the requested type.
+    InvalidConversion(String),
+    /// A bind position, bind name or column index doesn't exist.
+    InvalidIndex(String),
+    /// The value is of an Oracle or native type which isn't supported.
+    UnsupportedType(String),
+    /// OSON data is malformed or contains unsupported nodes.
+    InvalidOson(String),
+    /// A number cannot be represented as Oracle NUMBER.
+    InvalidNumber(String),
+    /// A vector is malformed or inconsistent with its format.
+    InvalidVector(String),
+    /// A transaction id violates limits of XA.
+    InvalidXid(String),
+    /// [`Statement::execute_many`] failed. This has the offset of the row
+    /// causing the error and the report of rows executed before it.
+    ///
+    /// [`Statement::execute_many`]: crate::Statement::execute_many
+    Batch(Box<BatchFailure>),
+    /// PL/SQL returned more elements than max array sizes of array binds
+    /// (ORA-06513). This has the bind keys of OUT and IN OUT arrays with
+    /// their max array sizes and the original error.
+    ArraySizeExceeded {
+        arrays: Vec<(String, u32)>,
+        error: Box<DpiError>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> result::Result<(), fmt::Error> {
+        match self {
+            Error::DpiError(err) => err.fmt(f),
+            Error::IoError(err) => err.fmt(f),
+            Error::InvalidOperation(msg) => write!(f, "invalid operation: {}", msg),
+            Error::NullValue => write!(f, "null value"),
+            Error::InvalidConversion(msg) => write!(f, "invalid conversion: {}", msg),
+            Error::InvalidIndex(msg) => write!(f, "invalid index: {}", msg),
+            Error::UnsupportedType(msg) => write!(f, "unsupported type: {}", msg),
+            Error::InvalidOson(msg) => write!(f, "invalid OSON: {}", msg),
+            Error::InvalidNumber(msg) => write!(f, "invalid number: {}", msg),
+            Error::InvalidVector(msg) => write!(f, "invalid vector: {}", msg),
+            Error::InvalidXid(msg) => write!(f, "invalid XID: {}", msg),
+            Error::Batch(failure) => write!(
+                f,
+                "batch failed at row {} after {} rows executed: {}",
+                failure.offset, failure.report.num_rows, failure.error
+            ),
+            Error::ArraySizeExceeded { arrays, error } => {
+                write!(f, "PL/SQL returned more elements than max array sizes of ")?;
+                for (idx, (key, max_array_size)) in arrays.iter().enumerate() {
+                    if idx != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} (max {})", key, max_array_size)?;
+                }
+                write!(f, ": {}", error)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::DpiError(err) => Some(err),
+            Error::IoError(err) => Some(err),
+            Error::Batch(failure) => Some(&failure.error),
+            Error::ArraySizeExceeded { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<DpiError> for Error {
+    fn from(err: DpiError) -> Error {
+        Error::DpiError(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::IoError(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        match err {
+            Error::IoError(err) => err,
+            err => io::Error::other(err),
+        }
+    }
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn array_size_exceeded() {
+        let dpi_err = DpiError {
+            code: 6513,
+            offset: 0,
+            message:
+                "ORA-06513: PL/SQL: index for PL/SQL table out of range for host language array"
+                    .into(),
+            fn_name: "dpiStmt_execute".into(),
+            action: "execute".into(),
+            sql_state: "65000".into(),
+            is_recoverable: false,
+            is_warning: false,
+        };
+        let err = Error::ArraySizeExceeded {
+            arrays: vec![("1".into(), 10), ("NAMES".into(), 5)],
+            error: Box::new(dpi_err.clone()),
+        };
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "PL/SQL returned more elements than max array sizes of 1 (max 10), NAMES (max 5): {}",
+                dpi_err
+            )
+        );
+        let source = err.source().unwrap().downcast_ref::<DpiError>();
+        assert_eq!(source, Some(&dpi_err));
+    }
+}
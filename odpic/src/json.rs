@@ -0,0 +1,274 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::value::bytes_slice;
+use crate::vector;
+use crate::{Error, IntervalDS, IntervalYM, Result, Timestamp, Vector};
+use std::slice;
+
+/// JSON value
+///
+/// This is an owned counterpart of a tree of [`dpiJsonNode`]. Each variant
+/// corresponds to an Oracle type which a node can hold.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    /// JSON null
+    Null,
+    /// `DPI_ORACLE_TYPE_BOOLEAN`
+    Boolean(bool),
+    /// `DPI_ORACLE_TYPE_NUMBER` in decimal text as with `DPI_JSON_OPT_NUMBER_AS_STRING`
+    Number(String),
+    /// `DPI_ORACLE_TYPE_NATIVE_FLOAT`
+    Float(f32),
+    /// `DPI_ORACLE_TYPE_NATIVE_DOUBLE`
+    Double(f64),
+    /// `DPI_ORACLE_TYPE_VARCHAR`
+    String(String),
+    /// `DPI_ORACLE_TYPE_RAW`
+    Raw(Vec<u8>),
+    /// `DPI_ORACLE_TYPE_JSON_ID`
+    Id(Vec<u8>),
+    /// `DPI_ORACLE_TYPE_DATE`
+    Date(Timestamp),
+    /// `DPI_ORACLE_TYPE_TIMESTAMP`
+    Timestamp(Timestamp),
+    /// `DPI_ORACLE_TYPE_TIMESTAMP_TZ`
+    TimestampTz(Timestamp),
+    /// `DPI_ORACLE_TYPE_INTERVAL_DS`
+    IntervalDS(IntervalDS),
+    /// `DPI_ORACLE_TYPE_INTERVAL_YM`
+    IntervalYM(IntervalYM),
+    /// `DPI_ORACLE_TYPE_VECTOR`
+    Vector(Vector),
+    /// `DPI_ORACLE_TYPE_JSON_ARRAY`
+    Array(Vec<JsonValue>),
+    /// `DPI_ORACLE_TYPE_JSON_OBJECT`. Fields are kept in their order.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the Oracle type of the value.
+    pub fn oracle_type(&self) -> dpiOracleTypeNum {
+        match self {
+            JsonValue::Null => DPI_ORACLE_TYPE_NONE,
+            JsonValue::Boolean(_) => DPI_ORACLE_TYPE_BOOLEAN,
+            JsonValue::Number(_) => DPI_ORACLE_TYPE_NUMBER,
+            JsonValue::Float(_) => DPI_ORACLE_TYPE_NATIVE_FLOAT,
+            JsonValue::Double(_) => DPI_ORACLE_TYPE_NATIVE_DOUBLE,
+            JsonValue::String(_) => DPI_ORACLE_TYPE_VARCHAR,
+            JsonValue::Raw(_) => DPI_ORACLE_TYPE_RAW,
+            JsonValue::Id(_) => DPI_ORACLE_TYPE_JSON_ID,
+            JsonValue::Date(_) => DPI_ORACLE_TYPE_DATE,
+            JsonValue::Timestamp(_) => DPI_ORACLE_TYPE_TIMESTAMP,
+            JsonValue::TimestampTz(_) => DPI_ORACLE_TYPE_TIMESTAMP_TZ,
+            JsonValue::IntervalDS(_) => DPI_ORACLE_TYPE_INTERVAL_DS,
+            JsonValue::IntervalYM(_) => DPI_ORACLE_TYPE_INTERVAL_YM,
+            JsonValue::Vector(_) => DPI_ORACLE_TYPE_VECTOR,
+            JsonValue::Array(_) => DPI_ORACLE_TYPE_JSON_ARRAY,
+            JsonValue::Object(_) => DPI_ORACLE_TYPE_JSON_OBJECT,
+        }
+    }
+
+    /// Returns the value of the field `name` when the value is an object.
+    pub fn get(&self, name: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|f| f.0 == name).map(|f| &f.1),
+            _ => None,
+        }
+    }
+
+    /// Converts a tree of nodes got with `DPI_JSON_OPT_NUMBER_AS_STRING`.
+    pub(crate) unsafe fn from_dpi_node(node: &dpiJsonNode) -> Result<JsonValue> {
+        let value = &*node.value;
+        match (node.oracleTypeNum, node.nativeTypeNum) {
+            (_, DPI_NATIVE_TYPE_NULL) => Ok(JsonValue::Null),
+            (_, DPI_NATIVE_TYPE_BOOLEAN) => Ok(JsonValue::Boolean(value.asBoolean != 0)),
+            (DPI_ORACLE_TYPE_NUMBER, DPI_NATIVE_TYPE_BYTES) => Ok(JsonValue::Number(
+                String::from_utf8_lossy(bytes_slice(&value.asBytes)).into_owned(),
+            )),
+            (DPI_ORACLE_TYPE_NUMBER, DPI_NATIVE_TYPE_DOUBLE)
+            | (DPI_ORACLE_TYPE_NATIVE_DOUBLE, _) => Ok(JsonValue::Double(value.asDouble)),
+            (DPI_ORACLE_TYPE_NATIVE_FLOAT, _) => Ok(JsonValue::Float(value.asFloat)),
+            (DPI_ORACLE_TYPE_VARCHAR, _) => Ok(JsonValue::String(
+                String::from_utf8_lossy(bytes_slice(&value.asBytes)).into_owned(),
+            )),
+            (DPI_ORACLE_TYPE_RAW, _) => Ok(JsonValue::Raw(bytes_slice(&value.asBytes).to_vec())),
+            (DPI_ORACLE_TYPE_JSON_ID, _) => Ok(JsonValue::Id(bytes_slice(&value.asBytes).to_vec())),
+            (DPI_ORACLE_TYPE_DATE, _) => Ok(JsonValue::Date(value.asTimestamp.into())),
+            (DPI_ORACLE_TYPE_TIMESTAMP, _) => Ok(JsonValue::Timestamp(value.asTimestamp.into())),
+            (DPI_ORACLE_TYPE_TIMESTAMP_TZ, _) => {
+                let ts = Timestamp::from(value.asTimestamp);
+                Ok(JsonValue::TimestampTz(
+                    ts.and_tz_offset(ts.tz_hour_offset, ts.tz_minute_offset),
+                ))
+            }
+            (DPI_ORACLE_TYPE_INTERVAL_DS, _) => {
+                Ok(JsonValue::IntervalDS(value.asIntervalDS.into()))
+            }
+            (DPI_ORACLE_TYPE_INTERVAL_YM, _) => {
+                Ok(JsonValue::IntervalYM(value.asIntervalYM.into()))
+            }
+            (DPI_ORACLE_TYPE_VECTOR, DPI_NATIVE_TYPE_BYTES) => Ok(JsonValue::Vector(
+                vector::decode_image(bytes_slice(&value.asBytes))?,
+            )),
+            (DPI_ORACLE_TYPE_VECTOR, DPI_NATIVE_TYPE_VECTOR) => {
+                let mut info = dpiVectorInfo::default();
+                chkerr!(
+                    crate::Context::get()?,
+                    dpiVector_getValue(value.asVector, &mut info)
+                );
+                Ok(JsonValue::Vector(Vector::from_dpi(&info)?))
+            }
+            (DPI_ORACLE_TYPE_JSON_ARRAY, _) => {
+                let array = &value.asJsonArray;
+                let elements = node_slice(array.elements, array.numElements);
+                Ok(JsonValue::Array(
+                    elements
+                        .iter()
+                        .map(|node| JsonValue::from_dpi_node(node))
+                        .collect::<Result<_>>()?,
+                ))
+            }
+            (DPI_ORACLE_TYPE_JSON_OBJECT, _) => {
+                let object = &value.asJsonObject;
+                let fields = node_slice(object.fields, object.numFields);
+                let mut values = Vec::with_capacity(fields.len());
+                for (i, node) in fields.iter().enumerate() {
+                    let name = slice::from_raw_parts(
+                        *object.fieldNames.add(i) as *const u8,
+                        *object.fieldNameLengths.add(i) as usize,
+                    );
+                    values.push((
+                        String::from_utf8_lossy(name).into_owned(),
+                        JsonValue::from_dpi_node(node)?,
+                    ));
+                }
+                Ok(JsonValue::Object(values))
+            }
+            (oratype, native) => Err(Error::UnsupportedType(format!(
+                "JSON node of Oracle type {} and native type {}",
+                oratype, native
+            ))),
+        }
+    }
+}
+
+unsafe fn node_slice<'a>(ptr: *const dpiJsonNode, len: u32) -> &'a [dpiJsonNode] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len as usize)
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(value: bool) -> JsonValue {
+        JsonValue::Boolean(value)
+    }
+}
+
+impl From<i64> for JsonValue {
+    fn from(value: i64) -> JsonValue {
+        JsonValue::Number(value.to_string())
+    }
+}
+
+impl From<f64> for JsonValue {
+    fn from(value: f64) -> JsonValue {
+        JsonValue::Double(value)
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> JsonValue {
+        JsonValue::String(value.into())
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(value: String) -> JsonValue {
+        JsonValue::String(value)
+    }
+}
+
+impl From<Vec<JsonValue>> for JsonValue {
+    fn from(value: Vec<JsonValue>) -> JsonValue {
+        JsonValue::Array(value)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod ser {
+    use super::JsonValue;
+    use crate::object::ser::{interval_ds, interval_ym, timestamp};
+    use serde::ser::{Error, Serialize, SerializeMap, SerializeSeq, Serializer};
+    use std::fmt::Write;
+
+    /// Arrays are serialized as sequences and objects as maps. `NUMBER`
+    /// values are serialized as `i64`, `u64` or `f64`, whichever first
+    /// holds them. `RAW` values and ids are hexadecimal strings, datetimes
+    /// are ISO 8601 strings and intervals are ISO 8601 durations. Vectors
+    /// are not serialized.
+    impl Serialize for JsonValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                JsonValue::Null => serializer.serialize_unit(),
+                JsonValue::Boolean(v) => serializer.serialize_bool(*v),
+                JsonValue::Number(v) => {
+                    if let Ok(v) = v.parse::<i64>() {
+                        serializer.serialize_i64(v)
+                    } else if let Ok(v) = v.parse::<u64>() {
+                        serializer.serialize_u64(v)
+                    } else if let Ok(v) = v.parse::<f64>() {
+                        serializer.serialize_f64(v)
+                    } else {
+                        Err(S::Error::custom(format!("invalid number {}", v)))
+                    }
+                }
+                JsonValue::Float(v) => serializer.serialize_f32(*v),
+                JsonValue::Double(v) => serializer.serialize_f64(*v),
+                JsonValue::String(v) => serializer.serialize_str(v),
+                JsonValue::Raw(v) | JsonValue::Id(v) => {
+                    let mut hex = String::with_capacity(v.len() * 2);
+                    for b in v {
+                        let _ = write!(hex, "{:02X}", b);
+                    }
+                    serializer.serialize_str(&hex)
+                }
+                JsonValue::Date(v) | JsonValue::Timestamp(v) | JsonValue::TimestampTz(v) => {
+                    serializer.serialize_str(&timestamp(v))
+                }
+                JsonValue::IntervalDS(v) => serializer.serialize_str(&interval_ds(v)),
+                JsonValue::IntervalYM(v) => serializer.serialize_str(&interval_ym(v)),
+                JsonValue::Vector(_) => Err(S::Error::custom("vectors cannot be serialized")),
+                JsonValue::Array(elements) => {
+                    let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                    for value in elements {
+                        seq.serialize_element(value)?;
+                    }
+                    seq.end()
+                }
+                JsonValue::Object(fields) => {
+                    let mut map = serializer.serialize_map(Some(fields.len()))?;
+                    for (name, value) in fields {
+                        map.serialize_entry(name, value)?;
+                    }
+                    map.end()
+                }
+            }
+        }
+    }
+}
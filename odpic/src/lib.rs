@@ -0,0 +1,75 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+#![doc = include_str!("../README.md")]
+
+// Functions which may be blocked by network round-trips are in the blocking
+// module when the `separate_blocking` feature of odpic-sys is enabled.
+mod ffi {
+    #[allow(unused_imports)]
+    pub use odpic_sys::blocking::*;
+    pub use odpic_sys::*;
+}
+
+/// Calls an ODPI-C function and returns the last error on failure.
+macro_rules! chkerr {
+    ($ctxt:expr, $code:expr) => {{
+        #[allow(unused_unsafe)]
+        if unsafe { $code } != $crate::ffi::DPI_SUCCESS {
+            return Err($crate::Error::DpiError($ctxt.last_error()));
+        }
+    }};
+}
+
+pub mod aq;
+mod array_bind;
+mod batch;
+mod conn;
+mod context;
+mod cursor;
+mod datetime;
+mod error;
+mod json;
+pub mod lob;
+mod number;
+pub mod object;
+pub mod oson;
+mod out_bind;
+pub mod qbe;
+pub mod query_cache;
+mod scroll;
+pub mod soda;
+mod sql_type;
+mod statement;
+pub mod subscr;
+pub mod tpc;
+mod value;
+mod var;
+pub mod vector;
+
+pub use array_bind::ArrayBind;
+pub use batch::{BatchError, BatchFailure, BatchOptions, BatchReport};
+pub use conn::Connection;
+pub use context::Context;
+pub use cursor::Cursor;
+pub use datetime::{IntervalDS, IntervalYM, Timestamp};
+pub use error::{DpiError, Error, Result};
+pub use json::JsonValue;
+pub use out_bind::OutBind;
+pub use scroll::ScrollableStatement;
+pub use sql_type::{FromSql, SqlData, SqlType, ToSql, ToSqlNull};
+pub use statement::{BindIndex, ColumnIndex, ColumnInfo, Row, Rows, Statement, StatementInfo};
+pub use value::SqlValue;
+pub use vector::Vector;
@@ -0,0 +1,950 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! LOB handles and streams
+//!
+//! [`LobStream`] reads and writes `BLOB`, `CLOB`, `NCLOB` and `BFILE` values
+//! through [`std::io`] traits. [`BFile`] accesses files on the database
+//! server. [`TempLob`] owns a temporary LOB.
+//!
+//! ```no_run
+//! # use odpic::lob::{Lob, LobStream};
+//! # fn copy_clob(lob: Lob) -> odpic::Result<String> {
+//! use std::io::Read;
+//!
+//! let mut stream = LobStream::new(lob)?;
+//! let mut text = String::new();
+//! stream.read_to_string(&mut text)?;
+//! # Ok(text)
+//! # }
+//! ```
+
+use crate::ffi::*;
+use crate::{Connection, Context, Error, Result};
+use std::cmp;
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::ops::Deref;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const DEFAULT_BUFFER_SIZE: u64 = 64 * 1024;
+
+/// LOB handle
+///
+/// This holds a reference to [`dpiLob`], which is released when it is
+/// dropped.
+#[derive(Debug)]
+pub struct Lob {
+    ctxt: &'static Context,
+    handle: *mut dpiLob,
+}
+
+impl Lob {
+    /// Creates a LOB from a handle, adding a reference to it.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid LOB handle.
+    pub unsafe fn from_raw(handle: *mut dpiLob) -> Result<Lob> {
+        let ctxt = Context::get()?;
+        chkerr!(ctxt, dpiLob_addRef(handle));
+        Ok(Lob { ctxt, handle })
+    }
+
+    pub fn handle(&self) -> *mut dpiLob {
+        self.handle
+    }
+
+    /// Returns `DPI_ORACLE_TYPE_CLOB`, `DPI_ORACLE_TYPE_NCLOB`,
+    /// `DPI_ORACLE_TYPE_BLOB` or `DPI_ORACLE_TYPE_BFILE`.
+    pub fn oracle_type(&self) -> Result<dpiOracleTypeNum> {
+        let mut oratype = 0;
+        chkerr!(self.ctxt, dpiLob_getType(self.handle, &mut oratype));
+        Ok(oratype)
+    }
+
+    /// Returns true for `CLOB` and `NCLOB`, whose sizes and offsets are
+    /// in characters.
+    pub fn is_char(&self) -> Result<bool> {
+        Ok(matches!(
+            self.oracle_type()?,
+            DPI_ORACLE_TYPE_CLOB | DPI_ORACLE_TYPE_NCLOB
+        ))
+    }
+
+    /// Returns the size of the LOB, in characters for `CLOB` and `NCLOB` and
+    /// in bytes for others.
+    pub fn size(&self) -> Result<u64> {
+        let mut size = 0;
+        chkerr!(self.ctxt, dpiLob_getSize(self.handle, &mut size));
+        Ok(size)
+    }
+
+    /// Returns the chunk size in bytes. Reading and writing in multiples of
+    /// this size improve performance.
+    pub fn chunk_size(&self) -> Result<u32> {
+        let mut size = 0;
+        chkerr!(self.ctxt, dpiLob_getChunkSize(self.handle, &mut size));
+        Ok(size)
+    }
+
+    /// Returns the size of buffers in bytes needed to read `size_in_chars`
+    /// characters.
+    pub fn buffer_size(&self, size_in_chars: u64) -> Result<u64> {
+        let mut size = 0;
+        chkerr!(
+            self.ctxt,
+            dpiLob_getBufferSize(self.handle, size_in_chars, &mut size)
+        );
+        Ok(size)
+    }
+
+    /// Reads data at the 1-based `offset` into `buf` and returns the number
+    /// of bytes read. `offset` and `amount` are in characters for `CLOB`
+    /// and `NCLOB`. `buf` must be large enough for `amount`. See
+    /// [`Lob::buffer_size`].
+    pub fn read_bytes(&self, offset: u64, amount: u64, buf: &mut [u8]) -> Result<usize> {
+        let mut len = buf.len() as u64;
+        chkerr!(
+            self.ctxt,
+            dpiLob_readBytes(
+                self.handle,
+                offset,
+                amount,
+                buf.as_mut_ptr() as *mut c_char,
+                &mut len
+            )
+        );
+        Ok(len as usize)
+    }
+
+    /// Writes `data` at the 1-based `offset`, which is in characters for
+    /// `CLOB` and `NCLOB`.
+    pub fn write_bytes(&self, offset: u64, data: &[u8]) -> Result<()> {
+        chkerr!(
+            self.ctxt,
+            dpiLob_writeBytes(
+                self.handle,
+                offset,
+                data.as_ptr() as *const c_char,
+                data.len() as u64
+            )
+        );
+        Ok(())
+    }
+
+    /// Changes the size of the LOB to `new_size`, which must not be greater
+    /// than the current size.
+    pub fn trim(&self, new_size: u64) -> Result<()> {
+        chkerr!(self.ctxt, dpiLob_trim(self.handle, new_size));
+        Ok(())
+    }
+
+    /// Creates an independent copy of the LOB.
+    pub fn copy(&self) -> Result<Lob> {
+        let mut handle = ptr::null_mut();
+        chkerr!(self.ctxt, dpiLob_copy(self.handle, &mut handle));
+        Ok(Lob {
+            ctxt: self.ctxt,
+            handle,
+        })
+    }
+}
+
+impl Clone for Lob {
+    fn clone(&self) -> Lob {
+        unsafe { dpiLob_addRef(self.handle) };
+        Lob {
+            ctxt: self.ctxt,
+            handle: self.handle,
+        }
+    }
+}
+
+impl Drop for Lob {
+    fn drop(&mut self) {
+        unsafe { dpiLob_release(self.handle) };
+    }
+}
+
+/// Buffered stream over a LOB
+///
+/// Positions used by [`Seek`] are in characters for `CLOB` and `NCLOB` and
+/// in bytes for others, as offsets of ODPI-C LOB functions. A character
+/// here is a UTF-16 code unit, which is how Oracle counts characters in
+/// `CLOB`s. Reads from the database are aligned to the LOB chunk size,
+/// which is converted from bytes to characters for `CLOB` and `NCLOB`.
+///
+/// Data of `CLOB` and `NCLOB` are in UTF-8. [`Read::read`] returns only
+/// complete characters, so multi-byte characters are never split across
+/// reads. It fails with [`io::ErrorKind::InvalidInput`] when the buffer
+/// cannot hold the next character. Data passed to [`Write::write`] may
+/// split characters. Incomplete characters are kept until the rest
+/// arrives.
+///
+/// Buffered data are written when the buffer is full, [`Write::flush`] or
+/// [`Seek::seek`] is called. Errors while writing them on drop are ignored.
+/// Call [`LobStream::into_inner`] to catch them.
+#[derive(Debug)]
+pub struct LobStream {
+    lob: Lob,
+    is_char: bool,
+    chunk_size: u64,
+    buf_units: u64,
+    write_capacity: usize,
+    // position where the next read or write to the database starts, 0-based
+    lob_pos: u64,
+    read_buf: Vec<u8>,
+    read_pos: usize,
+    write_buf: Vec<u8>,
+}
+
+impl LobStream {
+    pub fn new(lob: Lob) -> Result<LobStream> {
+        let oratype = lob.oracle_type()?;
+        let is_char = matches!(oratype, DPI_ORACLE_TYPE_CLOB | DPI_ORACLE_TYPE_NCLOB);
+        let chunk_size = if oratype == DPI_ORACLE_TYPE_BFILE {
+            DEFAULT_BUFFER_SIZE
+        } else {
+            lob.chunk_size()?.into()
+        };
+        let (chunk_size, buf_units) = stream_units(is_char, chunk_size);
+        let write_capacity = if is_char {
+            lob.buffer_size(buf_units)?
+        } else {
+            buf_units
+        } as usize;
+        Ok(LobStream {
+            lob,
+            is_char,
+            chunk_size,
+            buf_units,
+            write_capacity,
+            lob_pos: 0,
+            read_buf: Vec::new(),
+            read_pos: 0,
+            write_buf: Vec::new(),
+        })
+    }
+
+    pub fn get_ref(&self) -> &Lob {
+        &self.lob
+    }
+
+    /// Writes buffered data and returns the LOB.
+    ///
+    /// This fails when an incomplete UTF-8 character remains in the buffer.
+    pub fn into_inner(mut self) -> Result<Lob> {
+        self.flush_write()?;
+        self.check_no_pending()?;
+        let lob = self.lob.clone();
+        self.read_buf.clear();
+        Ok(lob)
+    }
+
+    fn units(&self, data: &[u8]) -> u64 {
+        units(self.is_char, data)
+    }
+
+    fn complete_len(&self, data: &[u8]) -> io::Result<usize> {
+        complete_len(self.is_char, data)
+    }
+
+    fn check_no_pending(&self) -> Result<()> {
+        if self.write_buf.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete UTF-8 character at the end of written data",
+            )
+            .into())
+        }
+    }
+
+    fn discard_read_buf(&mut self) {
+        let unread = self.units(&self.read_buf[self.read_pos..]);
+        self.lob_pos -= unread;
+        self.read_buf.clear();
+        self.read_pos = 0;
+    }
+
+    fn flush_write(&mut self) -> io::Result<()> {
+        let lob = &self.lob;
+        write_units(
+            self.is_char,
+            &mut self.lob_pos,
+            &mut self.write_buf,
+            |offset, data| lob.write_bytes(offset, data),
+        )
+    }
+
+    fn position(&self) -> u64 {
+        if self.write_buf.is_empty() {
+            self.lob_pos - self.units(&self.read_buf[self.read_pos..])
+        } else {
+            let len = self.complete_len(&self.write_buf).unwrap_or(0);
+            self.lob_pos + self.units(&self.write_buf[..len])
+        }
+    }
+}
+
+impl Read for LobStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let is_char = self.is_char;
+        let avail = self.fill_buf()?;
+        let mut len = cmp::min(avail.len(), buf.len());
+        if is_char && len < avail.len() {
+            // move back to the start of a UTF-8 character
+            while len > 0 && avail[len] & 0xC0 == 0x80 {
+                len -= 1;
+            }
+            if len == 0 && !buf.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "buffer too small to hold a UTF-8 character",
+                ));
+            }
+        }
+        buf[..len].copy_from_slice(&avail[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for LobStream {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.read_pos >= self.read_buf.len() {
+            self.flush_write()?;
+            self.check_no_pending()?;
+            // end the read at a chunk boundary
+            let amount = self.buf_units - self.lob_pos % self.chunk_size;
+            let buf_size = if self.is_char {
+                self.lob.buffer_size(amount)?
+            } else {
+                amount
+            };
+            self.read_pos = 0;
+            let lob = &self.lob;
+            read_units(
+                self.is_char,
+                &mut self.lob_pos,
+                &mut self.read_buf,
+                buf_size as usize,
+                |offset, buf| lob.read_bytes(offset, amount, buf),
+            )?;
+        }
+        Ok(&self.read_buf[self.read_pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.read_pos = cmp::min(self.read_pos + amt, self.read_buf.len());
+    }
+}
+
+impl Write for LobStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.read_pos < self.read_buf.len() {
+            self.discard_read_buf();
+        }
+        let (lob, lob_pos, is_char) = (&self.lob, &mut self.lob_pos, self.is_char);
+        write_buffered(&mut self.write_buf, self.write_capacity, buf, |data| {
+            write_units(is_char, lob_pos, data, |offset, data| {
+                lob.write_bytes(offset, data)
+            })
+        })
+    }
+
+    /// Writes buffered data except an incomplete UTF-8 character at the end.
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_write()
+    }
+}
+
+impl Seek for LobStream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.flush_write()?;
+        self.check_no_pending()?;
+        let (base, offset) = match pos {
+            SeekFrom::Start(pos) => (pos, 0),
+            SeekFrom::Current(offset) => (self.position(), offset),
+            SeekFrom::End(offset) => (self.lob.size()?, offset),
+        };
+        let pos = base.checked_add_signed(offset).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        self.read_buf.clear();
+        self.read_pos = 0;
+        self.lob_pos = pos;
+        Ok(pos)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.position())
+    }
+}
+
+impl Drop for LobStream {
+    fn drop(&mut self) {
+        let _ = self.flush_write();
+    }
+}
+
+// Returns the chunk size and the buffer size of LobStream in units of
+// positions, converting the chunk size in bytes to UTF-16 code units for
+// CLOB and NCLOB. Oracle stores them in a fixed-width two-byte character
+// set when the database character set is multi-byte.
+fn stream_units(is_char: bool, chunk_size_in_bytes: u64) -> (u64, u64) {
+    let unit_size = if is_char { 2 } else { 1 };
+    let chunk_size = cmp::max(chunk_size_in_bytes / unit_size, 1);
+    let buf_units = cmp::max(DEFAULT_BUFFER_SIZE / unit_size / chunk_size, 1) * chunk_size;
+    (chunk_size, buf_units)
+}
+
+// Returns the number of units in data which consists of complete
+// characters for CLOB.
+fn units(is_char: bool, data: &[u8]) -> u64 {
+    if is_char {
+        str::from_utf8(data)
+            .map(|s| s.encode_utf16().count() as u64)
+            .unwrap_or(0)
+    } else {
+        data.len() as u64
+    }
+}
+
+// Returns the length of the leading complete UTF-8 characters.
+fn complete_len(is_char: bool, data: &[u8]) -> io::Result<usize> {
+    if !is_char {
+        return Ok(data.len());
+    }
+    match str::from_utf8(data) {
+        Ok(_) => Ok(data.len()),
+        Err(err) if err.error_len().is_none() => Ok(err.valid_up_to()),
+        Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+    }
+}
+
+// Reads data at `lob_pos` into `buf` by `read`, which takes the 1-based
+// offset, and moves `lob_pos` past complete characters in it.
+fn read_units<F>(
+    is_char: bool,
+    lob_pos: &mut u64,
+    buf: &mut Vec<u8>,
+    buf_size: usize,
+    read: F,
+) -> io::Result<()>
+where
+    F: FnOnce(u64, &mut [u8]) -> Result<usize>,
+{
+    buf.resize(buf_size, 0);
+    let len = read(*lob_pos + 1, buf)?;
+    buf.truncate(len);
+    let complete = complete_len(is_char, buf)?;
+    if complete == 0 && len > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "incomplete UTF-8 character read from LOB",
+        ));
+    }
+    // An incomplete character at the end is read again next time.
+    buf.truncate(complete);
+    *lob_pos += units(is_char, buf);
+    Ok(())
+}
+
+// Writes complete characters in `buf` at `lob_pos` by `write`, which takes
+// the 1-based offset, and removes them from `buf`.
+fn write_units<F>(is_char: bool, lob_pos: &mut u64, buf: &mut Vec<u8>, write: F) -> io::Result<()>
+where
+    F: FnOnce(u64, &[u8]) -> Result<()>,
+{
+    let len = complete_len(is_char, buf)?;
+    if len == 0 {
+        return Ok(());
+    }
+    write(*lob_pos + 1, &buf[..len])?;
+    *lob_pos += units(is_char, &buf[..len]);
+    buf.drain(..len);
+    Ok(())
+}
+
+// Appends `data` to `buf` and flushes it when it reaches `capacity`. `data`
+// is removed from `buf` again when the flush fails, so that nothing is
+// written on errors as `Write::write` requires.
+fn write_buffered<F>(buf: &mut Vec<u8>, capacity: usize, data: &[u8], flush: F) -> io::Result<usize>
+where
+    F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+{
+    let len = buf.len();
+    buf.extend_from_slice(data);
+    if buf.len() >= capacity {
+        if let Err(err) = flush(buf) {
+            buf.truncate(len);
+            return Err(err);
+        }
+    }
+    Ok(data.len())
+}
+
+/// `BFILE` locator
+///
+/// A locator refers to a file in a directory object on the database server.
+/// The file must be opened by [`BFile::open`] before reading it.
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # use odpic::lob::{BFile, LobStream};
+/// # fn read_file(conn: &Connection) -> odpic::Result<Vec<u8>> {
+/// use std::io::Read;
+///
+/// let bfile = BFile::new(conn, "DATA_DIR", "data.bin")?;
+/// let file = bfile.open()?;
+/// let mut data = Vec::new();
+/// LobStream::new(file.lob().clone())?.read_to_end(&mut data)?;
+/// file.close()?;
+/// # Ok(data)
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BFile {
+    lob: Lob,
+}
+
+impl BFile {
+    /// Creates a locator which refers to `file_name` in `directory`. It is
+    /// used to bind `BFILE` values.
+    pub fn new(conn: &Connection, directory: &str, file_name: &str) -> Result<BFile> {
+        let ctxt = conn.ctxt();
+        let mut var = ptr::null_mut();
+        let mut data = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiConn_newVar(
+                conn.handle(),
+                DPI_ORACLE_TYPE_BFILE,
+                DPI_NATIVE_TYPE_LOB,
+                1,
+                0,
+                0,
+                0,
+                ptr::null_mut(),
+                &mut var,
+                &mut data
+            )
+        );
+        // The locator in the variable lives after the variable is released
+        // because a reference is added to it.
+        let lob = unsafe { Lob::from_raw(dpiData_getLOB(data)) };
+        unsafe { dpiVar_release(var) };
+        let bfile = BFile { lob: lob? };
+        bfile.set_directory_and_file_name(directory, file_name)?;
+        Ok(bfile)
+    }
+
+    pub fn lob(&self) -> &Lob {
+        &self.lob
+    }
+
+    pub fn into_lob(self) -> Lob {
+        self.lob
+    }
+
+    /// Returns the directory alias and the file name.
+    pub fn directory_and_file_name(&self) -> Result<(String, String)> {
+        let mut dir = ptr::null();
+        let mut dir_len = 0;
+        let mut file = ptr::null();
+        let mut file_len = 0;
+        chkerr!(
+            self.lob.ctxt,
+            dpiLob_getDirectoryAndFileName(
+                self.lob.handle,
+                &mut dir,
+                &mut dir_len,
+                &mut file,
+                &mut file_len
+            )
+        );
+        unsafe { Ok((to_string(dir, dir_len), to_string(file, file_len))) }
+    }
+
+    /// Changes the directory alias and the file name the locator refers to.
+    ///
+    /// This fails when the file is open.
+    pub fn set_directory_and_file_name(&self, directory: &str, file_name: &str) -> Result<()> {
+        chkerr!(
+            self.lob.ctxt,
+            dpiLob_setDirectoryAndFileName(
+                self.lob.handle,
+                directory.as_ptr() as *const c_char,
+                directory.len() as u32,
+                file_name.as_ptr() as *const c_char,
+                file_name.len() as u32
+            )
+        );
+        Ok(())
+    }
+
+    /// Returns true when the file exists on the database server.
+    pub fn exists(&self) -> Result<bool> {
+        let mut exists = 0;
+        chkerr!(
+            self.lob.ctxt,
+            dpiLob_getFileExists(self.lob.handle, &mut exists)
+        );
+        Ok(exists != 0)
+    }
+
+    /// Returns true when the file is open.
+    pub fn is_open(&self) -> Result<bool> {
+        let mut is_open = 0;
+        chkerr!(
+            self.lob.ctxt,
+            dpiLob_getIsResourceOpen(self.lob.handle, &mut is_open)
+        );
+        Ok(is_open != 0)
+    }
+
+    /// Opens the file and returns a guard which closes it on drop.
+    ///
+    /// Each open file holds a file handle on the server until it is closed.
+    pub fn open(&self) -> Result<BFileGuard<'_>> {
+        chkerr!(self.lob.ctxt, dpiLob_openResource(self.lob.handle));
+        Ok(BFileGuard { bfile: self })
+    }
+}
+
+impl TryFrom<Lob> for BFile {
+    type Error = Error;
+
+    fn try_from(lob: Lob) -> Result<BFile> {
+        if lob.oracle_type()? == DPI_ORACLE_TYPE_BFILE {
+            Ok(BFile { lob })
+        } else {
+            Err(Error::InvalidOperation("LOB is not a BFILE".into()))
+        }
+    }
+}
+
+impl From<BFile> for Lob {
+    fn from(bfile: BFile) -> Lob {
+        bfile.lob
+    }
+}
+
+/// Guard of an open `BFILE`
+///
+/// The file is closed when this is dropped. Errors on drop are ignored. Use
+/// [`BFileGuard::close`] to check them.
+#[derive(Debug)]
+pub struct BFileGuard<'a> {
+    bfile: &'a BFile,
+}
+
+impl BFileGuard<'_> {
+    /// Closes the file.
+    pub fn close(self) -> Result<()> {
+        let lob = &self.bfile.lob;
+        mem::forget(self);
+        chkerr!(lob.ctxt, dpiLob_closeResource(lob.handle));
+        Ok(())
+    }
+}
+
+impl Deref for BFileGuard<'_> {
+    type Target = BFile;
+
+    fn deref(&self) -> &BFile {
+        self.bfile
+    }
+}
+
+impl Drop for BFileGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { dpiLob_closeResource(self.bfile.lob.handle) };
+    }
+}
+
+/// Temporary LOB
+///
+/// Temporary LOBs reside in the `TEMP` tablespace until they are freed. This
+/// frees the LOB by [`TempLob::free`] or on drop, even when clones of the
+/// inner [`Lob`] remain.
+///
+/// [`Connection::temp_lob_count`] counts temporary LOBs created via a
+/// connection and not freed yet.
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # use odpic::lob::TempLob;
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let lob = TempLob::from_text(conn, "hello")?;
+/// // bind lob.handle() here
+/// lob.free()?;
+/// assert_eq!(conn.temp_lob_count(), 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TempLob {
+    lob: Lob,
+    counter: Arc<AtomicUsize>,
+}
+
+impl TempLob {
+    /// Creates an empty temporary LOB. `oratype` is one of
+    /// `DPI_ORACLE_TYPE_CLOB`, `DPI_ORACLE_TYPE_NCLOB` and
+    /// `DPI_ORACLE_TYPE_BLOB`.
+    pub fn new(conn: &Connection, oratype: dpiOracleTypeNum) -> Result<TempLob> {
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            conn.ctxt(),
+            dpiConn_newTempLob(conn.handle(), oratype, &mut handle)
+        );
+        Ok(TempLob::from_lob(
+            Lob {
+                ctxt: conn.ctxt(),
+                handle,
+            },
+            conn.temp_lob_counter(),
+        ))
+    }
+
+    /// Creates a temporary `BLOB` containing `data`.
+    pub fn from_bytes(conn: &Connection, data: &[u8]) -> Result<TempLob> {
+        let lob = TempLob::new(conn, DPI_ORACLE_TYPE_BLOB)?;
+        lob.set_from_bytes(data)?;
+        Ok(lob)
+    }
+
+    /// Creates a temporary `CLOB` containing `text`.
+    pub fn from_text(conn: &Connection, text: &str) -> Result<TempLob> {
+        let lob = TempLob::new(conn, DPI_ORACLE_TYPE_CLOB)?;
+        lob.set_from_bytes(text.as_bytes())?;
+        Ok(lob)
+    }
+
+    /// Creates a temporary LOB filled with data read from `reader`. Data
+    /// must be in UTF-8 for `CLOB` and `NCLOB`.
+    pub fn from_reader<R>(
+        conn: &Connection,
+        oratype: dpiOracleTypeNum,
+        mut reader: R,
+    ) -> Result<TempLob>
+    where
+        R: Read,
+    {
+        let lob = TempLob::new(conn, oratype)?;
+        let mut stream = LobStream::new(lob.lob.clone())?;
+        io::copy(&mut reader, &mut stream)?;
+        stream.into_inner()?;
+        Ok(lob)
+    }
+
+    fn from_lob(lob: Lob, counter: &Arc<AtomicUsize>) -> TempLob {
+        counter.fetch_add(1, Ordering::Relaxed);
+        TempLob {
+            lob,
+            counter: counter.clone(),
+        }
+    }
+
+    fn set_from_bytes(&self, data: &[u8]) -> Result<()> {
+        chkerr!(
+            self.lob.ctxt,
+            dpiLob_setFromBytes(
+                self.lob.handle,
+                data.as_ptr() as *const c_char,
+                data.len() as u64
+            )
+        );
+        Ok(())
+    }
+
+    /// Creates an independent temporary copy of the LOB.
+    pub fn copy(&self) -> Result<TempLob> {
+        Ok(TempLob::from_lob(self.lob.copy()?, &self.counter))
+    }
+
+    /// Frees the temporary LOB.
+    pub fn free(self) -> Result<()> {
+        let this = mem::ManuallyDrop::new(self);
+        this.counter.fetch_sub(1, Ordering::Relaxed);
+        // Move out the fields to release them when they go out of scope.
+        let lob = unsafe { ptr::read(&this.lob) };
+        let _counter = unsafe { ptr::read(&this.counter) };
+        chkerr!(lob.ctxt, dpiLob_close(lob.handle));
+        Ok(())
+    }
+}
+
+impl Deref for TempLob {
+    type Target = Lob;
+
+    fn deref(&self) -> &Lob {
+        &self.lob
+    }
+}
+
+impl Drop for TempLob {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::Relaxed);
+        unsafe { dpiLob_close(self.lob.handle) };
+    }
+}
+
+unsafe fn to_string(ptr: *const c_char, len: u32) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        String::from_utf8_lossy(slice::from_raw_parts(ptr as *const u8, len as usize)).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads `amount` UTF-16 code units of `text` as a CLOB does, except that
+    // a surrogate pair split at the end is returned as an incomplete UTF-8
+    // character.
+    fn read_clob(text: &str, amount: usize, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let start = cmp::min(offset as usize - 1, units.len());
+        let end = cmp::min(start + amount, units.len());
+        let mut data = String::from_utf16_lossy(&units[start..end]).into_bytes();
+        if end > start && char::decode_utf16([units[end - 1]]).any(|c| c.is_err()) {
+            data.truncate(data.len() - 3);
+            let c = char::decode_utf16(units[end - 1..].iter().copied())
+                .next()
+                .unwrap()
+                .unwrap();
+            data.extend_from_slice(&c.to_string().as_bytes()[..2]);
+        }
+        buf[..data.len()].copy_from_slice(&data);
+        Ok(data.len())
+    }
+
+    #[test]
+    fn chunk_sizes_in_units() {
+        assert_eq!(stream_units(false, 8132), (8132, 65056));
+        assert_eq!(stream_units(true, 8132), (4066, 32528));
+        assert_eq!(stream_units(true, 1), (1, 32768));
+        assert_eq!(stream_units(false, 0), (1, 65536));
+    }
+
+    #[test]
+    fn carry_over_incomplete_characters() {
+        let text = "ab\u{1F600}cd\u{20AC}";
+        let mut lob_pos = 0;
+        let mut buf = Vec::new();
+        let mut read = Vec::new();
+        let mut positions = Vec::new();
+        loop {
+            read_units(true, &mut lob_pos, &mut buf, 12, |offset, buf| {
+                read_clob(text, 3, offset, buf)
+            })
+            .unwrap();
+            if buf.is_empty() {
+                break;
+            }
+            read.extend_from_slice(&buf);
+            positions.push(lob_pos);
+        }
+        assert_eq!(str::from_utf8(&read).unwrap(), text);
+        assert_eq!(positions, [2, 5, 7]);
+    }
+
+    #[test]
+    fn keep_data_on_failed_writes() {
+        let mut lob_pos = 0;
+        let mut buf = Vec::new();
+        let mut written = Vec::new();
+        let mut write = |buf: &mut Vec<u8>, fail: bool| {
+            write_units(true, &mut lob_pos, buf, |offset, data| {
+                if fail {
+                    return Err(Error::InvalidOperation("write failed".into()));
+                }
+                written.push((offset, data.to_vec()));
+                Ok(())
+            })
+        };
+        assert_eq!(
+            write_buffered(&mut buf, 4, b"ab", |b| write(b, true)).unwrap(),
+            2
+        );
+        assert_eq!(buf, b"ab");
+        let err = write_buffered(&mut buf, 4, b"c\xe2\x82", |b| write(b, true)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(buf, b"ab");
+        // A retry writes the data once. The incomplete character is kept.
+        assert_eq!(
+            write_buffered(&mut buf, 4, b"c\xe2\x82", |b| write(b, false)).unwrap(),
+            3
+        );
+        assert_eq!(buf, b"\xe2\x82");
+        assert_eq!(
+            write_buffered(&mut buf, 3, b"\xac", |b| write(b, false)).unwrap(),
+            1
+        );
+        assert!(buf.is_empty());
+        assert_eq!(
+            written,
+            [(1, b"abc".to_vec()), (4, "\u{20AC}".as_bytes().to_vec())]
+        );
+        assert_eq!(lob_pos, 4);
+
+        // Invalid UTF-8 isn't taken either.
+        let err = write_buffered(&mut buf, 1, b"\xff", |b| {
+            write_units(true, &mut 0, b, |_, _| Ok(()))
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn incomplete_or_invalid_characters() {
+        let mut lob_pos = 2;
+        let mut buf = Vec::new();
+        let err = read_units(true, &mut lob_pos, &mut buf, 12, |offset, buf| {
+            read_clob("ab\u{1F600}", 1, offset, buf)
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(lob_pos, 2);
+        let err = read_units(true, &mut lob_pos, &mut buf, 12, |_, buf| {
+            buf[..2].copy_from_slice(b"a\xff");
+            Ok(2)
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        read_units(false, &mut lob_pos, &mut buf, 12, |_, buf| {
+            buf[..2].copy_from_slice(b"a\xff");
+            Ok(2)
+        })
+        .unwrap();
+        assert_eq!(lob_pos, 4);
+    }
+}
@@ -0,0 +1,205 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Conversion between Rust values and Oracle's internal number formats
+
+use crate::{Error, Result};
+
+const MAX_MANTISSA_LEN: usize = 20;
+const NEGATIVE_TERMINATOR: u8 = 102;
+
+/// Converts the internal format of Oracle NUMBER to decimal text.
+pub(crate) fn decode_number(bytes: &[u8]) -> Result<String> {
+    let (&first, rest) = bytes
+        .split_first()
+        .ok_or_else(|| Error::InvalidNumber("empty NUMBER data".into()))?;
+    if first == 0x80 && rest.is_empty() {
+        return Ok("0".into());
+    }
+    let is_positive = first & 0x80 != 0;
+    let (exponent, digits): (i32, Vec<u8>) = if is_positive {
+        (
+            first as i32 - 193,
+            rest.iter().map(|b| b.wrapping_sub(1)).collect(),
+        )
+    } else {
+        let rest = match rest.split_last() {
+            Some((&NEGATIVE_TERMINATOR, rest)) => rest,
+            _ => rest,
+        };
+        (
+            62 - first as i32,
+            rest.iter().map(|b| 101u8.wrapping_sub(*b)).collect(),
+        )
+    };
+    if digits.is_empty() || digits.len() > MAX_MANTISSA_LEN || digits.iter().any(|d| *d > 99) {
+        return Err(Error::InvalidNumber(format!(
+            "malformed NUMBER data {:02x?}",
+            bytes
+        )));
+    }
+    // decimal digits and the position of the decimal point in them
+    let mut text: Vec<u8> = digits
+        .iter()
+        .flat_map(|d| [b'0' + d / 10, b'0' + d % 10])
+        .collect();
+    let point = (exponent + 1) * 2;
+    while text.last() == Some(&b'0') && point < text.len() as i32 {
+        text.pop();
+    }
+    let mut s = String::new();
+    if !is_positive {
+        s.push('-');
+    }
+    if point <= 0 {
+        s.push_str("0.");
+        s.push_str(&"0".repeat(-point as usize));
+        s.extend(text.iter().map(|b| *b as char));
+    } else if point as usize >= text.len() {
+        let zeros = point as usize - text.len();
+        let start = text.iter().position(|b| *b != b'0').unwrap_or(text.len());
+        s.extend(text[start..].iter().map(|b| *b as char));
+        s.push_str(&"0".repeat(zeros));
+    } else {
+        let (int, frac) = text.split_at(point as usize);
+        let start = int.iter().position(|b| *b != b'0').unwrap_or(int.len());
+        if start == int.len() {
+            s.push('0');
+        }
+        s.extend(int[start..].iter().map(|b| *b as char));
+        s.push('.');
+        s.extend(frac.iter().map(|b| *b as char));
+    }
+    Ok(s)
+}
+
+/// Converts decimal text such as `-123.45` or `1.5e-3` to the internal format
+/// of Oracle NUMBER.
+pub(crate) fn encode_number(text: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::InvalidNumber(format!("{:?} isn't a decimal number", text));
+    let (is_negative, s) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (mantissa, exp) = match s.find(['e', 'E']) {
+        Some(pos) => (
+            &s[..pos],
+            s[pos + 1..].parse::<i32>().map_err(|_| invalid())?,
+        ),
+        None => (s, 0),
+    };
+    let (int, frac) = match mantissa.find('.') {
+        Some(pos) => (&mantissa[..pos], &mantissa[pos + 1..]),
+        None => (mantissa, ""),
+    };
+    if int.is_empty() && frac.is_empty()
+        || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let out_of_range =
+        || Error::InvalidNumber(format!("{} is out of the range of Oracle NUMBER", text));
+    let mut digits: Vec<u8> = int.bytes().chain(frac.bytes()).map(|b| b - b'0').collect();
+    let leading = digits.iter().position(|d| *d != 0).unwrap_or(digits.len());
+    digits.drain(..leading);
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    if digits.is_empty() {
+        return Ok(vec![0x80]);
+    }
+    let mut point = i32::try_from(int.len())
+        .ok()
+        .and_then(|len| len.checked_add(exp))
+        .and_then(|point| point.checked_sub(i32::try_from(leading).ok()?))
+        .ok_or_else(out_of_range)?;
+    // align the decimal point to a base-100 digit boundary
+    if point.rem_euclid(2) == 1 {
+        digits.insert(0, 0);
+        point = point.checked_add(1).ok_or_else(out_of_range)?;
+    }
+    if digits.len() % 2 == 1 {
+        digits.push(0);
+    }
+    let exponent = point / 2 - 1;
+    if !(-65..=62).contains(&exponent) {
+        return Err(out_of_range());
+    }
+    if digits.len() / 2 > MAX_MANTISSA_LEN {
+        return Err(Error::InvalidNumber(format!(
+            "{} has more than {} significant digits",
+            text,
+            MAX_MANTISSA_LEN * 2
+        )));
+    }
+    let pairs = digits.chunks(2).map(|c| c[0] * 10 + c[1]);
+    let mut bytes = Vec::with_capacity(MAX_MANTISSA_LEN + 2);
+    if is_negative {
+        bytes.push((62 - exponent) as u8);
+        bytes.extend(pairs.map(|d| 101 - d));
+        if bytes.len() <= MAX_MANTISSA_LEN {
+            bytes.push(NEGATIVE_TERMINATOR);
+        }
+    } else {
+        bytes.push((193 + exponent) as u8);
+        bytes.extend(pairs.map(|d| d + 1));
+    }
+    Ok(bytes)
+}
+
+/// Converts f32 to the internal format of Oracle BINARY_FLOAT.
+pub(crate) fn encode_binary_float(value: f32) -> [u8; 4] {
+    let mut bytes = value.to_bits().to_be_bytes();
+    encode_ieee_bytes(&mut bytes);
+    bytes
+}
+
+/// Converts the internal format of Oracle BINARY_FLOAT to f32.
+pub(crate) fn decode_binary_float(mut bytes: [u8; 4]) -> f32 {
+    decode_ieee_bytes(&mut bytes);
+    f32::from_bits(u32::from_be_bytes(bytes))
+}
+
+/// Converts f64 to the internal format of Oracle BINARY_DOUBLE.
+pub(crate) fn encode_binary_double(value: f64) -> [u8; 8] {
+    let mut bytes = value.to_bits().to_be_bytes();
+    encode_ieee_bytes(&mut bytes);
+    bytes
+}
+
+/// Converts the internal format of Oracle BINARY_DOUBLE to f64.
+pub(crate) fn decode_binary_double(mut bytes: [u8; 8]) -> f64 {
+    decode_ieee_bytes(&mut bytes);
+    f64::from_bits(u64::from_be_bytes(bytes))
+}
+
+// Oracle flips the sign bit of positive values and all bits of negative
+// values so that the byte images sort in the numeric order.
+fn encode_ieee_bytes(bytes: &mut [u8]) {
+    if bytes[0] & 0x80 == 0 {
+        bytes[0] |= 0x80;
+    } else {
+        bytes.iter_mut().for_each(|b| *b = !*b);
+    }
+}
+
+fn decode_ieee_bytes(bytes: &mut [u8]) {
+    if bytes[0] & 0x80 != 0 {
+        bytes[0] &= 0x7f;
+    } else {
+        bytes.iter_mut().for_each(|b| *b = !*b);
+    }
+}
@@ -0,0 +1,1164 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::{
+    Connection, Context, Error, FromSql, IntervalDS, IntervalYM, Result, SqlValue, Timestamp,
+};
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::os::raw::c_char;
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+// Buffer size to get NUMBER values as text, which is
+// DPI_NUMBER_AS_TEXT_CHARS in dpiImpl.h.
+const NUMBER_AS_TEXT_CHARS: usize = 172;
+
+/// Type of an attribute or of elements of a collection
+#[derive(Clone, Debug)]
+pub struct ObjectDataType {
+    pub oracle_type: dpiOracleTypeNum,
+    /// default native type
+    pub native_type: dpiNativeTypeNum,
+    /// precision of `NUMBER` and fractional seconds precision of timestamps
+    pub precision: i16,
+    /// scale of `NUMBER`
+    pub scale: i8,
+    /// maximum size of character and binary types in bytes
+    pub db_size_in_bytes: u32,
+    /// type of nested objects and collections
+    pub object_type: Option<ObjectType>,
+}
+
+impl ObjectDataType {
+    unsafe fn new(conn: &Connection, info: &dpiDataTypeInfo) -> Result<ObjectDataType> {
+        let object_type = if info.objectType.is_null() {
+            None
+        } else {
+            Some(ObjectType::from_raw(conn, info.objectType)?)
+        };
+        Ok(ObjectDataType {
+            oracle_type: info.oracleTypeNum,
+            native_type: info.defaultNativeTypeNum,
+            precision: if info.oracleTypeNum == DPI_ORACLE_TYPE_NUMBER {
+                info.precision
+            } else {
+                info.fsPrecision.into()
+            },
+            scale: info.scale,
+            db_size_in_bytes: info.dbSizeInBytes,
+            object_type,
+        })
+    }
+
+    // Native type to get and set values. NUMBER is passed as text so that
+    // it keeps its precision.
+    fn value_native_type(&self) -> dpiNativeTypeNum {
+        if self.oracle_type == DPI_ORACLE_TYPE_NUMBER {
+            DPI_NATIVE_TYPE_BYTES
+        } else {
+            self.native_type
+        }
+    }
+
+    // Native type to set `value`. Strings are set to CLOB and NCLOB as
+    // bytes, which ODPI-C writes to temporary LOBs.
+    fn scalar_native_type(&self, value: &SqlValue) -> dpiNativeTypeNum {
+        match (self.oracle_type, value) {
+            (DPI_ORACLE_TYPE_CLOB | DPI_ORACLE_TYPE_NCLOB, SqlValue::String(_)) => {
+                DPI_NATIVE_TYPE_BYTES
+            }
+            _ => self.value_native_type(),
+        }
+    }
+
+    fn nested_type(&self) -> Result<&ObjectType> {
+        self.object_type.as_ref().ok_or_else(|| {
+            Error::InvalidConversion(format!(
+                "Oracle type {} doesn't hold objects",
+                self.oracle_type
+            ))
+        })
+    }
+}
+
+/// Attribute of an object type
+///
+/// This holds a reference to [`dpiObjectAttr`].
+#[derive(Debug)]
+pub struct ObjectAttr {
+    handle: *mut dpiObjectAttr,
+    name: String,
+    data_type: ObjectDataType,
+}
+
+impl ObjectAttr {
+    // Takes the ownership of `handle`, which is released on error.
+    unsafe fn new(conn: &Connection, handle: *mut dpiObjectAttr) -> Result<ObjectAttr> {
+        let mut info = dpiObjectAttrInfo::default();
+        if dpiObjectAttr_getInfo(handle, &mut info) != DPI_SUCCESS {
+            let err = conn.ctxt().last_error();
+            dpiObjectAttr_release(handle);
+            return Err(Error::DpiError(err));
+        }
+        let name = to_string(info.name, info.nameLength);
+        match ObjectDataType::new(conn, &info.typeInfo) {
+            Ok(data_type) => Ok(ObjectAttr {
+                handle,
+                name,
+                data_type,
+            }),
+            Err(err) => {
+                dpiObjectAttr_release(handle);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn handle(&self) -> *mut dpiObjectAttr {
+        self.handle
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn data_type(&self) -> &ObjectDataType {
+        &self.data_type
+    }
+}
+
+impl Drop for ObjectAttr {
+    fn drop(&mut self) {
+        unsafe { dpiObjectAttr_release(self.handle) };
+    }
+}
+
+#[derive(Debug)]
+struct ObjectTypeInner {
+    ctxt: &'static Context,
+    handle: *mut dpiObjectType,
+    schema: String,
+    name: String,
+    package_name: Option<String>,
+    element_type: Option<ObjectDataType>,
+    attributes: Vec<ObjectAttr>,
+    // Rust types checked by OracleObject::check_object_type
+    checked_types: RefCell<Vec<TypeId>>,
+}
+
+impl Drop for ObjectTypeInner {
+    fn drop(&mut self) {
+        unsafe { dpiObjectType_release(self.handle) };
+    }
+}
+
+/// Object type or collection type
+///
+/// This holds a reference to [`dpiObjectType`] together with its attributes
+/// and element type, which are got once when the type is looked up. Types
+/// are cached per connection by [`Connection::object_type`]; cloning shares
+/// the cached type.
+#[derive(Clone, Debug)]
+pub struct ObjectType {
+    inner: Rc<ObjectTypeInner>,
+}
+
+impl ObjectType {
+    /// Creates an object type from a handle, adding a reference to it.
+    /// The type is taken from the cache of `conn` when it is there.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid object type handle got via `conn`.
+    pub unsafe fn from_raw(conn: &Connection, handle: *mut dpiObjectType) -> Result<ObjectType> {
+        let ctxt = conn.ctxt();
+        let mut info = dpiObjectTypeInfo::default();
+        chkerr!(ctxt, dpiObjectType_getInfo(handle, &mut info));
+        let schema = to_string(info.schema, info.schemaLength);
+        let name = to_string(info.name, info.nameLength);
+        let package_name = if info.packageName.is_null() {
+            None
+        } else {
+            Some(to_string(info.packageName, info.packageNameLength))
+        };
+        let full_name = full_name(&schema, package_name.as_deref(), &name);
+        if let Some(objtype) = conn.cached_object_type(&full_name) {
+            return Ok(objtype);
+        }
+        chkerr!(ctxt, dpiObjectType_addRef(handle));
+        let mut inner = ObjectTypeInner {
+            ctxt,
+            handle,
+            schema,
+            name,
+            package_name,
+            element_type: None,
+            attributes: Vec::new(),
+            checked_types: RefCell::new(Vec::new()),
+        };
+        if info.isCollection != 0 {
+            inner.element_type = Some(ObjectDataType::new(conn, &info.elementTypeInfo)?);
+        }
+        if info.numAttributes > 0 {
+            let mut handles = vec![ptr::null_mut(); info.numAttributes as usize];
+            chkerr!(
+                ctxt,
+                dpiObjectType_getAttributes(handle, info.numAttributes, handles.as_mut_ptr())
+            );
+            let mut handles = handles.into_iter();
+            while let Some(attr) = handles.next() {
+                match ObjectAttr::new(conn, attr) {
+                    Ok(attr) => inner.attributes.push(attr),
+                    Err(err) => {
+                        handles.for_each(|attr| {
+                            dpiObjectAttr_release(attr);
+                        });
+                        return Err(err);
+                    }
+                }
+            }
+        }
+        let objtype = ObjectType {
+            inner: Rc::new(inner),
+        };
+        conn.cache_object_type(full_name, &objtype);
+        Ok(objtype)
+    }
+
+    pub fn handle(&self) -> *mut dpiObjectType {
+        self.inner.handle
+    }
+
+    pub fn schema(&self) -> &str {
+        &self.inner.schema
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Returns the package name of a PL/SQL type.
+    pub fn package_name(&self) -> Option<&str> {
+        self.inner.package_name.as_deref()
+    }
+
+    /// Returns the name qualified by the schema and the package, such as
+    /// `SCHEMA.TYPE_NAME` and `SCHEMA.PACKAGE_NAME.TYPE_NAME`.
+    pub fn full_name(&self) -> String {
+        full_name(self.schema(), self.package_name(), self.name())
+    }
+
+    pub fn is_collection(&self) -> bool {
+        self.inner.element_type.is_some()
+    }
+
+    /// Returns the type of elements when the type is a collection.
+    pub fn element_type(&self) -> Option<&ObjectDataType> {
+        self.inner.element_type.as_ref()
+    }
+
+    pub fn attributes(&self) -> &[ObjectAttr] {
+        &self.inner.attributes
+    }
+
+    /// Returns the attribute `name`.
+    pub fn attribute(&self, name: &str) -> Option<&ObjectAttr> {
+        self.inner.attributes.iter().find(|attr| attr.name == name)
+    }
+
+    // Checks the type against `T` unless it has been checked.
+    fn check_once<T: OracleObject + 'static>(&self) -> Result<()> {
+        let type_id = TypeId::of::<T>();
+        if !self.inner.checked_types.borrow().contains(&type_id) {
+            T::check_object_type(self)?;
+            self.inner.checked_types.borrow_mut().push(type_id);
+        }
+        Ok(())
+    }
+
+    /// Creates an object whose attributes are null or an empty collection.
+    pub fn create_object(&self) -> Result<Object> {
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            self.inner.ctxt,
+            dpiObjectType_createObject(self.inner.handle, &mut handle)
+        );
+        Ok(Object {
+            handle,
+            object_type: self.clone(),
+        })
+    }
+}
+
+fn full_name(schema: &str, package_name: Option<&str>, name: &str) -> String {
+    match package_name {
+        Some(package_name) => format!("{}.{}.{}", schema, package_name, name),
+        None => format!("{}.{}", schema, name),
+    }
+}
+
+/// Object or collection
+///
+/// This holds a reference to [`dpiObject`], which is released when it is
+/// dropped.
+#[derive(Debug)]
+pub struct Object {
+    handle: *mut dpiObject,
+    object_type: ObjectType,
+}
+
+impl Object {
+    /// Creates an object from a handle, adding a reference to it.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid object handle of `object_type`.
+    pub unsafe fn from_raw(object_type: &ObjectType, handle: *mut dpiObject) -> Result<Object> {
+        chkerr!(object_type.inner.ctxt, dpiObject_addRef(handle));
+        Ok(Object {
+            handle,
+            object_type: object_type.clone(),
+        })
+    }
+
+    pub fn handle(&self) -> *mut dpiObject {
+        self.handle
+    }
+
+    pub fn object_type(&self) -> &ObjectType {
+        &self.object_type
+    }
+
+    /// Converts the object and its nested objects to a value tree.
+    pub fn to_value(&self) -> Result<ObjectValue> {
+        let objtype = &self.object_type;
+        let ctxt = objtype.inner.ctxt;
+        let type_name = objtype.full_name();
+        if let Some(elem_type) = objtype.element_type() {
+            let mut elements = Vec::new();
+            let mut index = 0;
+            let mut exists = 0;
+            chkerr!(
+                ctxt,
+                dpiObject_getFirstIndex(self.handle, &mut index, &mut exists)
+            );
+            while exists != 0 {
+                elements.push(unsafe {
+                    get_value(ctxt, elem_type, |native_type, data| {
+                        dpiObject_getElementValueByIndex(self.handle, index, native_type, data)
+                    })?
+                });
+                chkerr!(
+                    ctxt,
+                    dpiObject_getNextIndex(self.handle, index, &mut index, &mut exists)
+                );
+            }
+            Ok(ObjectValue::Collection {
+                type_name,
+                elements,
+            })
+        } else {
+            let attributes = objtype
+                .attributes()
+                .iter()
+                .map(|attr| {
+                    let value = unsafe {
+                        get_value(ctxt, &attr.data_type, |native_type, data| {
+                            dpiObject_getAttributeValue(self.handle, attr.handle, native_type, data)
+                        })?
+                    };
+                    Ok((attr.name.clone(), value))
+                })
+                .collect::<Result<_>>()?;
+            Ok(ObjectValue::Object {
+                type_name,
+                attributes,
+            })
+        }
+    }
+}
+
+impl Clone for Object {
+    fn clone(&self) -> Object {
+        unsafe { dpiObject_addRef(self.handle) };
+        Object {
+            handle: self.handle,
+            object_type: self.object_type.clone(),
+        }
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        unsafe { dpiObject_release(self.handle) };
+    }
+}
+
+/// Dynamic tree of an object or collection
+///
+/// This is an owned counterpart of [`Object`] including its nested objects.
+/// It is converted from an object by [`Object::to_value`] and to an object
+/// by [`ObjectValue::to_object`].
+///
+/// ```no_run
+/// # use odpic::{Connection, SqlValue};
+/// # use odpic::object::ObjectValue;
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let objtype = conn.object_type("HR.ADDRESS_T")?;
+/// let value = ObjectValue::Object {
+///     type_name: objtype.full_name(),
+///     attributes: vec![
+///         ("STREET".into(), ObjectValue::Scalar(SqlValue::String("Main St".into()))),
+///         ("ZIP".into(), ObjectValue::Null),
+///     ],
+/// };
+/// let obj = value.to_object(&objtype)?;
+/// println!("{:?}", obj.to_value()?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub enum ObjectValue {
+    /// null attribute or element
+    Null,
+    /// attribute or element other than objects and collections
+    Scalar(SqlValue),
+    /// object whose attributes are kept in their order
+    Object {
+        type_name: String,
+        attributes: Vec<(String, ObjectValue)>,
+    },
+    /// elements of a collection in their index order
+    Collection {
+        type_name: String,
+        elements: Vec<ObjectValue>,
+    },
+}
+
+impl ObjectValue {
+    pub fn is_null(&self) -> bool {
+        matches!(
+            self,
+            ObjectValue::Null | ObjectValue::Scalar(SqlValue::Null)
+        )
+    }
+
+    /// Returns the attribute `name` when the value is an object.
+    pub fn get(&self, name: &str) -> Option<&ObjectValue> {
+        match self {
+            ObjectValue::Object { attributes, .. } => {
+                attributes.iter().find(|a| a.0 == name).map(|a| &a.1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns elements when the value is a collection.
+    pub fn elements(&self) -> Option<&[ObjectValue]> {
+        match self {
+            ObjectValue::Collection { elements, .. } => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Creates an object of `objtype` from the value.
+    ///
+    /// Attributes which are not in the value are left null. `type_name` is
+    /// not checked.
+    pub fn to_object(&self, objtype: &ObjectType) -> Result<Object> {
+        let ctxt = objtype.inner.ctxt;
+        let obj = objtype.create_object()?;
+        match (self, objtype.element_type()) {
+            (ObjectValue::Object { attributes, .. }, None) => {
+                for (name, value) in attributes {
+                    let attr = objtype.attribute(name).ok_or_else(|| {
+                        Error::InvalidOperation(format!(
+                            "{} has no attribute {}",
+                            objtype.full_name(),
+                            name
+                        ))
+                    })?;
+                    set_value(ctxt, &attr.data_type, value, |native_type, data| unsafe {
+                        dpiObject_setAttributeValue(obj.handle, attr.handle, native_type, data)
+                    })?;
+                }
+            }
+            (ObjectValue::Collection { elements, .. }, Some(elem_type)) => {
+                for value in elements {
+                    set_value(ctxt, elem_type, value, |native_type, data| unsafe {
+                        dpiObject_appendElement(obj.handle, native_type, data)
+                    })?;
+                }
+            }
+            _ => {
+                return Err(Error::InvalidConversion(format!(
+                    "{} cannot be converted to {}",
+                    self.kind(),
+                    objtype.full_name()
+                )))
+            }
+        }
+        Ok(obj)
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            ObjectValue::Null => "null",
+            ObjectValue::Scalar(value) => value.type_name(),
+            ObjectValue::Object { .. } => "object",
+            ObjectValue::Collection { .. } => "collection",
+        }
+    }
+}
+
+impl From<SqlValue> for ObjectValue {
+    fn from(value: SqlValue) -> ObjectValue {
+        ObjectValue::Scalar(value)
+    }
+}
+
+// Gets an attribute or an element by `get`, which calls
+// dpiObject_getAttributeValue or dpiObject_getElementValueByIndex.
+unsafe fn get_value<F>(
+    ctxt: &'static Context,
+    data_type: &ObjectDataType,
+    get: F,
+) -> Result<ObjectValue>
+where
+    F: FnOnce(dpiNativeTypeNum, *mut dpiData) -> i32,
+{
+    let native_type = data_type.value_native_type();
+    let mut buf = [0u8; NUMBER_AS_TEXT_CHARS];
+    let mut data = dpiData::default();
+    if data_type.oracle_type == DPI_ORACLE_TYPE_NUMBER {
+        data.value.asBytes = dpiBytes {
+            ptr: buf.as_mut_ptr() as *mut c_char,
+            length: buf.len() as u32,
+            encoding: ptr::null(),
+        };
+    }
+    if get(native_type, &mut data) != DPI_SUCCESS {
+        return Err(Error::DpiError(ctxt.last_error()));
+    }
+    if data.isNull != 0 {
+        return Ok(ObjectValue::Null);
+    }
+    if native_type == DPI_NATIVE_TYPE_OBJECT {
+        // The reference got as DPI_NATIVE_TYPE_OBJECT is owned by the caller.
+        let handle = data.value.asObject;
+        let obj = data_type
+            .nested_type()
+            .and_then(|objtype| Object::from_raw(objtype, handle));
+        dpiObject_release(handle);
+        return obj?.to_value();
+    }
+    let value = SqlValue::from_dpi(data_type.oracle_type, native_type, &data);
+    if native_type == DPI_NATIVE_TYPE_LOB {
+        // The reference got as DPI_NATIVE_TYPE_LOB is also owned by the
+        // caller, while `SqlValue::Lob` holds its own one.
+        dpiLob_release(data.value.asLOB);
+    }
+    Ok(ObjectValue::Scalar(value?))
+}
+
+// Sets an attribute or appends an element by `set`, which calls
+// dpiObject_setAttributeValue or dpiObject_appendElement.
+fn set_value<F>(
+    ctxt: &'static Context,
+    data_type: &ObjectDataType,
+    value: &ObjectValue,
+    set: F,
+) -> Result<()>
+where
+    F: FnOnce(dpiNativeTypeNum, *mut dpiData) -> i32,
+{
+    let native_type = match value {
+        ObjectValue::Scalar(value) => data_type.scalar_native_type(value),
+        _ => data_type.value_native_type(),
+    };
+    let mut data = dpiData::default();
+    // buffers which must be alive until `set` is called
+    let mut bytes = Vec::new();
+    let mut nested = None;
+    match value {
+        ObjectValue::Null | ObjectValue::Scalar(SqlValue::Null) => data.isNull = 1,
+        ObjectValue::Object { .. } | ObjectValue::Collection { .. } => {
+            let obj = value.to_object(data_type.nested_type()?)?;
+            data.value.asObject = obj.handle;
+            nested = Some(obj);
+        }
+        ObjectValue::Scalar(value) => {
+            fill_data(data_type, native_type, value, &mut data, &mut bytes)?
+        }
+    }
+    chkerr!(ctxt, set(native_type, &mut data));
+    drop(nested);
+    Ok(())
+}
+
+fn fill_data(
+    data_type: &ObjectDataType,
+    native_type: dpiNativeTypeNum,
+    value: &SqlValue,
+    data: &mut dpiData,
+    bytes: &mut Vec<u8>,
+) -> Result<()> {
+    let is_number = data_type.oracle_type == DPI_ORACLE_TYPE_NUMBER;
+    match (native_type, value) {
+        (DPI_NATIVE_TYPE_BOOLEAN, SqlValue::Boolean(v)) => data.value.asBoolean = (*v).into(),
+        (DPI_NATIVE_TYPE_INT64, SqlValue::Int64(v)) => data.value.asInt64 = *v,
+        (DPI_NATIVE_TYPE_UINT64, SqlValue::Uint64(v)) => data.value.asUint64 = *v,
+        (DPI_NATIVE_TYPE_FLOAT, SqlValue::Float(v)) => data.value.asFloat = *v,
+        (DPI_NATIVE_TYPE_FLOAT, SqlValue::Double(v)) => data.value.asFloat = *v as f32,
+        (DPI_NATIVE_TYPE_DOUBLE, SqlValue::Float(v)) => data.value.asDouble = (*v).into(),
+        (DPI_NATIVE_TYPE_DOUBLE, SqlValue::Double(v)) => data.value.asDouble = *v,
+        (DPI_NATIVE_TYPE_BYTES, SqlValue::Number(v)) if is_number => bytes.extend(v.as_bytes()),
+        (DPI_NATIVE_TYPE_BYTES, SqlValue::Int64(v)) if is_number => {
+            bytes.extend(v.to_string().as_bytes())
+        }
+        (DPI_NATIVE_TYPE_BYTES, SqlValue::Uint64(v)) if is_number => {
+            bytes.extend(v.to_string().as_bytes())
+        }
+        (DPI_NATIVE_TYPE_BYTES, SqlValue::Float(v)) if is_number => {
+            bytes.extend(v.to_string().as_bytes())
+        }
+        (DPI_NATIVE_TYPE_BYTES, SqlValue::Double(v)) if is_number => {
+            bytes.extend(v.to_string().as_bytes())
+        }
+        (DPI_NATIVE_TYPE_BYTES, SqlValue::String(v)) if !is_number => bytes.extend(v.as_bytes()),
+        (DPI_NATIVE_TYPE_BYTES, SqlValue::Raw(v)) if !is_number => bytes.extend(v),
+        (DPI_NATIVE_TYPE_TIMESTAMP, SqlValue::Timestamp(v)) => data.value.asTimestamp = (*v).into(),
+        (DPI_NATIVE_TYPE_INTERVAL_DS, SqlValue::IntervalDS(v)) => {
+            data.value.asIntervalDS = (*v).into()
+        }
+        (DPI_NATIVE_TYPE_INTERVAL_YM, SqlValue::IntervalYM(v)) => {
+            data.value.asIntervalYM = (*v).into()
+        }
+        (DPI_NATIVE_TYPE_LOB, SqlValue::Lob(v)) => data.value.asLOB = v.handle(),
+        _ => {
+            return Err(Error::InvalidConversion(format!(
+                "{} cannot be set to Oracle type {}",
+                value.type_name(),
+                data_type.oracle_type
+            )))
+        }
+    }
+    if native_type == DPI_NATIVE_TYPE_BYTES {
+        data.value.asBytes = dpiBytes {
+            ptr: bytes.as_mut_ptr() as *mut c_char,
+            length: bytes.len() as u32,
+            encoding: ptr::null(),
+        };
+    }
+    Ok(())
+}
+
+#[cfg(feature = "derive")]
+pub use odpic_derive::OracleObject;
+
+/// Rust type mapped to an Oracle object type or collection type
+///
+/// This is usually implemented by `#[derive(OracleObject)]` with the
+/// `derive` feature, which maps fields of a struct to attributes of an
+/// object type and a newtype struct of `Vec<T>` to a collection type.
+pub trait OracleObject: Sized {
+    /// Default object type name such as `SCHEMA.TYPE_NAME`. It is empty
+    /// when not specified.
+    const TYPE_NAME: &'static str;
+
+    /// Checks attribute names and types, or the element type of a
+    /// collection.
+    fn check_object_type(objtype: &ObjectType) -> Result<()>;
+
+    fn to_object_value(&self) -> Result<ObjectValue>;
+
+    fn from_object_value(value: ObjectValue) -> Result<Self>;
+
+    /// Creates an object of `objtype`. The type is checked by
+    /// [`OracleObject::check_object_type`] on first use.
+    fn to_object(&self, objtype: &ObjectType) -> Result<Object>
+    where
+        Self: 'static,
+    {
+        objtype.check_once::<Self>()?;
+        self.to_object_value()?.to_object(objtype)
+    }
+
+    /// Converts an object. The type is checked by
+    /// [`OracleObject::check_object_type`] on first use.
+    fn from_object(obj: &Object) -> Result<Self>
+    where
+        Self: 'static,
+    {
+        obj.object_type().check_once::<Self>()?;
+        Self::from_object_value(obj.to_value()?)
+    }
+}
+
+/// Rust type mapped to an attribute or an element of objects
+///
+/// Types implementing [`OracleObject`] are mapped to nested objects and
+/// `Vec<T>` to nested collections.
+pub trait ObjectField: Sized {
+    /// Checks whether values of the type can be stored in `data_type`.
+    fn check_data_type(data_type: &ObjectDataType) -> Result<()>;
+
+    fn to_field_value(&self) -> Result<ObjectValue>;
+
+    fn from_field_value(value: ObjectValue) -> Result<Self>;
+}
+
+impl ObjectDataType {
+    /// Returns an error unless the Oracle type is one of `oracle_types`.
+    pub fn expect_oracle_types(
+        &self,
+        rust_type: &str,
+        oracle_types: &[dpiOracleTypeNum],
+    ) -> Result<()> {
+        if oracle_types.contains(&self.oracle_type) {
+            Ok(())
+        } else {
+            Err(Error::InvalidConversion(format!(
+                "{} cannot be mapped to Oracle type {}",
+                rust_type, self.oracle_type
+            )))
+        }
+    }
+
+    /// Returns the object type unless it is a collection.
+    pub fn expect_object_type(&self, rust_type: &str) -> Result<&ObjectType> {
+        match &self.object_type {
+            Some(objtype) if !objtype.is_collection() => Ok(objtype),
+            _ => Err(Error::InvalidConversion(format!(
+                "{} cannot be mapped to Oracle type {}",
+                rust_type, self.oracle_type
+            ))),
+        }
+    }
+
+    /// Returns the element type when the type is a collection.
+    pub fn expect_element_type(&self, rust_type: &str) -> Result<&ObjectDataType> {
+        self.object_type
+            .as_ref()
+            .and_then(|objtype| objtype.element_type())
+            .ok_or_else(|| {
+                Error::InvalidConversion(format!(
+                    "{} cannot be mapped to Oracle type {}",
+                    rust_type, self.oracle_type
+                ))
+            })
+    }
+}
+
+/// Data type of objects of the object type
+impl From<&ObjectType> for ObjectDataType {
+    fn from(objtype: &ObjectType) -> ObjectDataType {
+        ObjectDataType {
+            oracle_type: DPI_ORACLE_TYPE_OBJECT,
+            native_type: DPI_NATIVE_TYPE_OBJECT,
+            precision: 0,
+            scale: 0,
+            db_size_in_bytes: 0,
+            object_type: Some(objtype.clone()),
+        }
+    }
+}
+
+impl ObjectType {
+    /// Returns the attribute `name` or an error naming the type.
+    pub fn expect_attribute(&self, name: &str) -> Result<&ObjectAttr> {
+        self.attribute(name).ok_or_else(|| {
+            Error::InvalidOperation(format!("{} has no attribute {}", self.full_name(), name))
+        })
+    }
+}
+
+impl ObjectValue {
+    /// Takes the attribute `name` out of an object value. It is null when
+    /// the attribute isn't in the value.
+    pub fn take_attribute(&mut self, name: &str) -> ObjectValue {
+        match self {
+            ObjectValue::Object { attributes, .. } => attributes
+                .iter_mut()
+                .find(|a| a.0 == name)
+                .map(|a| std::mem::replace(&mut a.1, ObjectValue::Null))
+                .unwrap_or(ObjectValue::Null),
+            _ => ObjectValue::Null,
+        }
+    }
+
+    /// Returns the elements of a collection value.
+    pub fn into_elements(self) -> Result<Vec<ObjectValue>> {
+        match self {
+            ObjectValue::Collection { elements, .. } => Ok(elements),
+            value => Err(Error::InvalidConversion(format!(
+                "{} cannot be converted to a collection",
+                value.kind()
+            ))),
+        }
+    }
+
+    /// Returns the value unless it is an object.
+    pub fn into_object(self) -> Result<ObjectValue> {
+        match self {
+            ObjectValue::Object { .. } => Ok(self),
+            ObjectValue::Null | ObjectValue::Scalar(SqlValue::Null) => Err(Error::NullValue),
+            value => Err(Error::InvalidConversion(format!(
+                "{} cannot be converted to an object",
+                value.kind()
+            ))),
+        }
+    }
+}
+
+const NUMERIC_TYPES: &[dpiOracleTypeNum] = &[
+    DPI_ORACLE_TYPE_NUMBER,
+    DPI_ORACLE_TYPE_NATIVE_INT,
+    DPI_ORACLE_TYPE_NATIVE_DOUBLE,
+    DPI_ORACLE_TYPE_NATIVE_FLOAT,
+];
+const INTEGER_TYPES: &[dpiOracleTypeNum] = &[DPI_ORACLE_TYPE_NUMBER, DPI_ORACLE_TYPE_NATIVE_INT];
+const STRING_TYPES: &[dpiOracleTypeNum] = &[
+    DPI_ORACLE_TYPE_VARCHAR,
+    DPI_ORACLE_TYPE_NVARCHAR,
+    DPI_ORACLE_TYPE_CHAR,
+    DPI_ORACLE_TYPE_NCHAR,
+    DPI_ORACLE_TYPE_CLOB,
+    DPI_ORACLE_TYPE_NCLOB,
+];
+const TIMESTAMP_TYPES: &[dpiOracleTypeNum] = &[
+    DPI_ORACLE_TYPE_DATE,
+    DPI_ORACLE_TYPE_TIMESTAMP,
+    DPI_ORACLE_TYPE_TIMESTAMP_TZ,
+    DPI_ORACLE_TYPE_TIMESTAMP_LTZ,
+];
+
+fn scalar<T: FromSql>(value: ObjectValue) -> Result<T> {
+    match value {
+        ObjectValue::Null => Err(Error::NullValue),
+        ObjectValue::Scalar(value) => T::from_sql(&value),
+        value => Err(Error::InvalidConversion(format!(
+            "{} cannot be converted to a scalar",
+            value.kind()
+        ))),
+    }
+}
+
+macro_rules! impl_scalar_field {
+    ($ty:ty, $oracle_types:expr, $variant:ident, $sql_ty:ty) => {
+        impl ObjectField for $ty {
+            fn check_data_type(data_type: &ObjectDataType) -> Result<()> {
+                data_type.expect_oracle_types(stringify!($ty), $oracle_types)
+            }
+
+            fn to_field_value(&self) -> Result<ObjectValue> {
+                Ok(ObjectValue::Scalar(SqlValue::$variant(<$sql_ty>::from(
+                    self.clone(),
+                ))))
+            }
+
+            fn from_field_value(value: ObjectValue) -> Result<Self> {
+                scalar(value)
+            }
+        }
+    };
+}
+
+impl_scalar_field!(i8, INTEGER_TYPES, Int64, i64);
+impl_scalar_field!(i16, INTEGER_TYPES, Int64, i64);
+impl_scalar_field!(i32, INTEGER_TYPES, Int64, i64);
+impl_scalar_field!(i64, INTEGER_TYPES, Int64, i64);
+impl_scalar_field!(u8, INTEGER_TYPES, Int64, i64);
+impl_scalar_field!(u16, INTEGER_TYPES, Int64, i64);
+impl_scalar_field!(u32, INTEGER_TYPES, Int64, i64);
+impl_scalar_field!(u64, &[DPI_ORACLE_TYPE_NUMBER], Uint64, u64);
+impl_scalar_field!(f32, NUMERIC_TYPES, Float, f32);
+impl_scalar_field!(f64, NUMERIC_TYPES, Double, f64);
+impl_scalar_field!(bool, &[DPI_ORACLE_TYPE_BOOLEAN], Boolean, bool);
+impl_scalar_field!(String, STRING_TYPES, String, String);
+impl_scalar_field!(Timestamp, TIMESTAMP_TYPES, Timestamp, Timestamp);
+impl_scalar_field!(
+    IntervalDS,
+    &[DPI_ORACLE_TYPE_INTERVAL_DS],
+    IntervalDS,
+    IntervalDS
+);
+impl_scalar_field!(
+    IntervalYM,
+    &[DPI_ORACLE_TYPE_INTERVAL_YM],
+    IntervalYM,
+    IntervalYM
+);
+
+/// Any scalar attribute
+impl ObjectField for SqlValue {
+    fn check_data_type(data_type: &ObjectDataType) -> Result<()> {
+        match data_type.object_type {
+            None => Ok(()),
+            Some(_) => Err(Error::InvalidConversion(format!(
+                "SqlValue cannot be mapped to Oracle type {}",
+                data_type.oracle_type
+            ))),
+        }
+    }
+
+    fn to_field_value(&self) -> Result<ObjectValue> {
+        Ok(ObjectValue::Scalar(self.clone()))
+    }
+
+    fn from_field_value(value: ObjectValue) -> Result<Self> {
+        match value {
+            ObjectValue::Null => Ok(SqlValue::Null),
+            value => scalar(value),
+        }
+    }
+}
+
+/// Any attribute
+impl ObjectField for ObjectValue {
+    fn check_data_type(_data_type: &ObjectDataType) -> Result<()> {
+        Ok(())
+    }
+
+    fn to_field_value(&self) -> Result<ObjectValue> {
+        Ok(self.clone())
+    }
+
+    fn from_field_value(value: ObjectValue) -> Result<Self> {
+        Ok(value)
+    }
+}
+
+impl<T: ObjectField> ObjectField for Option<T> {
+    fn check_data_type(data_type: &ObjectDataType) -> Result<()> {
+        T::check_data_type(data_type)
+    }
+
+    fn to_field_value(&self) -> Result<ObjectValue> {
+        match self {
+            Some(value) => value.to_field_value(),
+            None => Ok(ObjectValue::Null),
+        }
+    }
+
+    fn from_field_value(value: ObjectValue) -> Result<Self> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_field_value(value).map(Some)
+        }
+    }
+}
+
+/// Nested collection
+impl<T: ObjectField> ObjectField for Vec<T> {
+    fn check_data_type(data_type: &ObjectDataType) -> Result<()> {
+        T::check_data_type(data_type.expect_element_type("Vec")?)
+    }
+
+    fn to_field_value(&self) -> Result<ObjectValue> {
+        Ok(ObjectValue::Collection {
+            type_name: String::new(),
+            elements: self
+                .iter()
+                .map(ObjectField::to_field_value)
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    fn from_field_value(value: ObjectValue) -> Result<Self> {
+        if value.is_null() {
+            return Err(Error::NullValue);
+        }
+        value
+            .into_elements()?
+            .into_iter()
+            .map(T::from_field_value)
+            .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) mod ser {
+    use super::ObjectValue;
+    use crate::{IntervalDS, IntervalYM, SqlValue, Timestamp};
+    use serde::ser::{Error, Serialize, SerializeMap, SerializeSeq, Serializer};
+    use std::fmt::Write;
+
+    /// Objects are serialized as maps of attributes and collections as
+    /// sequences of elements. `NUMBER` values are serialized as `i64` when
+    /// they fit and as `f64` otherwise. `RAW` values are hexadecimal strings,
+    /// datetimes are ISO 8601 strings and intervals are ISO 8601 durations.
+    /// LOBs are not serialized; read them first.
+    impl Serialize for ObjectValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                ObjectValue::Null => serializer.serialize_none(),
+                ObjectValue::Scalar(value) => serialize_scalar(value, serializer),
+                ObjectValue::Object { attributes, .. } => {
+                    let mut map = serializer.serialize_map(Some(attributes.len()))?;
+                    for (name, value) in attributes {
+                        map.serialize_entry(name, value)?;
+                    }
+                    map.end()
+                }
+                ObjectValue::Collection { elements, .. } => {
+                    let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                    for value in elements {
+                        seq.serialize_element(value)?;
+                    }
+                    seq.end()
+                }
+            }
+        }
+    }
+
+    fn serialize_scalar<S: Serializer>(value: &SqlValue, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            SqlValue::Null => serializer.serialize_none(),
+            SqlValue::Boolean(v) => serializer.serialize_bool(*v),
+            SqlValue::Int64(v) => serializer.serialize_i64(*v),
+            SqlValue::Uint64(v) => serializer.serialize_u64(*v),
+            SqlValue::Float(v) => serializer.serialize_f32(*v),
+            SqlValue::Double(v) => serializer.serialize_f64(*v),
+            SqlValue::Number(v) => match (v.parse::<i64>(), v.parse::<f64>()) {
+                (Ok(v), _) => serializer.serialize_i64(v),
+                (_, Ok(v)) => serializer.serialize_f64(v),
+                _ => Err(S::Error::custom(format!("invalid number {}", v))),
+            },
+            SqlValue::String(v) | SqlValue::Rowid(v) => serializer.serialize_str(v),
+            SqlValue::Raw(v) => {
+                let mut hex = String::with_capacity(v.len() * 2);
+                for b in v {
+                    let _ = write!(hex, "{:02X}", b);
+                }
+                serializer.serialize_str(&hex)
+            }
+            SqlValue::Timestamp(v) => serializer.serialize_str(&timestamp(v)),
+            SqlValue::IntervalDS(v) => serializer.serialize_str(&interval_ds(v)),
+            SqlValue::IntervalYM(v) => serializer.serialize_str(&interval_ym(v)),
+            value => Err(S::Error::custom(format!(
+                "{} cannot be serialized",
+                value.type_name()
+            ))),
+        }
+    }
+
+    // e.g. 2024-01-02T03:04:05.123456789+09:00. The time zone offset is
+    // omitted for timestamps without time zone.
+    pub(crate) fn timestamp(ts: &Timestamp) -> String {
+        let mut s = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            ts.year, ts.month, ts.day, ts.hour, ts.minute, ts.second
+        );
+        if ts.nanosecond != 0 {
+            let _ = write!(s, ".{:09}", ts.nanosecond);
+        }
+        let offset = ts.tz_offset_minutes();
+        if ts.has_tz {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.abs();
+            let _ = write!(s, "{}{:02}:{:02}", sign, offset / 60, offset % 60);
+        }
+        s
+    }
+
+    // e.g. P1DT2H3M4.5S
+    pub(crate) fn interval_ds(it: &IntervalDS) -> String {
+        let negative =
+            it.days < 0 || it.hours < 0 || it.minutes < 0 || it.seconds < 0 || it.nanoseconds < 0;
+        let mut s = String::from(if negative { "-P" } else { "P" });
+        let _ = write!(
+            s,
+            "{}DT{}H{}M{}",
+            it.days.abs(),
+            it.hours.abs(),
+            it.minutes.abs(),
+            it.seconds.abs()
+        );
+        if it.nanoseconds != 0 {
+            let _ = write!(s, ".{:09}", it.nanoseconds.abs());
+        }
+        s.push('S');
+        s
+    }
+
+    // e.g. P1Y2M
+    pub(crate) fn interval_ym(it: &IntervalYM) -> String {
+        let sign = if it.years < 0 || it.months < 0 {
+            "-"
+        } else {
+            ""
+        };
+        format!("{}P{}Y{}M", sign, it.years.abs(), it.months.abs())
+    }
+}
+
+unsafe fn to_string(ptr: *const c_char, len: u32) -> String {
+    if ptr.is_null() || len == 0 {
+        String::new()
+    } else {
+        let bytes = slice::from_raw_parts(ptr as *const u8, len as usize);
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::ManuallyDrop;
+
+    // Values are kept from being dropped since the test binary isn't linked
+    // with ODPI-C.
+
+    fn data_type(
+        oracle_type: dpiOracleTypeNum,
+        native_type: dpiNativeTypeNum,
+    ) -> ManuallyDrop<ObjectDataType> {
+        ManuallyDrop::new(ObjectDataType {
+            oracle_type,
+            native_type,
+            precision: 0,
+            scale: 0,
+            db_size_in_bytes: 0,
+            object_type: None,
+        })
+    }
+
+    #[test]
+    fn set_strings_to_char_lobs() {
+        for oracle_type in [DPI_ORACLE_TYPE_CLOB, DPI_ORACLE_TYPE_NCLOB] {
+            let data_type = data_type(oracle_type, DPI_NATIVE_TYPE_LOB);
+            String::check_data_type(&data_type).unwrap();
+            let value = ManuallyDrop::new(SqlValue::String("text".into()));
+            let native_type = data_type.scalar_native_type(&value);
+            assert_eq!(native_type, DPI_NATIVE_TYPE_BYTES);
+            let mut data = dpiData::default();
+            let mut bytes = Vec::new();
+            fill_data(&data_type, native_type, &value, &mut data, &mut bytes).unwrap();
+            assert_eq!(bytes, b"text");
+            assert_eq!(unsafe { data.value.asBytes.length }, 4);
+        }
+        let data_type = data_type(DPI_ORACLE_TYPE_BLOB, DPI_NATIVE_TYPE_LOB);
+        assert!(String::check_data_type(&data_type).is_err());
+        assert_eq!(
+            data_type.scalar_native_type(&ManuallyDrop::new(SqlValue::String("text".into()))),
+            DPI_NATIVE_TYPE_LOB
+        );
+    }
+}
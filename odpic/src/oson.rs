@@ -0,0 +1,932 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! OSON, the binary format of Oracle's native JSON data type
+//!
+//! [`decode`] and [`encode`] convert OSON images to and from [`JsonValue`]
+//! without database connections. They are useful to handle OSON stored in
+//! `BLOB` columns or read from replication streams.
+//!
+//! ```
+//! use odpic::oson;
+//! use odpic::JsonValue;
+//!
+//! let value = JsonValue::Object(vec![
+//!     ("name".into(), "Scott".into()),
+//!     ("age".into(), 42i64.into()),
+//! ]);
+//! let image = oson::encode(&value)?;
+//! assert_eq!(oson::decode(&image)?, value);
+//! # Ok::<(), odpic::Error>(())
+//! ```
+
+use crate::number::{
+    decode_binary_double, decode_binary_float, decode_number, encode_binary_double,
+    encode_binary_float, encode_number,
+};
+use crate::vector;
+use crate::{Error, IntervalDS, IntervalYM, JsonValue, Result, Timestamp};
+use std::collections::{HashMap, HashSet};
+
+const MAGIC_BYTES: [u8; 3] = [0xff, 0x4a, 0x5a];
+const VERSION_MAX_FNAME_255: u8 = 1;
+const VERSION_MAX_FNAME_65535: u8 = 3;
+
+// primary flags
+const FLAG_REL_OFFSET_MODE: u16 = 0x0001;
+const FLAG_INLINE_LEAF: u16 = 0x0002;
+const FLAG_NUM_FNAMES_UINT32: u16 = 0x0008;
+const FLAG_IS_SCALAR: u16 = 0x0010;
+const FLAG_HASH_ID_UINT8: u16 = 0x0100;
+const FLAG_NUM_FNAMES_UINT16: u16 = 0x0400;
+const FLAG_FNAMES_SEG_UINT32: u16 = 0x0800;
+const FLAG_TREE_SEG_UINT32: u16 = 0x1000;
+const FLAG_TINY_NODES_STAT: u16 = 0x2000;
+
+// secondary flags
+const FLAG_SEC_FNAMES_SEG_UINT16: u16 = 0x0100;
+
+// node types
+const TYPE_NULL: u8 = 0x30;
+const TYPE_TRUE: u8 = 0x31;
+const TYPE_FALSE: u8 = 0x32;
+const TYPE_STRING_LENGTH_UINT8: u8 = 0x33;
+const TYPE_NUMBER_LENGTH_UINT8: u8 = 0x34;
+const TYPE_BINARY_DOUBLE: u8 = 0x36;
+const TYPE_STRING_LENGTH_UINT16: u8 = 0x37;
+const TYPE_STRING_LENGTH_UINT32: u8 = 0x38;
+const TYPE_TIMESTAMP: u8 = 0x39;
+const TYPE_BINARY_LENGTH_UINT16: u8 = 0x3a;
+const TYPE_BINARY_LENGTH_UINT32: u8 = 0x3b;
+const TYPE_DATE: u8 = 0x3c;
+const TYPE_INTERVAL_YM: u8 = 0x3d;
+const TYPE_INTERVAL_DS: u8 = 0x3e;
+const TYPE_EXTENDED: u8 = 0x7b;
+const TYPE_TIMESTAMP_TZ: u8 = 0x7c;
+const TYPE_TIMESTAMP7: u8 = 0x7d;
+const TYPE_ID: u8 = 0x7e;
+const TYPE_BINARY_FLOAT: u8 = 0x7f;
+const TYPE_OBJECT: u8 = 0x84;
+const TYPE_ARRAY: u8 = 0xc0;
+const TYPE_EXTENDED_VECTOR: u8 = 0x01;
+
+// bits in container node types
+const CONTAINER_BIT: u8 = 0x80;
+const ARRAY_BIT: u8 = 0x40;
+const OFFSET_UINT32_BIT: u8 = 0x20;
+const NUM_CHILDREN_MASK: u8 = 0x18;
+const NUM_CHILDREN_UINT16: u8 = 0x08;
+const NUM_CHILDREN_UINT32: u8 = 0x10;
+const NUM_CHILDREN_SHARED: u8 = 0x18;
+
+const INTERVAL_MID: u32 = 0x8000_0000;
+const INTERVAL_OFFSET: u8 = 60;
+const TZ_HOUR_OFFSET: u8 = 20;
+const TZ_MINUTE_OFFSET: u8 = 60;
+// maximum nesting depth of containers accepted by decode
+const MAX_DEPTH: usize = 1000;
+
+/// Decodes an OSON image.
+pub fn decode(data: &[u8]) -> Result<JsonValue> {
+    let mut d = Decoder {
+        data,
+        pos: 0,
+        tree_seg_pos: 0,
+        relative_offsets: false,
+        field_id_len: 1,
+        field_names: Vec::new(),
+        depth: 0,
+    };
+    if d.bytes(3)? != MAGIC_BYTES {
+        return Err(Error::InvalidOson("invalid magic bytes".into()));
+    }
+    let version = d.u8()?;
+    if version != VERSION_MAX_FNAME_255 && version != VERSION_MAX_FNAME_65535 {
+        return Err(Error::InvalidOson(format!(
+            "unsupported version {}",
+            version
+        )));
+    }
+    let flags = d.u16()?;
+    d.relative_offsets = flags & FLAG_REL_OFFSET_MODE != 0;
+
+    if flags & FLAG_IS_SCALAR != 0 {
+        let tree_seg_size = d.seg_size(flags & FLAG_TREE_SEG_UINT32 != 0)?;
+        d.tree_seg_pos = d.pos;
+        d.check_len(tree_seg_size)?;
+        return d.node();
+    }
+
+    let num_short_names = if flags & FLAG_NUM_FNAMES_UINT32 != 0 {
+        d.field_id_len = 4;
+        d.u32()? as usize
+    } else if flags & FLAG_NUM_FNAMES_UINT16 != 0 {
+        d.field_id_len = 2;
+        d.u16()? as usize
+    } else {
+        d.u8()? as usize
+    };
+    let short_offset_u32 = flags & FLAG_FNAMES_SEG_UINT32 != 0;
+    let short_seg_size = d.seg_size(short_offset_u32)?;
+    let (num_long_names, long_offset_u32, long_seg_size) = if version == VERSION_MAX_FNAME_65535 {
+        let sec_flags = d.u16()?;
+        (
+            d.u32()? as usize,
+            sec_flags & FLAG_SEC_FNAMES_SEG_UINT16 == 0,
+            d.u32()? as usize,
+        )
+    } else {
+        (0, false, 0)
+    };
+    let tree_seg_size = d.seg_size(flags & FLAG_TREE_SEG_UINT32 != 0)?;
+    d.u16()?; // number of tiny nodes
+
+    d.field_names.reserve(num_short_names + num_long_names);
+    d.field_names_seg(num_short_names, 1, short_offset_u32, short_seg_size, 1)?;
+    d.field_names_seg(num_long_names, 2, long_offset_u32, long_seg_size, 2)?;
+    d.tree_seg_pos = d.pos;
+    d.check_len(tree_seg_size)?;
+    d.node()
+}
+
+/// Encodes a JSON value to an OSON image.
+pub fn encode(value: &JsonValue) -> Result<Vec<u8>> {
+    let mut flags = FLAG_INLINE_LEAF;
+    if !matches!(value, JsonValue::Array(_) | JsonValue::Object(_)) {
+        let mut tree = Vec::new();
+        encode_node(&mut tree, value, &FieldNames::default())?;
+        flags |= FLAG_IS_SCALAR;
+        if tree.len() > u16::MAX as usize {
+            flags |= FLAG_TREE_SEG_UINT32;
+        }
+        let mut buf = header(VERSION_MAX_FNAME_255, flags);
+        put_seg_size(&mut buf, tree.len(), flags & FLAG_TREE_SEG_UINT32 != 0)?;
+        buf.extend_from_slice(&tree);
+        return Ok(buf);
+    }
+
+    let names = FieldNames::new(value)?;
+    let mut tree = Vec::new();
+    encode_node(&mut tree, value, &names)?;
+    let short_seg = names.segment(false);
+    let long_seg = names.segment(true);
+    flags |= FLAG_HASH_ID_UINT8 | FLAG_TINY_NODES_STAT;
+    match names.field_id_len {
+        4 => flags |= FLAG_NUM_FNAMES_UINT32,
+        2 => flags |= FLAG_NUM_FNAMES_UINT16,
+        _ => (),
+    }
+    let short_offset_u32 = short_seg.len() > u16::MAX as usize;
+    if short_offset_u32 {
+        flags |= FLAG_FNAMES_SEG_UINT32;
+    }
+    if tree.len() > u16::MAX as usize {
+        flags |= FLAG_TREE_SEG_UINT32;
+    }
+    let has_long_names = names.num_long > 0;
+    let version = if has_long_names {
+        VERSION_MAX_FNAME_65535
+    } else {
+        VERSION_MAX_FNAME_255
+    };
+
+    let mut buf = header(version, flags);
+    put_uint(&mut buf, names.num_short as u32, names.field_id_len);
+    put_seg_size(&mut buf, short_seg.len(), short_offset_u32)?;
+    let long_offset_u32 = long_seg.len() > u16::MAX as usize;
+    if has_long_names {
+        let sec_flags = if long_offset_u32 {
+            0
+        } else {
+            FLAG_SEC_FNAMES_SEG_UINT16
+        };
+        buf.extend_from_slice(&sec_flags.to_be_bytes());
+        buf.extend_from_slice(&(names.num_long as u32).to_be_bytes());
+        buf.extend_from_slice(&(long_seg.len() as u32).to_be_bytes());
+    }
+    put_seg_size(&mut buf, tree.len(), flags & FLAG_TREE_SEG_UINT32 != 0)?;
+    buf.extend_from_slice(&0u16.to_be_bytes()); // number of tiny nodes
+    names.write_segment(&mut buf, false, short_offset_u32, &short_seg);
+    names.write_segment(&mut buf, true, long_offset_u32, &long_seg);
+    buf.extend_from_slice(&tree);
+    Ok(buf)
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    tree_seg_pos: usize,
+    relative_offsets: bool,
+    field_id_len: usize,
+    field_names: Vec<String>,
+    // number of containers being decoded
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn check_len(&self, len: usize) -> Result<()> {
+        if self.data.len() - self.pos < len {
+            Err(Error::InvalidOson("unexpected end of data".into()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        self.check_len(len)?;
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    fn uint(&mut self, len: usize) -> Result<usize> {
+        Ok(match len {
+            1 => self.u8()? as usize,
+            2 => self.u16()? as usize,
+            _ => self.u32()? as usize,
+        })
+    }
+
+    fn seg_size(&mut self, is_u32: bool) -> Result<usize> {
+        self.uint(if is_u32 { 4 } else { 2 })
+    }
+
+    fn seek(&mut self, pos: usize) -> Result<()> {
+        if pos > self.data.len() {
+            return Err(Error::InvalidOson(format!("offset {} out of range", pos)));
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn string(&mut self, len: usize) -> Result<String> {
+        let bytes = self.bytes(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| Error::InvalidOson("invalid UTF-8 string".into()))
+    }
+
+    // Reads the field names segment. The segment consists of hash ids,
+    // offsets to names and names prefixed by their lengths.
+    fn field_names_seg(
+        &mut self,
+        num_names: usize,
+        hash_id_len: usize,
+        offset_u32: bool,
+        seg_size: usize,
+        name_len_len: usize,
+    ) -> Result<()> {
+        if num_names == 0 {
+            return Ok(());
+        }
+        let offset_len = if offset_u32 { 4 } else { 2 };
+        self.bytes(num_names.saturating_mul(hash_id_len))?;
+        let offsets_pos = self.pos;
+        self.bytes(num_names.saturating_mul(offset_len))?;
+        let seg = self.bytes(seg_size)?;
+        let final_pos = self.pos;
+        self.pos = offsets_pos;
+        for _ in 0..num_names {
+            let offset = self.uint(offset_len)?;
+            let name = seg
+                .get(offset..offset + name_len_len)
+                .and_then(|len| {
+                    let len = if name_len_len == 1 {
+                        len[0] as usize
+                    } else {
+                        u16::from_be_bytes([len[0], len[1]]) as usize
+                    };
+                    seg.get(offset + name_len_len..offset + name_len_len + len)
+                })
+                .ok_or_else(|| Error::InvalidOson("field name out of range".into()))?;
+            let name = String::from_utf8(name.to_vec())
+                .map_err(|_| Error::InvalidOson("invalid UTF-8 field name".into()))?;
+            self.field_names.push(name);
+        }
+        self.pos = final_pos;
+        Ok(())
+    }
+
+    fn node(&mut self) -> Result<JsonValue> {
+        let node_type = self.u8()?;
+        if node_type & CONTAINER_BIT != 0 {
+            self.container(node_type)
+        } else {
+            self.scalar(node_type)
+        }
+    }
+
+    // This is separated from `node` to keep the stack frames of the
+    // recursive calls small.
+    #[inline(never)]
+    fn scalar(&mut self, node_type: u8) -> Result<JsonValue> {
+        Ok(match node_type {
+            TYPE_NULL => JsonValue::Null,
+            TYPE_TRUE => JsonValue::Boolean(true),
+            TYPE_FALSE => JsonValue::Boolean(false),
+            TYPE_DATE => JsonValue::Date(decode_date(self.bytes(7)?)),
+            TYPE_TIMESTAMP7 => JsonValue::Timestamp(decode_date(self.bytes(7)?)),
+            TYPE_TIMESTAMP => JsonValue::Timestamp(decode_date(self.bytes(11)?)),
+            TYPE_TIMESTAMP_TZ => JsonValue::TimestampTz(decode_timestamp_tz(self.bytes(13)?)?),
+            TYPE_BINARY_FLOAT => JsonValue::Float(decode_binary_float(self.array()?)),
+            TYPE_BINARY_DOUBLE => JsonValue::Double(decode_binary_double(self.array()?)),
+            TYPE_INTERVAL_DS => JsonValue::IntervalDS(decode_interval_ds(self.bytes(11)?)),
+            TYPE_INTERVAL_YM => JsonValue::IntervalYM(decode_interval_ym(self.bytes(5)?)),
+            TYPE_STRING_LENGTH_UINT8 => {
+                let len = self.u8()? as usize;
+                JsonValue::String(self.string(len)?)
+            }
+            TYPE_STRING_LENGTH_UINT16 => {
+                let len = self.u16()? as usize;
+                JsonValue::String(self.string(len)?)
+            }
+            TYPE_STRING_LENGTH_UINT32 => {
+                let len = self.u32()? as usize;
+                JsonValue::String(self.string(len)?)
+            }
+            TYPE_NUMBER_LENGTH_UINT8 => {
+                let len = self.u8()? as usize;
+                JsonValue::Number(decode_number(self.bytes(len)?)?)
+            }
+            TYPE_ID => {
+                let len = self.u8()? as usize;
+                JsonValue::Id(self.bytes(len)?.to_vec())
+            }
+            TYPE_BINARY_LENGTH_UINT16 => {
+                let len = self.u16()? as usize;
+                JsonValue::Raw(self.bytes(len)?.to_vec())
+            }
+            TYPE_BINARY_LENGTH_UINT32 => {
+                let len = self.u32()? as usize;
+                JsonValue::Raw(self.bytes(len)?.to_vec())
+            }
+            TYPE_EXTENDED => match self.u8()? {
+                TYPE_EXTENDED_VECTOR => {
+                    let len = self.u32()? as usize;
+                    JsonValue::Vector(vector::decode_image(self.bytes(len)?)?)
+                }
+                ext_type => {
+                    return Err(Error::InvalidOson(format!(
+                        "unsupported extended node type 0x{:02x}",
+                        ext_type
+                    )))
+                }
+            },
+            // numbers whose lengths are in the node types
+            _ if matches!(node_type & 0xf0, 0x20 | 0x60) => {
+                let len = (node_type & 0x0f) as usize + 1;
+                JsonValue::Number(decode_number(self.bytes(len)?)?)
+            }
+            _ if matches!(node_type & 0xf0, 0x40 | 0x50) => {
+                let len = (node_type & 0x0f) as usize;
+                JsonValue::Number(decode_number(self.bytes(len)?)?)
+            }
+            // strings whose lengths are in the node types
+            _ if node_type & 0xe0 == 0 => JsonValue::String(self.string(node_type as usize)?),
+            _ => {
+                return Err(Error::InvalidOson(format!(
+                    "unsupported node type 0x{:02x}",
+                    node_type
+                )))
+            }
+        })
+    }
+
+    fn num_children(&mut self, node_type: u8) -> Result<(usize, bool)> {
+        Ok(match node_type & NUM_CHILDREN_MASK {
+            NUM_CHILDREN_SHARED => (0, true),
+            NUM_CHILDREN_UINT16 => (self.u16()? as usize, false),
+            NUM_CHILDREN_UINT32 => (self.u32()? as usize, false),
+            _ => (self.u8()? as usize, false),
+        })
+    }
+
+    fn offset(&mut self, node_type: u8) -> Result<usize> {
+        self.uint(if node_type & OFFSET_UINT32_BIT != 0 {
+            4
+        } else {
+            2
+        })
+    }
+
+    fn container(&mut self, node_type: u8) -> Result<JsonValue> {
+        let children = self.children(node_type)?;
+        self.depth += 1;
+        let mut fields = Vec::new();
+        let mut elements = Vec::new();
+        for (name, pos) in children {
+            self.pos = pos;
+            let value = self.node()?;
+            match name {
+                Some(name) => fields.push((name, value)),
+                None => elements.push(value),
+            }
+        }
+        self.depth -= 1;
+        Ok(if node_type & ARRAY_BIT == 0 {
+            JsonValue::Object(fields)
+        } else {
+            JsonValue::Array(elements)
+        })
+    }
+
+    // Checks the nesting depth and reads the field names and the positions
+    // of the children of a container. This is separated from `container`
+    // to keep the stack frames of the recursive calls small.
+    #[inline(never)]
+    fn children(&mut self, node_type: u8) -> Result<Vec<(Option<String>, usize)>> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::InvalidOson(format!(
+                "containers are nested deeper than {}",
+                MAX_DEPTH
+            )));
+        }
+        let is_object = node_type & ARRAY_BIT == 0;
+        let container_offset = self.pos - self.tree_seg_pos - 1;
+        let (mut num_children, is_shared) = self.num_children(node_type)?;
+        let (mut field_ids_pos, mut offsets_pos);
+        if is_shared {
+            // The object shares field ids with the object at the offset.
+            let offset = self.offset(node_type)?;
+            offsets_pos = self.pos;
+            self.seek(self.tree_seg_pos + offset)?;
+            let shared_type = self.u8()?;
+            let (n, _) = self.num_children(shared_type)?;
+            num_children = n;
+            field_ids_pos = self.pos;
+        } else {
+            field_ids_pos = self.pos;
+            offsets_pos = self.pos;
+            if is_object {
+                offsets_pos += self.field_id_len * num_children;
+            }
+        }
+        let mut children = Vec::new();
+        for _ in 0..num_children {
+            let mut name = None;
+            if is_object {
+                self.seek(field_ids_pos)?;
+                let field_id = self.uint(self.field_id_len)?;
+                name = Some(
+                    field_id
+                        .checked_sub(1)
+                        .and_then(|idx| self.field_names.get(idx))
+                        .cloned()
+                        .ok_or_else(|| {
+                            Error::InvalidOson(format!("invalid field id {}", field_id))
+                        })?,
+                );
+                field_ids_pos = self.pos;
+            }
+            self.seek(offsets_pos)?;
+            let mut offset = self.offset(node_type)?;
+            if self.relative_offsets {
+                offset += container_offset;
+            }
+            // Children follow their parents. This also rejects cycles.
+            if offset <= container_offset {
+                return Err(Error::InvalidOson(format!(
+                    "child offset {} isn't after the container at {}",
+                    offset, container_offset
+                )));
+            }
+            offsets_pos = self.pos;
+            self.seek(self.tree_seg_pos + offset)?;
+            children.push((name, self.pos));
+        }
+        Ok(children)
+    }
+}
+
+// Field names in an object tree, sorted in the order of field ids
+#[derive(Default)]
+struct FieldNames<'a> {
+    names: Vec<FieldName<'a>>,
+    ids: HashMap<&'a str, u32>,
+    num_short: usize,
+    num_long: usize,
+    field_id_len: usize,
+}
+
+struct FieldName<'a> {
+    name: &'a str,
+    hash_id: u32,
+}
+
+impl<'a> FieldNames<'a> {
+    fn new(value: &'a JsonValue) -> Result<FieldNames<'a>> {
+        let mut names = Vec::new();
+        let mut seen = HashSet::new();
+        collect_field_names(value, &mut names, &mut seen)?;
+        let sort_key =
+            |n: &FieldName<'a>| (n.name.len() > 255, n.hash_id & 0xff, n.name.len(), n.name);
+        names.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+        let num_long = names.iter().filter(|n| n.name.len() > 255).count();
+        let ids = names
+            .iter()
+            .enumerate()
+            .map(|(idx, n)| (n.name, idx as u32 + 1))
+            .collect();
+        let field_id_len = match names.len() {
+            0..=0xff => 1,
+            0x100..=0xffff => 2,
+            _ => 4,
+        };
+        Ok(FieldNames {
+            num_short: names.len() - num_long,
+            num_long,
+            names,
+            ids,
+            field_id_len,
+        })
+    }
+
+    fn names(&self, long: bool) -> &[FieldName<'a>] {
+        if long {
+            &self.names[self.num_short..]
+        } else {
+            &self.names[..self.num_short]
+        }
+    }
+
+    fn segment(&self, long: bool) -> Vec<u8> {
+        let mut seg = Vec::new();
+        for n in self.names(long) {
+            if long {
+                seg.extend_from_slice(&(n.name.len() as u16).to_be_bytes());
+            } else {
+                seg.push(n.name.len() as u8);
+            }
+            seg.extend_from_slice(n.name.as_bytes());
+        }
+        seg
+    }
+
+    fn write_segment(&self, buf: &mut Vec<u8>, long: bool, offset_u32: bool, seg: &[u8]) {
+        let names = self.names(long);
+        for n in names {
+            if long {
+                buf.extend_from_slice(&(n.hash_id as u16).to_be_bytes());
+            } else {
+                buf.push(n.hash_id as u8);
+            }
+        }
+        let mut offset = 0;
+        for n in names {
+            put_uint(buf, offset as u32, if offset_u32 { 4 } else { 2 });
+            offset += n.name.len() + if long { 2 } else { 1 };
+        }
+        buf.extend_from_slice(seg);
+    }
+}
+
+fn collect_field_names<'a>(
+    value: &'a JsonValue,
+    names: &mut Vec<FieldName<'a>>,
+    seen: &mut HashSet<&'a str>,
+) -> Result<()> {
+    match value {
+        JsonValue::Array(elements) => {
+            for elem in elements {
+                collect_field_names(elem, names, seen)?;
+            }
+        }
+        JsonValue::Object(fields) => {
+            for (name, value) in fields {
+                if name.len() > u16::MAX as usize {
+                    return Err(Error::InvalidOson(format!(
+                        "field name longer than {} bytes",
+                        u16::MAX
+                    )));
+                }
+                if seen.insert(name) {
+                    names.push(FieldName {
+                        name,
+                        hash_id: hash_id(name.as_bytes()),
+                    });
+                }
+                collect_field_names(value, names, seen)?;
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+// FNV-1a hash of a field name
+fn hash_id(name: &[u8]) -> u32 {
+    name.iter().fold(0x811c9dc5u32, |hash, b| {
+        (hash ^ *b as u32).wrapping_mul(16777619)
+    })
+}
+
+fn header(version: u8, flags: u16) -> Vec<u8> {
+    let mut buf = MAGIC_BYTES.to_vec();
+    buf.push(version);
+    buf.extend_from_slice(&flags.to_be_bytes());
+    buf
+}
+
+fn put_uint(buf: &mut Vec<u8>, value: u32, len: usize) {
+    match len {
+        1 => buf.push(value as u8),
+        2 => buf.extend_from_slice(&(value as u16).to_be_bytes()),
+        _ => buf.extend_from_slice(&value.to_be_bytes()),
+    }
+}
+
+fn put_seg_size(buf: &mut Vec<u8>, size: usize, is_u32: bool) -> Result<()> {
+    let size =
+        u32::try_from(size).map_err(|_| Error::InvalidOson("segment larger than 4 GiB".into()))?;
+    put_uint(buf, size, if is_u32 { 4 } else { 2 });
+    Ok(())
+}
+
+fn put_len(buf: &mut Vec<u8>, len: usize, types: [u8; 3]) -> Result<()> {
+    if len <= u8::MAX as usize && types[0] != 0 {
+        buf.push(types[0]);
+        buf.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        buf.push(types[1]);
+        buf.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        let len =
+            u32::try_from(len).map_err(|_| Error::InvalidOson("value larger than 4 GiB".into()))?;
+        buf.push(types[2]);
+        buf.extend_from_slice(&len.to_be_bytes());
+    }
+    Ok(())
+}
+
+fn encode_node(buf: &mut Vec<u8>, value: &JsonValue, names: &FieldNames) -> Result<()> {
+    match value {
+        JsonValue::Array(elements) => {
+            container_header(buf, TYPE_ARRAY, elements.len())?;
+            let mut offset_pos = buf.len();
+            buf.resize(buf.len() + elements.len() * 4, 0);
+            for elem in elements {
+                let offset = buf.len() as u32;
+                buf[offset_pos..offset_pos + 4].copy_from_slice(&offset.to_be_bytes());
+                offset_pos += 4;
+                encode_node(buf, elem, names)?;
+            }
+        }
+        JsonValue::Object(fields) => {
+            container_header(buf, TYPE_OBJECT, fields.len())?;
+            let id_len = names.field_id_len;
+            let mut id_pos = buf.len();
+            let mut offset_pos = id_pos + fields.len() * id_len;
+            buf.resize(offset_pos + fields.len() * 4, 0);
+            for (name, value) in fields {
+                let id = names.ids[name.as_str()];
+                let mut id_bytes = Vec::with_capacity(4);
+                put_uint(&mut id_bytes, id, id_len);
+                buf[id_pos..id_pos + id_len].copy_from_slice(&id_bytes);
+                id_pos += id_len;
+                let offset = buf.len() as u32;
+                buf[offset_pos..offset_pos + 4].copy_from_slice(&offset.to_be_bytes());
+                offset_pos += 4;
+                encode_node(buf, value, names)?;
+            }
+        }
+        _ => encode_scalar(buf, value)?,
+    }
+    Ok(())
+}
+
+// This is separated from `encode_node` to keep the stack frames of the
+// recursive calls small.
+#[inline(never)]
+fn encode_scalar(buf: &mut Vec<u8>, value: &JsonValue) -> Result<()> {
+    match value {
+        JsonValue::Null => buf.push(TYPE_NULL),
+        JsonValue::Boolean(true) => buf.push(TYPE_TRUE),
+        JsonValue::Boolean(false) => buf.push(TYPE_FALSE),
+        JsonValue::Number(text) => {
+            let bytes = encode_number(text)?;
+            buf.push(TYPE_NUMBER_LENGTH_UINT8);
+            buf.push(bytes.len() as u8);
+            buf.extend_from_slice(&bytes);
+        }
+        JsonValue::Float(value) => {
+            buf.push(TYPE_BINARY_FLOAT);
+            buf.extend_from_slice(&encode_binary_float(*value));
+        }
+        JsonValue::Double(value) => {
+            buf.push(TYPE_BINARY_DOUBLE);
+            buf.extend_from_slice(&encode_binary_double(*value));
+        }
+        JsonValue::String(s) => {
+            put_len(
+                buf,
+                s.len(),
+                [
+                    TYPE_STRING_LENGTH_UINT8,
+                    TYPE_STRING_LENGTH_UINT16,
+                    TYPE_STRING_LENGTH_UINT32,
+                ],
+            )?;
+            buf.extend_from_slice(s.as_bytes());
+        }
+        JsonValue::Raw(bytes) => {
+            put_len(
+                buf,
+                bytes.len(),
+                [0, TYPE_BINARY_LENGTH_UINT16, TYPE_BINARY_LENGTH_UINT32],
+            )?;
+            buf.extend_from_slice(bytes);
+        }
+        JsonValue::Id(bytes) => {
+            let len = u8::try_from(bytes.len())
+                .map_err(|_| Error::InvalidOson("id longer than 255 bytes".into()))?;
+            buf.push(TYPE_ID);
+            buf.push(len);
+            buf.extend_from_slice(bytes);
+        }
+        JsonValue::Date(ts) => {
+            buf.push(TYPE_DATE);
+            encode_date(buf, ts);
+        }
+        JsonValue::Timestamp(ts) => {
+            buf.push(TYPE_TIMESTAMP);
+            encode_date(buf, ts);
+            buf.extend_from_slice(&ts.nanosecond.to_be_bytes());
+        }
+        JsonValue::TimestampTz(ts) => {
+            if !(-12..=14).contains(&ts.tz_hour_offset)
+                || !(-59..=59).contains(&ts.tz_minute_offset)
+            {
+                return Err(Error::InvalidOson(format!(
+                    "invalid time zone offset {:+}:{:02}",
+                    ts.tz_hour_offset, ts.tz_minute_offset
+                )));
+            }
+            buf.push(TYPE_TIMESTAMP_TZ);
+            // The date and time are stored in UTC.
+            let utc = ts.add_minutes(-ts.tz_offset_minutes());
+            encode_date(buf, &utc);
+            buf.extend_from_slice(&ts.nanosecond.to_be_bytes());
+            buf.push((ts.tz_hour_offset as i16 + TZ_HOUR_OFFSET as i16) as u8);
+            buf.push((ts.tz_minute_offset as i16 + TZ_MINUTE_OFFSET as i16) as u8);
+        }
+        JsonValue::IntervalDS(it) => {
+            let days = interval_field(it.days, 999_999_999, "days")?;
+            let hours = interval_field(it.hours, 23, "hours")?;
+            let minutes = interval_field(it.minutes, 59, "minutes")?;
+            let seconds = interval_field(it.seconds, 59, "seconds")?;
+            let nanoseconds = interval_field(it.nanoseconds, 999_999_999, "nanoseconds")?;
+            buf.push(TYPE_INTERVAL_DS);
+            buf.extend_from_slice(&(days as u32).wrapping_add(INTERVAL_MID).to_be_bytes());
+            buf.push((hours + INTERVAL_OFFSET as i32) as u8);
+            buf.push((minutes + INTERVAL_OFFSET as i32) as u8);
+            buf.push((seconds + INTERVAL_OFFSET as i32) as u8);
+            buf.extend_from_slice(
+                &(nanoseconds as u32)
+                    .wrapping_add(INTERVAL_MID)
+                    .to_be_bytes(),
+            );
+        }
+        JsonValue::IntervalYM(it) => {
+            let years = interval_field(it.years, 999_999_999, "years")?;
+            let months = interval_field(it.months, 11, "months")?;
+            buf.push(TYPE_INTERVAL_YM);
+            buf.extend_from_slice(&(years as u32).wrapping_add(INTERVAL_MID).to_be_bytes());
+            buf.push((months + INTERVAL_OFFSET as i32) as u8);
+        }
+        JsonValue::Vector(vector) => {
+            let image = vector::encode_image(vector)?;
+            buf.push(TYPE_EXTENDED);
+            buf.push(TYPE_EXTENDED_VECTOR);
+            buf.extend_from_slice(&(image.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&image);
+        }
+        JsonValue::Array(_) | JsonValue::Object(_) => unreachable!(),
+    }
+    Ok(())
+}
+
+fn container_header(buf: &mut Vec<u8>, node_type: u8, num_children: usize) -> Result<()> {
+    // offsets are always written in uint32.
+    let node_type = node_type | OFFSET_UINT32_BIT;
+    if num_children <= u8::MAX as usize {
+        buf.push(node_type);
+        buf.push(num_children as u8);
+    } else if num_children <= u16::MAX as usize {
+        buf.push(node_type | NUM_CHILDREN_UINT16);
+        buf.extend_from_slice(&(num_children as u16).to_be_bytes());
+    } else {
+        let num_children = u32::try_from(num_children)
+            .map_err(|_| Error::InvalidOson("too many children".into()))?;
+        buf.push(node_type | NUM_CHILDREN_UINT32);
+        buf.extend_from_slice(&num_children.to_be_bytes());
+    }
+    Ok(())
+}
+
+// Decodes the internal format of Oracle DATE. When it has 11 bytes, the last
+// four bytes are fractional seconds.
+fn decode_date(bytes: &[u8]) -> Timestamp {
+    let mut ts = Timestamp::new(
+        (bytes[0] as i16 - 100) * 100 + (bytes[1] as i16 - 100),
+        bytes[2],
+        bytes[3],
+        bytes[4].wrapping_sub(1),
+        bytes[5].wrapping_sub(1),
+        bytes[6].wrapping_sub(1),
+    );
+    if bytes.len() >= 11 {
+        ts.nanosecond = u32::from_be_bytes([bytes[7], bytes[8], bytes[9], bytes[10]]);
+    }
+    ts
+}
+
+fn decode_timestamp_tz(bytes: &[u8]) -> Result<Timestamp> {
+    if bytes[11] & 0x80 != 0 {
+        return Err(Error::InvalidOson(
+            "time zone region ids are not supported".into(),
+        ));
+    }
+    let tz_hour = bytes[11] as i16 - TZ_HOUR_OFFSET as i16;
+    let tz_minute = bytes[12] as i16 - TZ_MINUTE_OFFSET as i16;
+    if !(-12..=14).contains(&tz_hour) || !(-59..=59).contains(&tz_minute) {
+        return Err(Error::InvalidOson(format!(
+            "invalid time zone offset {:+}:{:02}",
+            tz_hour, tz_minute
+        )));
+    }
+    let utc = decode_date(bytes);
+    let minutes = tz_hour as i32 * 60 + tz_minute as i32;
+    Ok(utc
+        .add_minutes(minutes)
+        .and_tz_offset(tz_hour as i8, tz_minute as i8))
+}
+
+// Returns `value` when it is within ±`max`, the range of the interval field.
+fn interval_field(value: i32, max: i32, name: &str) -> Result<i32> {
+    if (-max..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(Error::InvalidOson(format!(
+            "interval {} {} is out of the range ±{}",
+            name, value, max
+        )))
+    }
+}
+
+fn encode_date(buf: &mut Vec<u8>, ts: &Timestamp) {
+    buf.push((ts.year / 100 + 100) as u8);
+    buf.push((ts.year % 100 + 100) as u8);
+    buf.push(ts.month);
+    buf.push(ts.day);
+    buf.push(ts.hour + 1);
+    buf.push(ts.minute + 1);
+    buf.push(ts.second + 1);
+}
+
+fn decode_interval_ds(bytes: &[u8]) -> IntervalDS {
+    let offset = INTERVAL_OFFSET as i32;
+    IntervalDS::new(
+        u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).wrapping_sub(INTERVAL_MID)
+            as i32,
+        bytes[4] as i32 - offset,
+        bytes[5] as i32 - offset,
+        bytes[6] as i32 - offset,
+        u32::from_be_bytes([bytes[7], bytes[8], bytes[9], bytes[10]]).wrapping_sub(INTERVAL_MID)
+            as i32,
+    )
+}
+
+fn decode_interval_ym(bytes: &[u8]) -> IntervalYM {
+    IntervalYM::new(
+        u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).wrapping_sub(INTERVAL_MID)
+            as i32,
+        bytes[4] as i32 - INTERVAL_OFFSET as i32,
+    )
+}
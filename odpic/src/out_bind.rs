@@ -0,0 +1,142 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::var::Var;
+use crate::{BindIndex, Error, FromSql, Result, Statement, ToSql};
+use std::marker::PhantomData;
+
+/// Handle of an OUT or IN OUT bind variable which receives values of `T`
+///
+/// It is returned by [`Statement::bind_out`] and its variants and is used
+/// to get values from the statement after execution.
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// // PL/SQL OUT and IN OUT parameters
+/// let mut stmt = conn.prepare("begin :1 := upper(:2); :3 := :3 * 2; end;")?;
+/// let upper = stmt.bind_out_with_size::<String, _>(1, 100)?;
+/// stmt.bind(2, &"hello")?;
+/// let doubled = stmt.bind_inout::<i64, _>(3, &21)?;
+/// stmt.execute()?;
+/// assert_eq!(stmt.out_value(&upper)?, "HELLO");
+/// assert_eq!(stmt.out_value(&doubled)?, 42);
+///
+/// // DML RETURNING INTO with array DML
+/// let mut stmt = conn.prepare(
+///     "update emp set sal = sal * 1.1 where deptno = :1 returning empno into :2",
+/// )?;
+/// let empno = stmt.bind_out::<i64, _>(2)?;
+/// stmt.execute_many([[10], [20]], &odpic::BatchOptions::new())?;
+/// for (deptno, empnos) in [10, 20].iter().zip(stmt.returned_values(&empno)?) {
+///     println!("updated {:?} in dept {}", empnos, deptno);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OutBind<T> {
+    key: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> OutBind<T> {
+    fn new(key: String) -> OutBind<T> {
+        OutBind {
+            key,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for OutBind<T> {
+    fn clone(&self) -> Self {
+        OutBind::new(self.key.clone())
+    }
+}
+
+impl Statement<'_> {
+    /// Binds an OUT variable of [`FromSql::sql_type`] at `index`.
+    ///
+    /// The default size of variable-length types such as `String` may be
+    /// changed by [`Statement::bind_out_with_size`].
+    pub fn bind_out<T: FromSql, I: BindIndex>(&mut self, index: I) -> Result<OutBind<T>> {
+        let var = Var::new(self.connection(), T::sql_type(), 1)?;
+        let key = index.key();
+        self.bind_var_with_direction(index, var, true)?;
+        Ok(OutBind::new(key))
+    }
+
+    /// Binds an OUT variable whose buffer size is `size` bytes.
+    pub fn bind_out_with_size<T: FromSql, I: BindIndex>(
+        &mut self,
+        index: I,
+        size: u32,
+    ) -> Result<OutBind<T>> {
+        let var = Var::new(self.connection(), T::sql_type().with_size(size), 1)?;
+        let key = index.key();
+        self.bind_var_with_direction(index, var, true)?;
+        Ok(OutBind::new(key))
+    }
+
+    /// Binds an IN OUT variable initialized with `value`.
+    pub fn bind_inout<T: FromSql, I: BindIndex>(
+        &mut self,
+        index: I,
+        value: &dyn ToSql,
+    ) -> Result<OutBind<T>> {
+        self.bind_inout_with_size(index, value, T::sql_type().size)
+    }
+
+    /// Binds an IN OUT variable initialized with `value` whose buffer size
+    /// is at least `size` bytes.
+    pub fn bind_inout_with_size<T: FromSql, I: BindIndex>(
+        &mut self,
+        index: I,
+        value: &dyn ToSql,
+        size: u32,
+    ) -> Result<OutBind<T>> {
+        let sql_type = value.sql_type();
+        let sql_type = sql_type.with_size(sql_type.size.max(size));
+        let mut var = Var::new(self.connection(), sql_type, 1)?;
+        var.set(0, value)?;
+        let key = index.key();
+        self.bind_var_with_direction(index, var, true)?;
+        Ok(OutBind::new(key))
+    }
+
+    /// Gets the value of the OUT variable after execution.
+    ///
+    /// Use [`Statement::returned_values`] for DML RETURNING INTO.
+    pub fn out_value<T: FromSql>(&self, bind: &OutBind<T>) -> Result<T> {
+        T::from_sql(&self.bound_var_by_key(&bind.key)?.get(0)?)
+    }
+
+    /// Gets values returned by DML RETURNING INTO after execution.
+    ///
+    /// The outer vector has an element per iteration: one for
+    /// [`Statement::execute`] and one per row for
+    /// [`Statement::execute_many`]. The inner vector has values of rows
+    /// affected by the iteration.
+    pub fn returned_values<T: FromSql>(&self, bind: &OutBind<T>) -> Result<Vec<Vec<T>>> {
+        let values = self.returned_by_key(&bind.key).ok_or_else(|| {
+            Error::InvalidOperation(format!("no values are returned at {}", bind.key))
+        })?;
+        values
+            .iter()
+            .map(|iter| iter.iter().map(T::from_sql).collect())
+            .collect()
+    }
+}
@@ -0,0 +1,607 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Query-by-example (QBE) filters of SODA
+//!
+//! [`Filter`] builds QBE expressions with typed operators and serializes
+//! them to the JSON text passed to [`SodaOperation::qbe`]. Field paths are
+//! dot-separated names with optional array steps, such as `address.city`
+//! and `items[*].qty`.
+//!
+//! ```
+//! use odpic::qbe::{Cond, Expr, Filter, OrderBy};
+//!
+//! # fn main() -> odpic::Result<()> {
+//! let filter = Filter::new()
+//!     .field("status", Cond::eq("open"))
+//!     .or([
+//!         Expr::field("qty", Cond::gt(10)),
+//!         Expr::field("items[*].sku", Cond::starts_with("X-")),
+//!     ])
+//!     .order_by(OrderBy::desc("qty"));
+//! assert_eq!(
+//!     filter.to_json()?,
+//!     r#"{"$query":{"status":{"$eq":"open"},"$or":[{"qty":{"$gt":10}},{"items[*].sku":{"$startsWith":"X-"}}]},"$orderby":{"$fields":[{"path":"qty","order":"desc"}]}}"#
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SodaOperation::qbe`]: crate::soda::SodaOperation::qbe
+
+use crate::{Error, Result};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Scalar or array value in QBE conditions
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// Non-finite numbers make [`Filter::to_json`] fail, as JSON can't hold
+    /// them.
+    Float(f64),
+    String(String),
+    Array(Vec<Value>),
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty)*) => {
+        $(impl From<$t> for Value {
+            fn from(value: $t) -> Value {
+                Value::Int(value.into())
+            }
+        })*
+    };
+}
+
+impl_from_int!(i8 i16 i32 i64 u8 u16 u32);
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value {
+        Value::Bool(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Value {
+        Value::Float(value.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Value {
+        Value::Float(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Value {
+        Value::String(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value {
+        Value::String(value)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Value {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Value {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+/// Condition on the value of a field
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cond {
+    /// `$eq`
+    Eq(Value),
+    /// `$ne`
+    Ne(Value),
+    /// `$gt`
+    Gt(Value),
+    /// `$gte`
+    Gte(Value),
+    /// `$lt`
+    Lt(Value),
+    /// `$lte`
+    Lte(Value),
+    /// `$in`
+    In(Vec<Value>),
+    /// `$nin`
+    Nin(Vec<Value>),
+    /// `$exists`
+    Exists(bool),
+    /// `$startsWith`
+    StartsWith(String),
+    /// `$regex`
+    Regex(String),
+    /// `$not`
+    Not(Box<Cond>),
+    /// all conditions, written as operators of one object such as
+    /// `{"$gt":1,"$lt":5}`
+    All(Vec<Cond>),
+}
+
+impl Cond {
+    pub fn eq<V: Into<Value>>(value: V) -> Cond {
+        Cond::Eq(value.into())
+    }
+
+    pub fn ne<V: Into<Value>>(value: V) -> Cond {
+        Cond::Ne(value.into())
+    }
+
+    pub fn gt<V: Into<Value>>(value: V) -> Cond {
+        Cond::Gt(value.into())
+    }
+
+    pub fn gte<V: Into<Value>>(value: V) -> Cond {
+        Cond::Gte(value.into())
+    }
+
+    pub fn lt<V: Into<Value>>(value: V) -> Cond {
+        Cond::Lt(value.into())
+    }
+
+    pub fn lte<V: Into<Value>>(value: V) -> Cond {
+        Cond::Lte(value.into())
+    }
+
+    pub fn is_in<I, V>(values: I) -> Cond
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        Cond::In(values.into_iter().map(Into::into).collect())
+    }
+
+    pub fn not_in<I, V>(values: I) -> Cond
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        Cond::Nin(values.into_iter().map(Into::into).collect())
+    }
+
+    pub fn exists(exists: bool) -> Cond {
+        Cond::Exists(exists)
+    }
+
+    pub fn starts_with(prefix: &str) -> Cond {
+        Cond::StartsWith(prefix.into())
+    }
+
+    pub fn regex(pattern: &str) -> Cond {
+        Cond::Regex(pattern.into())
+    }
+
+    /// Negates the condition with `$not`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Cond {
+        Cond::Not(Box::new(self))
+    }
+
+    /// Combines the condition with `other` on the same field.
+    pub fn and(self, other: Cond) -> Cond {
+        match self {
+            Cond::All(mut conds) => {
+                conds.push(other);
+                Cond::All(conds)
+            }
+            cond => Cond::All(vec![cond, other]),
+        }
+    }
+
+    // Pushes conditions in `self` other than `Cond::All`.
+    fn leaves<'a>(&'a self, leaves: &mut Vec<&'a Cond>) {
+        match self {
+            Cond::All(conds) => conds.iter().for_each(|cond| cond.leaves(leaves)),
+            cond => leaves.push(cond),
+        }
+    }
+
+    fn operator(&self) -> &'static str {
+        match self {
+            Cond::Eq(_) => "$eq",
+            Cond::Ne(_) => "$ne",
+            Cond::Gt(_) => "$gt",
+            Cond::Gte(_) => "$gte",
+            Cond::Lt(_) => "$lt",
+            Cond::Lte(_) => "$lte",
+            Cond::In(_) => "$in",
+            Cond::Nin(_) => "$nin",
+            Cond::Exists(_) => "$exists",
+            Cond::StartsWith(_) => "$startsWith",
+            Cond::Regex(_) => "$regex",
+            Cond::Not(_) => "$not",
+            Cond::All(_) => "$and",
+        }
+    }
+
+    // Returns true when the condition is written as an object without
+    // repeated operators.
+    fn is_single_object(&self) -> bool {
+        let mut leaves = Vec::new();
+        self.leaves(&mut leaves);
+        let mut operators = HashSet::new();
+        leaves.iter().all(|cond| {
+            operators.insert(cond.operator())
+                && match cond {
+                    Cond::Not(cond) => cond.is_single_object(),
+                    _ => true,
+                }
+        })
+    }
+
+    fn write(&self, out: &mut String) -> Result<()> {
+        let mut leaves = Vec::new();
+        self.leaves(&mut leaves);
+        out.push('{');
+        for (i, cond) in leaves.into_iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_string(out, cond.operator());
+            out.push(':');
+            match cond {
+                Cond::Eq(v)
+                | Cond::Ne(v)
+                | Cond::Gt(v)
+                | Cond::Gte(v)
+                | Cond::Lt(v)
+                | Cond::Lte(v) => write_value(out, v)?,
+                Cond::In(v) | Cond::Nin(v) => write_values(out, v)?,
+                Cond::Exists(v) => out.push_str(if *v { "true" } else { "false" }),
+                Cond::StartsWith(v) | Cond::Regex(v) => write_string(out, v),
+                Cond::Not(cond) => cond.write(out)?,
+                Cond::All(_) => unreachable!(),
+            }
+        }
+        out.push('}');
+        Ok(())
+    }
+}
+
+// Returns the key of the member written by `write_field`.
+fn field_key<'a>(path: &'a str, cond: &Cond) -> &'a str {
+    match cond {
+        _ if cond.is_single_object() => path,
+        Cond::Not(_) => "$nor",
+        _ => "$and",
+    }
+}
+
+// Writes a condition on a field as a member of an object. Conditions with
+// repeated operators are split into `$and` of conditions on the field, and
+// their negation is written by `$nor`.
+fn write_field(out: &mut String, path: &str, cond: &Cond) -> Result<()> {
+    if cond.is_single_object() {
+        write_string(out, path);
+        out.push(':');
+        return cond.write(out);
+    }
+    match cond {
+        Cond::Not(cond) => write_objects(out, "$nor", &[cond.as_ref()], |out, cond| {
+            write_field(out, path, cond)
+        }),
+        cond => {
+            let mut leaves = Vec::new();
+            cond.leaves(&mut leaves);
+            write_objects(out, "$and", &leaves, |out, cond| {
+                write_field(out, path, cond)
+            })
+        }
+    }
+}
+
+// Writes a member whose value is an array of objects, each of which has
+// one member written by `write`.
+fn write_objects<T, F>(out: &mut String, key: &str, items: &[T], write: F) -> Result<()>
+where
+    F: Fn(&mut String, &T) -> Result<()>,
+{
+    write_string(out, key);
+    out.push_str(":[");
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('{');
+        write(out, item)?;
+        out.push('}');
+    }
+    out.push(']');
+    Ok(())
+}
+
+// Writes expressions as members of an object. They are wrapped in `$and`
+// when their keys repeat.
+fn write_members(out: &mut String, exprs: &[Expr]) -> Result<()> {
+    let mut keys = HashSet::new();
+    if !exprs.iter().all(|expr| keys.insert(expr.key())) {
+        return write_objects(out, "$and", exprs, Expr::write_member);
+    }
+    for (i, expr) in exprs.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        Expr::write_member(out, expr)?;
+    }
+    Ok(())
+}
+
+/// Expression of a filter
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    /// condition on the field at the path
+    Field(String, Cond),
+    /// `$and`
+    And(Vec<Expr>),
+    /// `$or`
+    Or(Vec<Expr>),
+    /// `$nor`
+    Nor(Vec<Expr>),
+    /// `$id`, which matches documents with the keys
+    Id(Vec<String>),
+}
+
+impl Expr {
+    pub fn field(path: &str, cond: Cond) -> Expr {
+        Expr::Field(path.into(), cond)
+    }
+
+    pub fn and<I: IntoIterator<Item = Expr>>(exprs: I) -> Expr {
+        Expr::And(exprs.into_iter().collect())
+    }
+
+    pub fn or<I: IntoIterator<Item = Expr>>(exprs: I) -> Expr {
+        Expr::Or(exprs.into_iter().collect())
+    }
+
+    pub fn nor<I: IntoIterator<Item = Expr>>(exprs: I) -> Expr {
+        Expr::Nor(exprs.into_iter().collect())
+    }
+
+    pub fn id<I, S>(keys: I) -> Expr
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Expr::Id(keys.into_iter().map(Into::into).collect())
+    }
+
+    fn key(&self) -> &str {
+        match self {
+            Expr::Field(path, cond) => field_key(path, cond),
+            Expr::And(_) => "$and",
+            Expr::Or(_) => "$or",
+            Expr::Nor(_) => "$nor",
+            Expr::Id(_) => "$id",
+        }
+    }
+
+    // Writes the expression as a member of an object.
+    fn write_member(out: &mut String, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::Field(path, cond) => write_field(out, path, cond),
+            Expr::Id(keys) => {
+                write_string(out, "$id");
+                out.push(':');
+                match keys.as_slice() {
+                    [key] => write_string(out, key),
+                    _ => {
+                        out.push('[');
+                        for (i, key) in keys.iter().enumerate() {
+                            if i > 0 {
+                                out.push(',');
+                            }
+                            write_string(out, key);
+                        }
+                        out.push(']');
+                    }
+                }
+                Ok(())
+            }
+            Expr::And(exprs) | Expr::Or(exprs) | Expr::Nor(exprs) => {
+                write_objects(out, expr.key(), exprs, Expr::write_member)
+            }
+        }
+    }
+}
+
+/// Sort order of [`Filter::order_by`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderBy {
+    path: String,
+    descending: bool,
+    datatype: Option<String>,
+}
+
+impl OrderBy {
+    pub fn asc(path: &str) -> OrderBy {
+        OrderBy {
+            path: path.into(),
+            descending: false,
+            datatype: None,
+        }
+    }
+
+    pub fn desc(path: &str) -> OrderBy {
+        OrderBy {
+            path: path.into(),
+            descending: true,
+            datatype: None,
+        }
+    }
+
+    /// Sets the SQL type to which values are converted for sorting, such
+    /// as `number`, `varchar2`, `date` and `timestamp`.
+    pub fn datatype(mut self, datatype: &str) -> OrderBy {
+        self.datatype = Some(datatype.into());
+        self
+    }
+}
+
+/// QBE filter
+///
+/// Expressions are combined by AND. The filter is written under `$query`
+/// when sort orders are set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    exprs: Vec<Expr>,
+    order_by: Vec<OrderBy>,
+}
+
+impl Filter {
+    /// Creates a filter matching all documents.
+    pub fn new() -> Filter {
+        Filter::default()
+    }
+
+    pub fn expr(mut self, expr: Expr) -> Filter {
+        self.exprs.push(expr);
+        self
+    }
+
+    pub fn field(self, path: &str, cond: Cond) -> Filter {
+        self.expr(Expr::field(path, cond))
+    }
+
+    pub fn and<I: IntoIterator<Item = Expr>>(self, exprs: I) -> Filter {
+        self.expr(Expr::and(exprs))
+    }
+
+    pub fn or<I: IntoIterator<Item = Expr>>(self, exprs: I) -> Filter {
+        self.expr(Expr::or(exprs))
+    }
+
+    pub fn nor<I: IntoIterator<Item = Expr>>(self, exprs: I) -> Filter {
+        self.expr(Expr::nor(exprs))
+    }
+
+    pub fn id<I, S>(self, keys: I) -> Filter
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.expr(Expr::id(keys))
+    }
+
+    /// Appends a sort order.
+    pub fn order_by(mut self, order: OrderBy) -> Filter {
+        self.order_by.push(order);
+        self
+    }
+
+    /// Returns the filter in JSON.
+    ///
+    /// Expressions and operators whose keys repeat are combined by `$and`.
+    /// It fails when values contain non-finite numbers, which JSON can't
+    /// hold.
+    pub fn to_json(&self) -> Result<String> {
+        let mut out = String::new();
+        out.push('{');
+        if self.order_by.is_empty() {
+            write_members(&mut out, &self.exprs)?;
+        } else {
+            out.push_str("\"$query\":{");
+            write_members(&mut out, &self.exprs)?;
+            out.push_str("},\"$orderby\":{\"$fields\":[");
+            for (i, order) in self.order_by.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str("{\"path\":");
+                write_string(&mut out, &order.path);
+                if let Some(datatype) = &order.datatype {
+                    out.push_str(",\"datatype\":");
+                    write_string(&mut out, datatype);
+                }
+                out.push_str(",\"order\":");
+                out.push_str(if order.descending {
+                    "\"desc\""
+                } else {
+                    "\"asc\""
+                });
+                out.push('}');
+            }
+            out.push_str("]}");
+        }
+        out.push('}');
+        Ok(out)
+    }
+}
+
+pub(crate) fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_value(out: &mut String, value: &Value) -> Result<()> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+        Value::Int(v) => {
+            let _ = write!(out, "{}", v);
+        }
+        Value::Float(v) if v.is_finite() => {
+            let _ = write!(out, "{}", v);
+        }
+        Value::Float(v) => {
+            return Err(Error::InvalidConversion(format!(
+                "{} can't be written in JSON",
+                v
+            )))
+        }
+        Value::String(v) => write_string(out, v),
+        Value::Array(values) => write_values(out, values)?,
+    }
+    Ok(())
+}
+
+fn write_values(out: &mut String, values: &[Value]) -> Result<()> {
+    out.push('[');
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_value(out, value)?;
+    }
+    out.push(']');
+    Ok(())
+}
@@ -0,0 +1,479 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Query result cache invalidated by continuous query notification
+//!
+//! [`QueryCache`] registers queries to a subscription with [`Qos::QUERY`]
+//! and keeps their rows until notifications report that their results
+//! changed. The connection must be created by
+//! [`Connection::connect_with_events`].
+//!
+//! ```no_run
+//! # use odpic::Connection;
+//! # use odpic::query_cache::QueryCache;
+//! # fn f(conn: &Connection) -> odpic::Result<()> {
+//! let mut cache = QueryCache::new(conn)?;
+//! let rows = cache.get("select code, name from countries")?;
+//! for row in rows.iter() {
+//!     let code: String = row.get(0)?;
+//!     println!("{}", code);
+//! }
+//! // The query is executed again only after the table changes.
+//! let rows = cache.get("select code, name from countries")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::subscr::{EventType, Namespace, Qos, SubscrCreateParams, SubscrMessage, Subscription};
+use crate::{Connection, Result, Row, Statement};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+// State updated by notifications in threads of ODPI-C
+#[derive(Debug, Default)]
+struct Notified {
+    changed_query_ids: HashSet<u64>,
+    deregistered: bool,
+}
+
+// Database operations of `Cache`, which are replaced in tests
+trait Backend {
+    type Subscr;
+    type Rows: Clone;
+
+    // Subscribes with a callback recording notifications to `notified`.
+    fn subscribe(&self, notified: &Arc<Mutex<Notified>>) -> Result<Self::Subscr>;
+
+    // Executes `sql` registering it to the subscription and returns its
+    // query id and rows.
+    fn register(&self, subscr: &Self::Subscr, sql: &str) -> Result<(u64, Self::Rows)>;
+
+    fn fetch(&self, sql: &str) -> Result<Self::Rows>;
+}
+
+struct ConnBackend<'conn> {
+    conn: &'conn Connection,
+    params: SubscrCreateParams,
+}
+
+impl<'conn> Backend for ConnBackend<'conn> {
+    type Subscr = Subscription<'conn>;
+    type Rows = Arc<[Row]>;
+
+    fn subscribe(&self, notified: &Arc<Mutex<Notified>>) -> Result<Subscription<'conn>> {
+        let notified = notified.clone();
+        self.conn
+            .subscribe(&self.params, move |msg: SubscrMessage| {
+                let mut notified = notified.lock().unwrap_or_else(|err| err.into_inner());
+                match msg.event_type {
+                    EventType::QueryChange => {
+                        notified
+                            .changed_query_ids
+                            .extend(msg.queries.iter().map(|query| query.id));
+                    }
+                    EventType::Dereg => notified.deregistered = true,
+                    _ => {}
+                }
+                if !msg.registered {
+                    notified.deregistered = true;
+                }
+            })
+    }
+
+    fn register(&self, subscr: &Subscription<'conn>, sql: &str) -> Result<(u64, Arc<[Row]>)> {
+        let mut stmt = subscr.prepare(sql)?;
+        let rows = fetch(&mut stmt)?;
+        Ok((stmt.subscr_query_id()?, rows))
+    }
+
+    fn fetch(&self, sql: &str) -> Result<Arc<[Row]>> {
+        fetch(&mut self.conn.prepare(sql)?)
+    }
+}
+
+fn fetch(stmt: &mut Statement) -> Result<Arc<[Row]>> {
+    stmt.query()?.collect()
+}
+
+struct Entry<R> {
+    query_id: u64,
+    // None after the result changed
+    rows: Option<R>,
+}
+
+struct Cache<B: Backend> {
+    backend: B,
+    subscr: B::Subscr,
+    notified: Arc<Mutex<Notified>>,
+    entries: HashMap<String, Entry<B::Rows>>,
+}
+
+impl<B: Backend> Cache<B> {
+    fn new(backend: B) -> Result<Cache<B>> {
+        let notified = Arc::new(Mutex::new(Notified::default()));
+        let subscr = backend.subscribe(&notified)?;
+        Ok(Cache {
+            backend,
+            subscr,
+            notified,
+            entries: HashMap::new(),
+        })
+    }
+
+    fn get(&mut self, sql: &str) -> Result<B::Rows> {
+        self.apply_notifications()?;
+        match self.entries.get_mut(sql) {
+            Some(Entry {
+                rows: Some(rows), ..
+            }) => Ok(rows.clone()),
+            Some(entry) => {
+                let rows = self.backend.fetch(sql)?;
+                entry.rows = Some(rows.clone());
+                Ok(rows)
+            }
+            None => {
+                let (query_id, rows) = self.backend.register(&self.subscr, sql)?;
+                self.entries.insert(
+                    sql.to_string(),
+                    Entry {
+                        query_id,
+                        rows: Some(rows.clone()),
+                    },
+                );
+                Ok(rows)
+            }
+        }
+    }
+
+    fn is_fresh(&self, sql: &str) -> bool {
+        let notified = self.notified.lock().unwrap_or_else(|err| err.into_inner());
+        !notified.deregistered
+            && self.entries.get(sql).is_some_and(|entry| {
+                entry.rows.is_some() && !notified.changed_query_ids.contains(&entry.query_id)
+            })
+    }
+
+    fn refresh(&mut self) -> Result<usize> {
+        self.apply_notifications()?;
+        let mut count = 0;
+        for (sql, entry) in &mut self.entries {
+            if entry.rows.is_none() {
+                entry.rows = Some(self.backend.fetch(sql)?);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    // Drops rows of changed queries and subscribes again after
+    // deregistration.
+    fn apply_notifications(&mut self) -> Result<()> {
+        let changed = {
+            let mut notified = self.notified.lock().unwrap_or_else(|err| err.into_inner());
+            if notified.deregistered {
+                None
+            } else {
+                Some(mem::take(&mut notified.changed_query_ids))
+            }
+        };
+        match changed {
+            Some(changed) => {
+                for entry in self.entries.values_mut() {
+                    if changed.contains(&entry.query_id) {
+                        entry.rows = None;
+                    }
+                }
+            }
+            // The state is replaced only after all queries are registered
+            // again. Otherwise the deregistered flag stays and the next
+            // call tries again.
+            None => {
+                // A new state keeps notifications of the old subscription,
+                // which may be still delivered, away from the new one.
+                let notified = Arc::new(Mutex::new(Notified::default()));
+                let subscr = self.backend.subscribe(&notified)?;
+                let mut entries = HashMap::with_capacity(self.entries.len());
+                for sql in self.entries.keys() {
+                    let (query_id, rows) = self.backend.register(&subscr, sql)?;
+                    entries.insert(
+                        sql.clone(),
+                        Entry {
+                            query_id,
+                            rows: Some(rows),
+                        },
+                    );
+                }
+                self.subscr = subscr;
+                self.notified = notified;
+                self.entries = entries;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cache of query results refreshed when they change
+///
+/// Results are refreshed lazily by [`QueryCache::get`] or eagerly by
+/// [`QueryCache::refresh`]. When the subscription is unregistered, such as
+/// by a timeout or a database restart, the cache subscribes again and
+/// registers cached queries again.
+pub struct QueryCache<'conn> {
+    cache: Cache<ConnBackend<'conn>>,
+}
+
+impl<'conn> QueryCache<'conn> {
+    /// Creates a cache with a subscription with [`Qos::QUERY`].
+    pub fn new(conn: &'conn Connection) -> Result<QueryCache<'conn>> {
+        QueryCache::with_params(conn, SubscrCreateParams::new(Namespace::DbChange))
+    }
+
+    /// Creates a cache with a subscription created by `params`, whose
+    /// namespace and QOS are overwritten with [`Namespace::DbChange`] and
+    /// [`Qos::QUERY`] added.
+    pub fn with_params(
+        conn: &'conn Connection,
+        params: SubscrCreateParams,
+    ) -> Result<QueryCache<'conn>> {
+        let params = params.namespace(Namespace::DbChange).add_qos(Qos::QUERY);
+        Ok(QueryCache {
+            cache: Cache::new(ConnBackend { conn, params })?,
+        })
+    }
+
+    /// Returns the subscription.
+    pub fn subscription(&self) -> &Subscription<'conn> {
+        &self.cache.subscr
+    }
+
+    /// Returns the cached rows of `sql`, executing it when it isn't cached
+    /// or its result changed.
+    pub fn get(&mut self, sql: &str) -> Result<Arc<[Row]>> {
+        self.cache.get(sql)
+    }
+
+    /// Returns true when `sql` is cached and its result hasn't changed as
+    /// far as notifications received so far tell.
+    pub fn is_fresh(&self, sql: &str) -> bool {
+        self.cache.is_fresh(sql)
+    }
+
+    /// Executes queries whose results changed and returns the number of
+    /// them.
+    pub fn refresh(&mut self) -> Result<usize> {
+        self.cache.refresh()
+    }
+
+    /// Removes `sql` from the cache. The query stays registered until the
+    /// subscription is unregistered.
+    pub fn invalidate(&mut self, sql: &str) {
+        self.cache.entries.remove(sql);
+    }
+}
+
+impl fmt::Debug for QueryCache<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryCache")
+            .field("params", &self.cache.backend.params)
+            .field("subscr", &self.cache.subscr)
+            .field("queries", &self.cache.entries.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use std::cell::{Cell, RefCell};
+
+    // Backend whose queries return the values of `tables`
+    #[derive(Default)]
+    struct MockBackend {
+        tables: RefCell<HashMap<String, u32>>,
+        notified: RefCell<Option<Arc<Mutex<Notified>>>>,
+        fail_subscribe: Cell<bool>,
+        fail_register: Cell<bool>,
+        num_subscribes: Cell<u32>,
+        num_executions: Cell<u32>,
+        // (subscription, query id, sql)
+        registered: RefCell<Vec<(u32, u64, String)>>,
+    }
+
+    impl MockBackend {
+        fn value(&self, sql: &str) -> u32 {
+            self.num_executions.set(self.num_executions.get() + 1);
+            self.tables.borrow().get(sql).copied().unwrap_or(0)
+        }
+    }
+
+    impl Backend for MockBackend {
+        type Subscr = u32;
+        type Rows = u32;
+
+        fn subscribe(&self, notified: &Arc<Mutex<Notified>>) -> Result<u32> {
+            if self.fail_subscribe.get() {
+                return Err(Error::InvalidOperation("subscribe failed".into()));
+            }
+            self.num_subscribes.set(self.num_subscribes.get() + 1);
+            *self.notified.borrow_mut() = Some(notified.clone());
+            Ok(self.num_subscribes.get())
+        }
+
+        fn register(&self, subscr: &u32, sql: &str) -> Result<(u64, u32)> {
+            if self.fail_register.get() {
+                return Err(Error::InvalidOperation("register failed".into()));
+            }
+            let mut registered = self.registered.borrow_mut();
+            let query_id = registered.len() as u64 + 100;
+            registered.push((*subscr, query_id, sql.into()));
+            Ok((query_id, self.value(sql)))
+        }
+
+        fn fetch(&self, sql: &str) -> Result<u32> {
+            Ok(self.value(sql))
+        }
+    }
+
+    fn cache() -> Cache<MockBackend> {
+        Cache::new(MockBackend::default()).unwrap()
+    }
+
+    // Updates a table and notifies the change of queries registered for it
+    // as a notification thread of ODPI-C does.
+    fn update(cache: &Cache<MockBackend>, sql: &str, value: u32) {
+        let backend = &cache.backend;
+        backend.tables.borrow_mut().insert(sql.into(), value);
+        let subscr = backend.num_subscribes.get();
+        let ids = backend
+            .registered
+            .borrow()
+            .iter()
+            .filter(|(s, _, q)| *s == subscr && q == sql)
+            .map(|(_, id, _)| *id)
+            .collect::<Vec<_>>();
+        let notified = backend.notified.borrow().clone().unwrap();
+        notified.lock().unwrap().changed_query_ids.extend(ids);
+    }
+
+    fn deregister(cache: &Cache<MockBackend>) {
+        let notified = cache.backend.notified.borrow().clone().unwrap();
+        notified.lock().unwrap().deregistered = true;
+    }
+
+    #[test]
+    fn invalidates_changed_queries() {
+        let mut cache = cache();
+        assert_eq!(cache.get("q1").unwrap(), 0);
+        assert_eq!(cache.get("q2").unwrap(), 0);
+        assert_eq!(cache.get("q1").unwrap(), 0);
+        assert_eq!(cache.backend.num_executions.get(), 2);
+        assert!(cache.is_fresh("q1"));
+        assert!(!cache.is_fresh("q3"));
+
+        update(&cache, "q1", 1);
+        assert!(!cache.is_fresh("q1"));
+        assert!(cache.is_fresh("q2"));
+        assert_eq!(cache.get("q1").unwrap(), 1);
+        assert_eq!(cache.get("q2").unwrap(), 0);
+        assert_eq!(cache.backend.num_executions.get(), 3);
+        assert!(cache.is_fresh("q1"));
+
+        update(&cache, "q1", 2);
+        update(&cache, "q2", 2);
+        assert_eq!(cache.refresh().unwrap(), 2);
+        assert_eq!(cache.refresh().unwrap(), 0);
+        assert_eq!(cache.get("q1").unwrap(), 2);
+        assert_eq!(cache.get("q2").unwrap(), 2);
+        assert_eq!(cache.backend.num_executions.get(), 5);
+        // Queries are registered once per subscription.
+        assert_eq!(cache.backend.registered.borrow().len(), 2);
+    }
+
+    #[test]
+    fn reregisters_after_deregistration() {
+        let mut cache = cache();
+        cache.get("q1").unwrap();
+        cache.get("q2").unwrap();
+        let old_notified = cache.notified.clone();
+        deregister(&cache);
+        assert!(!cache.is_fresh("q1"));
+
+        cache.backend.tables.borrow_mut().insert("q2".into(), 5);
+        assert_eq!(cache.get("q2").unwrap(), 5);
+        assert_eq!(cache.backend.num_subscribes.get(), 2);
+        let mut registered: Vec<_> = cache
+            .backend
+            .registered
+            .borrow()
+            .iter()
+            .filter(|(s, _, _)| *s == 2)
+            .map(|(_, _, sql)| sql.clone())
+            .collect();
+        registered.sort();
+        assert_eq!(registered, ["q1", "q2"]);
+        assert!(cache.is_fresh("q1"));
+        assert!(cache.is_fresh("q2"));
+
+        // Late notifications of the old subscription are ignored.
+        old_notified.lock().unwrap().changed_query_ids.insert(100);
+        old_notified.lock().unwrap().deregistered = true;
+        assert!(cache.is_fresh("q1"));
+        cache.get("q1").unwrap();
+        assert_eq!(cache.backend.num_subscribes.get(), 2);
+    }
+
+    #[test]
+    fn retries_failed_resubscription() {
+        let mut cache = cache();
+        cache.get("q1").unwrap();
+        deregister(&cache);
+
+        cache.backend.fail_subscribe.set(true);
+        assert!(cache.get("q1").is_err());
+        assert!(cache.refresh().is_err());
+        assert!(!cache.is_fresh("q1"));
+
+        cache.backend.fail_subscribe.set(false);
+        cache.backend.fail_register.set(true);
+        assert!(cache.get("q1").is_err());
+        assert!(!cache.is_fresh("q1"));
+        assert_eq!(cache.backend.num_subscribes.get(), 2);
+
+        cache.backend.fail_register.set(false);
+        cache.backend.tables.borrow_mut().insert("q1".into(), 7);
+        assert_eq!(cache.get("q1").unwrap(), 7);
+        assert!(cache.is_fresh("q1"));
+        assert_eq!(cache.backend.num_subscribes.get(), 3);
+    }
+
+    #[test]
+    fn tolerates_poisoned_lock() {
+        let mut cache = cache();
+        cache.get("q1").unwrap();
+        let notified = cache.notified.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = notified.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(cache.notified.is_poisoned());
+        assert!(cache.is_fresh("q1"));
+        assert_eq!(cache.get("q1").unwrap(), 0);
+        assert_eq!(cache.refresh().unwrap(), 0);
+    }
+}
@@ -0,0 +1,193 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::{BindIndex, ColumnInfo, Connection, Error, Result, Row, Statement, ToSql};
+use std::sync::Arc;
+
+// OCI_ATTR_CURRENT_POSITION in oci.h
+const DPI_OCI_ATTR_CURRENT_POSITION: u32 = 164;
+
+// prefix of DPI_ERR_SCROLL_OUT_OF_RS
+const SCROLL_OUT_OF_RS: &str = "DPI-1027:";
+
+/// Query with a scrollable cursor
+///
+/// Each scroll method moves the cursor and returns the row there, or `None`
+/// when the destination is out of the result set. Positions are 1-based.
+/// After scrolling out of the result set, the position is unknown until
+/// [`first`], [`last`] or [`absolute`] is called.
+///
+/// ```no_run
+/// # use odpic::{Connection, ScrollableStatement};
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let mut stmt = ScrollableStatement::new(conn, "select * from emp order by empno")?;
+/// stmt.execute()?;
+/// let last = stmt.last()?;
+/// let num_rows = stmt.position();
+/// let tenth = stmt.absolute(10)?;
+/// let ninth = stmt.prior()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`first`]: ScrollableStatement::first
+/// [`last`]: ScrollableStatement::last
+/// [`absolute`]: ScrollableStatement::absolute
+#[derive(Debug)]
+pub struct ScrollableStatement<'conn> {
+    stmt: Statement<'conn>,
+    columns: Option<Arc<[ColumnInfo]>>,
+    position: Option<u32>,
+}
+
+impl<'conn> ScrollableStatement<'conn> {
+    /// Prepares a query with a scrollable cursor. This fails for statements
+    /// other than queries.
+    pub fn new(conn: &'conn Connection, sql: &str) -> Result<ScrollableStatement<'conn>> {
+        let stmt = Statement::prepare(conn, sql, true)?;
+        if !stmt.info().is_query {
+            return Err(Error::InvalidOperation(
+                "scrollable cursors are available only for queries".into(),
+            ));
+        }
+        Ok(ScrollableStatement {
+            stmt,
+            columns: None,
+            position: None,
+        })
+    }
+
+    pub fn statement(&self) -> &Statement<'conn> {
+        &self.stmt
+    }
+
+    /// Binds a value. See [`Statement::bind`].
+    pub fn bind<I: BindIndex>(&mut self, index: I, value: &dyn ToSql) -> Result<()> {
+        self.stmt.bind(index, value)
+    }
+
+    /// Executes the query and places the cursor before the first row.
+    pub fn execute(&mut self) -> Result<()> {
+        let num_cols = self.stmt.exec(DPI_MODE_EXEC_DEFAULT)?;
+        self.columns = Some(self.stmt.define_columns(num_cols)?);
+        self.position = Some(0);
+        Ok(())
+    }
+
+    /// Returns column metadata. It is empty before execution.
+    pub fn column_info(&self) -> &[ColumnInfo] {
+        self.columns.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the position of the current row. It is `Some(0)` just
+    /// after execution and `None` after scrolling out of the result set.
+    pub fn position(&self) -> Option<u32> {
+        self.position
+    }
+
+    pub fn first(&mut self) -> Result<Option<Row>> {
+        self.scroll(DPI_MODE_FETCH_FIRST, 0)
+    }
+
+    pub fn last(&mut self) -> Result<Option<Row>> {
+        self.scroll(DPI_MODE_FETCH_LAST, 0)
+    }
+
+    pub fn prior(&mut self) -> Result<Option<Row>> {
+        self.scroll(DPI_MODE_FETCH_PRIOR, 0)
+    }
+
+    /// Moves to the row after the current row.
+    pub fn next_row(&mut self) -> Result<Option<Row>> {
+        self.scroll(DPI_MODE_FETCH_NEXT, 0)
+    }
+
+    /// Moves to the row at `position`.
+    pub fn absolute(&mut self, position: u32) -> Result<Option<Row>> {
+        let offset = i32::try_from(position)
+            .map_err(|_| Error::InvalidOperation(format!("position {} is too large", position)))?;
+        self.scroll(DPI_MODE_FETCH_ABSOLUTE, offset)
+    }
+
+    /// Moves by `offset` rows from the current row.
+    pub fn relative(&mut self, offset: i32) -> Result<Option<Row>> {
+        self.scroll(DPI_MODE_FETCH_RELATIVE, offset)
+    }
+
+    fn scroll(&mut self, mode: dpiFetchMode, offset: i32) -> Result<Option<Row>> {
+        let columns = self
+            .columns
+            .clone()
+            .ok_or_else(|| Error::InvalidOperation("the query has not been executed".into()))?;
+        let ctxt = self.stmt.connection().ctxt();
+        let handle = self.stmt.handle();
+        let current = self.position;
+        let position = match mode {
+            DPI_MODE_FETCH_FIRST => Some(1),
+            DPI_MODE_FETCH_LAST => None,
+            DPI_MODE_FETCH_PRIOR => current.map(|pos| pos as i64 - 1),
+            DPI_MODE_FETCH_NEXT => current.map(|pos| pos as i64 + 1),
+            DPI_MODE_FETCH_ABSOLUTE => Some(offset as i64),
+            _ => current.map(|pos| pos as i64 + offset as i64),
+        };
+        if mode != DPI_MODE_FETCH_LAST && position.is_none() {
+            return Err(Error::InvalidOperation(
+                "the cursor position is unknown after scrolling out of the result set".into(),
+            ));
+        }
+        if unsafe { dpiStmt_scroll(handle, mode, offset, 0) } != DPI_SUCCESS {
+            let err = ctxt.last_error();
+            if err.message.starts_with(SCROLL_OUT_OF_RS) {
+                self.position = None;
+                return Ok(None);
+            }
+            return Err(Error::DpiError(err));
+        }
+        let mut found = 0;
+        let mut buffer_row_index = 0;
+        chkerr!(
+            ctxt,
+            dpiStmt_fetch(handle, &mut found, &mut buffer_row_index)
+        );
+        if found == 0 {
+            // FIRST or LAST on an empty result set
+            self.position = Some(0);
+            return Ok(None);
+        }
+        self.position = Some(match position {
+            Some(pos) => pos as u32,
+            None => self.oci_current_position()?,
+        });
+        self.stmt.read_row(&columns).map(Some)
+    }
+
+    // Gets the position of the last row fetched from the database, which is
+    // the current row just after scrolling to the last row.
+    fn oci_current_position(&self) -> Result<u32> {
+        let mut value = dpiDataBuffer::default();
+        let mut len = 0;
+        chkerr!(
+            self.stmt.connection().ctxt(),
+            dpiStmt_getOciAttr(
+                self.stmt.handle(),
+                DPI_OCI_ATTR_CURRENT_POSITION,
+                &mut value,
+                &mut len
+            )
+        );
+        Ok(unsafe { value.asUint32 })
+    }
+}
@@ -0,0 +1,1789 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Simple Oracle Document Access (SODA)
+//!
+//! [`SodaDb`] creates and opens [`Collection`]s of [`Document`]s. Reads,
+//! replaces and removes are built by [`Collection::find`]. Indexes and
+//! collection metadata are built by [`IndexSpec`] and
+//! [`CollectionMetadata`].
+//!
+//! Writes don't commit unless atomic commit is enabled by
+//! [`SodaDb::set_atomic_commit`] or [`Collection::set_atomic_commit`],
+//! with which each successful write commits the current transaction,
+//! including changes made before it.
+//!
+//! ```no_run
+//! # use odpic::Connection;
+//! # fn f(conn: &Connection) -> odpic::Result<()> {
+//! let db = conn.soda_db()?;
+//! let coll = db.create_collection("orders")?;
+//! let doc = db.create_document(None, br#"{"item":"pen","qty":3}"#, None)?;
+//! let key = coll.insert_one(&doc)?.key()?;
+//! conn.commit()?;
+//!
+//! for doc in coll.find().filter(r#"{"qty":{"$gt":1}}"#).limit(10).iter()? {
+//!     println!("{}", doc?.content_as_string()?);
+//! }
+//! coll.find().key(&key).remove()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ffi::*;
+use crate::qbe::{write_string, Filter};
+use crate::{Connection, Context, Error, JsonValue, Result};
+use std::marker::PhantomData;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+fn soda_flags(atomic_commit: bool) -> u32 {
+    if atomic_commit {
+        DPI_SODA_FLAGS_ATOMIC_COMMIT
+    } else {
+        DPI_SODA_FLAGS_DEFAULT
+    }
+}
+
+unsafe fn to_bytes<'a>(ptr: *const c_char, len: u32) -> &'a [u8] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr as *const u8, len as usize)
+    }
+}
+
+fn get_string<F>(ctxt: &Context, get: F) -> Result<String>
+where
+    F: FnOnce(*mut *const c_char, *mut u32) -> i32,
+{
+    let mut ptr = ptr::null();
+    let mut len = 0;
+    chkerr!(ctxt, get(&mut ptr, &mut len));
+    Ok(String::from_utf8_lossy(unsafe { to_bytes(ptr, len) }).into_owned())
+}
+
+// Converts and frees a string list filled by ODPI-C.
+unsafe fn take_string_list(ctxt: &Context, list: &mut dpiStringList) -> Vec<String> {
+    let len = list.__bindgen_anon_1.numStrings as usize;
+    let strings = list.__bindgen_anon_2.strings;
+    let lengths = list.__bindgen_anon_3.stringLengths;
+    let vec = (0..len)
+        .map(|i| String::from_utf8_lossy(to_bytes(*strings.add(i), *lengths.add(i))).into_owned())
+        .collect();
+    dpiContext_freeStringList(ctxt.handle(), list);
+    vec
+}
+
+/// SODA database
+///
+/// This holds a reference to [`dpiSodaDb`], which is released when it is
+/// dropped.
+#[derive(Debug)]
+pub struct SodaDb<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiSodaDb,
+    atomic_commit: bool,
+}
+
+impl<'conn> SodaDb<'conn> {
+    pub fn new(conn: &'conn Connection) -> Result<SodaDb<'conn>> {
+        let mut handle = ptr::null_mut();
+        chkerr!(conn.ctxt(), dpiConn_getSodaDb(conn.handle(), &mut handle));
+        Ok(SodaDb {
+            conn,
+            handle,
+            atomic_commit: false,
+        })
+    }
+
+    pub fn handle(&self) -> *mut dpiSodaDb {
+        self.handle
+    }
+
+    /// Enables `DPI_SODA_FLAGS_ATOMIC_COMMIT` for collection creation and
+    /// for collections opened afterwards.
+    pub fn set_atomic_commit(&mut self, enable: bool) {
+        self.atomic_commit = enable;
+    }
+
+    pub fn atomic_commit(&self) -> bool {
+        self.atomic_commit
+    }
+
+    /// Creates a collection with the default metadata or opens it when it
+    /// exists.
+    pub fn create_collection(&self, name: &str) -> Result<Collection<'conn>> {
+        self.create_collection_flags(name, "", DPI_SODA_FLAGS_DEFAULT)
+    }
+
+    /// Creates a collection with metadata in JSON or opens it when it
+    /// exists with the same metadata.
+    pub fn create_collection_with_metadata(
+        &self,
+        name: &str,
+        metadata: &str,
+    ) -> Result<Collection<'conn>> {
+        self.create_collection_flags(name, metadata, DPI_SODA_FLAGS_DEFAULT)
+    }
+
+    /// Creates a collection with typed metadata or opens it when it exists
+    /// with the same metadata.
+    pub fn create_collection_with(
+        &self,
+        name: &str,
+        metadata: &CollectionMetadata,
+    ) -> Result<Collection<'conn>> {
+        self.create_collection_flags(name, &metadata.to_json(), DPI_SODA_FLAGS_DEFAULT)
+    }
+
+    /// Creates a collection over an existing table in MAP mode, using
+    /// `DPI_SODA_FLAGS_CREATE_COLL_MAP`. The metadata must describe the
+    /// columns of the table.
+    pub fn map_collection(
+        &self,
+        name: &str,
+        metadata: &CollectionMetadata,
+    ) -> Result<Collection<'conn>> {
+        self.create_collection_flags(name, &metadata.to_json(), DPI_SODA_FLAGS_CREATE_COLL_MAP)
+    }
+
+    fn create_collection_flags(
+        &self,
+        name: &str,
+        metadata: &str,
+        flags: u32,
+    ) -> Result<Collection<'conn>> {
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiSodaDb_createCollection(
+                self.handle,
+                name.as_ptr() as *const c_char,
+                name.len() as u32,
+                if metadata.is_empty() {
+                    ptr::null()
+                } else {
+                    metadata.as_ptr() as *const c_char
+                },
+                metadata.len() as u32,
+                flags | soda_flags(self.atomic_commit),
+                &mut handle
+            )
+        );
+        Ok(self.collection(handle))
+    }
+
+    /// Opens a collection. It returns `None` when it doesn't exist.
+    pub fn open_collection(&self, name: &str) -> Result<Option<Collection<'conn>>> {
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiSodaDb_openCollection(
+                self.handle,
+                name.as_ptr() as *const c_char,
+                name.len() as u32,
+                soda_flags(self.atomic_commit),
+                &mut handle
+            )
+        );
+        Ok((!handle.is_null()).then(|| self.collection(handle)))
+    }
+
+    // Takes over a reference returned by ODPI-C.
+    fn collection(&self, handle: *mut dpiSodaColl) -> Collection<'conn> {
+        Collection {
+            conn: self.conn,
+            handle,
+            atomic_commit: self.atomic_commit,
+        }
+    }
+
+    /// Returns up to `limit` names of collections from `start_name` in
+    /// order. `limit` 0 means no limit.
+    pub fn collection_names(&self, start_name: &str, limit: u32) -> Result<Vec<String>> {
+        let ctxt = self.conn.ctxt();
+        let mut list = dpiStringList::default();
+        chkerr!(
+            ctxt,
+            dpiSodaDb_getCollectionNames(
+                self.handle,
+                start_name.as_ptr() as *const c_char,
+                start_name.len() as u32,
+                limit,
+                DPI_SODA_FLAGS_DEFAULT,
+                &mut list
+            )
+        );
+        Ok(unsafe { take_string_list(ctxt, &mut list) })
+    }
+
+    /// Returns an iterator over collections from `start_name` in order.
+    pub fn collections(&self, start_name: &str) -> Result<Collections<'conn>> {
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiSodaDb_getCollections(
+                self.handle,
+                start_name.as_ptr() as *const c_char,
+                start_name.len() as u32,
+                DPI_SODA_FLAGS_DEFAULT,
+                &mut handle
+            )
+        );
+        Ok(Collections {
+            conn: self.conn,
+            handle,
+            atomic_commit: self.atomic_commit,
+            done: false,
+        })
+    }
+
+    /// Creates a document to be written to collections. `key` is needed
+    /// only for collections with client-assigned keys. The media type is
+    /// `application/json` when it is `None`.
+    pub fn create_document(
+        &self,
+        key: Option<&str>,
+        content: &[u8],
+        media_type: Option<&str>,
+    ) -> Result<Document> {
+        let ctxt = self.conn.ctxt();
+        let key = key.unwrap_or("");
+        let media_type = media_type.unwrap_or("");
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiSodaDb_createDocument(
+                self.handle,
+                key.as_ptr() as *const c_char,
+                key.len() as u32,
+                content.as_ptr() as *const c_char,
+                content.len() as u32,
+                media_type.as_ptr() as *const c_char,
+                media_type.len() as u32,
+                DPI_SODA_FLAGS_DEFAULT,
+                &mut handle
+            )
+        );
+        Ok(Document { ctxt, handle })
+    }
+}
+
+impl Drop for SodaDb<'_> {
+    fn drop(&mut self) {
+        unsafe { dpiSodaDb_release(self.handle) };
+    }
+}
+
+impl Connection {
+    /// Gets the SODA database of the connection.
+    pub fn soda_db(&self) -> Result<SodaDb<'_>> {
+        SodaDb::new(self)
+    }
+}
+
+/// Iterator over collections returned by [`SodaDb::collections`]
+#[derive(Debug)]
+pub struct Collections<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiSodaCollCursor,
+    atomic_commit: bool,
+    // true after the end or an error
+    done: bool,
+}
+
+impl<'conn> Iterator for Collections<'conn> {
+    type Item = Result<Collection<'conn>>;
+
+    fn next(&mut self) -> Option<Result<Collection<'conn>>> {
+        if self.done {
+            return None;
+        }
+        let mut handle = ptr::null_mut();
+        if unsafe { dpiSodaCollCursor_getNext(self.handle, DPI_SODA_FLAGS_DEFAULT, &mut handle) }
+            != DPI_SUCCESS
+        {
+            self.done = true;
+            return Some(Err(Error::DpiError(self.conn.ctxt().last_error())));
+        }
+        self.done = handle.is_null();
+        (!handle.is_null()).then(|| {
+            Ok(Collection {
+                conn: self.conn,
+                handle,
+                atomic_commit: self.atomic_commit,
+            })
+        })
+    }
+}
+
+impl Drop for Collections<'_> {
+    fn drop(&mut self) {
+        unsafe { dpiSodaCollCursor_release(self.handle) };
+    }
+}
+
+/// SODA collection
+///
+/// This holds a reference to [`dpiSodaColl`], which is released when it is
+/// dropped.
+#[derive(Debug)]
+pub struct Collection<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiSodaColl,
+    atomic_commit: bool,
+}
+
+impl<'conn> Collection<'conn> {
+    pub fn handle(&self) -> *mut dpiSodaColl {
+        self.handle
+    }
+
+    /// Enables `DPI_SODA_FLAGS_ATOMIC_COMMIT` for writes to the collection.
+    pub fn set_atomic_commit(&mut self, enable: bool) {
+        self.atomic_commit = enable;
+    }
+
+    pub fn atomic_commit(&self) -> bool {
+        self.atomic_commit
+    }
+
+    fn flags(&self) -> u32 {
+        soda_flags(self.atomic_commit)
+    }
+
+    pub fn name(&self) -> Result<String> {
+        get_string(self.conn.ctxt(), |p, l| unsafe {
+            dpiSodaColl_getName(self.handle, p, l)
+        })
+    }
+
+    /// Returns the metadata in JSON.
+    pub fn metadata(&self) -> Result<String> {
+        get_string(self.conn.ctxt(), |p, l| unsafe {
+            dpiSodaColl_getMetadata(self.handle, p, l)
+        })
+    }
+
+    /// Starts building an operation on documents which match all criteria
+    /// set to it.
+    pub fn find(&self) -> SodaOperation<'_, 'conn> {
+        SodaOperation {
+            coll: self,
+            keys: Vec::new(),
+            key: None,
+            version: None,
+            filter: None,
+            skip: 0,
+            limit: 0,
+            fetch_array_size: 0,
+            hint: None,
+            lock: false,
+        }
+    }
+
+    /// Inserts a document and returns the inserted document without
+    /// content, which has the key, version and timestamps.
+    pub fn insert_one(&self, doc: &Document) -> Result<Document> {
+        let ctxt = self.conn.ctxt();
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiSodaColl_insertOne(self.handle, doc.handle, self.flags(), &mut handle)
+        );
+        Ok(Document { ctxt, handle })
+    }
+
+    /// Inserts documents in a round-trip and returns the inserted documents
+    /// without content.
+    pub fn insert_many(&self, docs: &[Document]) -> Result<Vec<Document>> {
+        let ctxt = self.conn.ctxt();
+        let mut handles: Vec<_> = docs.iter().map(|doc| doc.handle).collect();
+        let mut inserted = vec![ptr::null_mut(); docs.len()];
+        chkerr!(
+            ctxt,
+            dpiSodaColl_insertMany(
+                self.handle,
+                handles.len() as u32,
+                handles.as_mut_ptr(),
+                self.flags(),
+                inserted.as_mut_ptr()
+            )
+        );
+        Ok(inserted
+            .into_iter()
+            .map(|handle| Document { ctxt, handle })
+            .collect())
+    }
+
+    /// Inserts a document or replaces the document with the same key and
+    /// returns the saved document without content.
+    pub fn save(&self, doc: &Document) -> Result<Document> {
+        let ctxt = self.conn.ctxt();
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiSodaColl_save(self.handle, doc.handle, self.flags(), &mut handle)
+        );
+        Ok(Document { ctxt, handle })
+    }
+
+    /// Removes all documents. This is DDL, which commits the transaction.
+    pub fn truncate(&self) -> Result<()> {
+        chkerr!(self.conn.ctxt(), dpiSodaColl_truncate(self.handle));
+        Ok(())
+    }
+
+    /// Drops the collection. It returns false when it didn't exist.
+    pub fn drop_collection(&self) -> Result<bool> {
+        let mut is_dropped = 0;
+        chkerr!(
+            self.conn.ctxt(),
+            dpiSodaColl_drop(self.handle, self.flags(), &mut is_dropped)
+        );
+        Ok(is_dropped != 0)
+    }
+
+    /// Creates an index.
+    pub fn create_index<S: Into<IndexSpec>>(&self, spec: S) -> Result<()> {
+        let spec = spec.into().to_json();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiSodaColl_createIndex(
+                self.handle,
+                spec.as_ptr() as *const c_char,
+                spec.len() as u32,
+                self.flags()
+            )
+        );
+        Ok(())
+    }
+
+    /// Drops an index. `force` drops JSON search indexes and spatial
+    /// indexes being used, using `DPI_SODA_FLAGS_INDEX_DROP_FORCE`. It
+    /// returns false when the index didn't exist.
+    pub fn drop_index(&self, name: &str, force: bool) -> Result<bool> {
+        let mut flags = self.flags();
+        if force {
+            flags |= DPI_SODA_FLAGS_INDEX_DROP_FORCE;
+        }
+        let mut is_dropped = 0;
+        chkerr!(
+            self.conn.ctxt(),
+            dpiSodaColl_dropIndex(
+                self.handle,
+                name.as_ptr() as *const c_char,
+                name.len() as u32,
+                flags,
+                &mut is_dropped
+            )
+        );
+        Ok(is_dropped != 0)
+    }
+
+    /// Returns specifications of indexes in JSON.
+    pub fn list_indexes(&self) -> Result<Vec<String>> {
+        let ctxt = self.conn.ctxt();
+        let mut list = dpiStringList::default();
+        chkerr!(
+            ctxt,
+            dpiSodaColl_listIndexes(self.handle, DPI_SODA_FLAGS_DEFAULT, &mut list)
+        );
+        Ok(unsafe { take_string_list(ctxt, &mut list) })
+    }
+
+    /// Returns the data guide maintained by a [`SearchIndex`]. It returns
+    /// `None` when no index maintains it.
+    pub fn data_guide(&self) -> Result<Option<DataGuide>> {
+        let ctxt = self.conn.ctxt();
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiSodaColl_getDataGuide(self.handle, DPI_SODA_FLAGS_DEFAULT, &mut handle)
+        );
+        if handle.is_null() {
+            return Ok(None);
+        }
+        let doc = Document { ctxt, handle };
+        let value = if doc.is_json()? {
+            doc.json_content()?
+        } else {
+            self.parse_json(&doc.content()?)?
+        };
+        DataGuide::from_json(&value).map(Some)
+    }
+
+    // Parses JSON text by ODPI-C.
+    fn parse_json(&self, text: &[u8]) -> Result<JsonValue> {
+        let ctxt = self.conn.ctxt();
+        let mut json = ptr::null_mut();
+        chkerr!(ctxt, dpiConn_newJson(self.conn.handle(), &mut json));
+        let res = (|| {
+            chkerr!(
+                ctxt,
+                dpiJson_setFromText(json, text.as_ptr() as *const c_char, text.len() as u64, 0)
+            );
+            let mut node = ptr::null_mut();
+            chkerr!(
+                ctxt,
+                dpiJson_getValue(json, DPI_JSON_OPT_NUMBER_AS_STRING, &mut node)
+            );
+            unsafe { JsonValue::from_dpi_node(&*node) }
+        })();
+        unsafe { dpiJson_release(json) };
+        res
+    }
+}
+
+impl Drop for Collection<'_> {
+    fn drop(&mut self) {
+        unsafe { dpiSodaColl_release(self.handle) };
+    }
+}
+
+/// Builder of operations on documents, created by [`Collection::find`]
+///
+/// It fills [`dpiSodaOperOptions`]. Criteria which are not set match all
+/// documents.
+#[derive(Clone, Debug)]
+pub struct SodaOperation<'c, 'conn> {
+    coll: &'c Collection<'conn>,
+    keys: Vec<String>,
+    key: Option<String>,
+    version: Option<String>,
+    filter: Option<String>,
+    skip: u32,
+    limit: u32,
+    fetch_array_size: u32,
+    hint: Option<String>,
+    lock: bool,
+}
+
+impl<'c, 'conn> SodaOperation<'c, 'conn> {
+    /// Matches documents with one of `keys`.
+    pub fn keys<I, S>(mut self, keys: I) -> SodaOperation<'c, 'conn>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Matches the document with `key`.
+    pub fn key(mut self, key: &str) -> SodaOperation<'c, 'conn> {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Matches the document with `version`, which is used with
+    /// [`SodaOperation::key`] for optimistic locking.
+    pub fn version(mut self, version: &str) -> SodaOperation<'c, 'conn> {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Matches documents by a query-by-example (QBE) filter in JSON.
+    pub fn filter(mut self, filter: &str) -> SodaOperation<'c, 'conn> {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// Matches documents by a typed QBE filter. It fails when
+    /// [`Filter::to_json`] fails.
+    pub fn qbe(self, filter: &Filter) -> Result<SodaOperation<'c, 'conn>> {
+        Ok(self.filter(&filter.to_json()?))
+    }
+
+    /// Skips the first `count` documents of reads.
+    pub fn skip(mut self, count: u32) -> SodaOperation<'c, 'conn> {
+        self.skip = count;
+        self
+    }
+
+    /// Reads up to `count` documents.
+    pub fn limit(mut self, count: u32) -> SodaOperation<'c, 'conn> {
+        self.limit = count;
+        self
+    }
+
+    /// Sets the number of documents fetched in a round-trip by
+    /// [`SodaOperation::iter`].
+    pub fn fetch_array_size(mut self, size: u32) -> SodaOperation<'c, 'conn> {
+        self.fetch_array_size = size;
+        self
+    }
+
+    /// Sets an optimizer hint, such as `MONITOR`, without `/*+ */`.
+    pub fn hint(mut self, hint: &str) -> SodaOperation<'c, 'conn> {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Locks documents read like `SELECT ... FOR UPDATE`.
+    pub fn lock(mut self, enable: bool) -> SodaOperation<'c, 'conn> {
+        self.lock = enable;
+        self
+    }
+
+    // Calls `f` with options pointing to strings in `self`.
+    fn with_options<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut dpiSodaOperOptions) -> Result<T>,
+    {
+        let ctxt = self.coll.conn.ctxt();
+        let mut options = dpiSodaOperOptions::default();
+        chkerr!(
+            ctxt,
+            dpiContext_initSodaOperOptions(ctxt.handle(), &mut options)
+        );
+        let mut key_ptrs: Vec<_> = self
+            .keys
+            .iter()
+            .map(|key| key.as_ptr() as *const c_char)
+            .collect();
+        let mut key_lengths: Vec<_> = self.keys.iter().map(|key| key.len() as u32).collect();
+        if !self.keys.is_empty() {
+            options.numKeys = self.keys.len() as u32;
+            options.keys = key_ptrs.as_mut_ptr();
+            options.keyLengths = key_lengths.as_mut_ptr();
+        }
+        if let Some(key) = &self.key {
+            options.key = key.as_ptr() as *const c_char;
+            options.keyLength = key.len() as u32;
+        }
+        if let Some(version) = &self.version {
+            options.version = version.as_ptr() as *const c_char;
+            options.versionLength = version.len() as u32;
+        }
+        if let Some(filter) = &self.filter {
+            options.filter = filter.as_ptr() as *const c_char;
+            options.filterLength = filter.len() as u32;
+        }
+        if let Some(hint) = &self.hint {
+            options.hint = hint.as_ptr() as *const c_char;
+            options.hintLength = hint.len() as u32;
+        }
+        options.skip = self.skip;
+        options.limit = self.limit;
+        options.fetchArraySize = self.fetch_array_size;
+        options.lock = self.lock.into();
+        f(&mut options)
+    }
+
+    /// Returns an iterator over matching documents.
+    pub fn iter(&self) -> Result<DocCursor<'c>> {
+        let ctxt = self.coll.conn.ctxt();
+        let handle = self.with_options(|options| {
+            let mut handle = ptr::null_mut();
+            chkerr!(
+                ctxt,
+                dpiSodaColl_find(self.coll.handle, options, self.coll.flags(), &mut handle)
+            );
+            Ok(handle)
+        })?;
+        Ok(DocCursor {
+            ctxt,
+            handle,
+            done: false,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the first matching document.
+    pub fn get_one(&self) -> Result<Option<Document>> {
+        let ctxt = self.coll.conn.ctxt();
+        self.with_options(|options| {
+            let mut handle = ptr::null_mut();
+            chkerr!(
+                ctxt,
+                dpiSodaColl_findOne(self.coll.handle, options, self.coll.flags(), &mut handle)
+            );
+            Ok((!handle.is_null()).then_some(Document { ctxt, handle }))
+        })
+    }
+
+    /// Returns the number of matching documents. Skip and limit are not
+    /// allowed.
+    pub fn count(&self) -> Result<u64> {
+        let ctxt = self.coll.conn.ctxt();
+        self.with_options(|options| {
+            let mut count = 0;
+            chkerr!(
+                ctxt,
+                dpiSodaColl_getDocCount(self.coll.handle, options, self.coll.flags(), &mut count)
+            );
+            Ok(count)
+        })
+    }
+
+    /// Removes matching documents and returns the number of them.
+    pub fn remove(&self) -> Result<u64> {
+        let ctxt = self.coll.conn.ctxt();
+        self.with_options(|options| {
+            let mut count = 0;
+            chkerr!(
+                ctxt,
+                dpiSodaColl_remove(self.coll.handle, options, self.coll.flags(), &mut count)
+            );
+            Ok(count)
+        })
+    }
+
+    /// Replaces the document matching the key with `doc`. It returns false
+    /// when no document matched.
+    pub fn replace_one(&self, doc: &Document) -> Result<bool> {
+        let ctxt = self.coll.conn.ctxt();
+        self.with_options(|options| {
+            let mut replaced = 0;
+            chkerr!(
+                ctxt,
+                dpiSodaColl_replaceOne(
+                    self.coll.handle,
+                    options,
+                    doc.handle,
+                    self.coll.flags(),
+                    &mut replaced,
+                    ptr::null_mut()
+                )
+            );
+            Ok(replaced != 0)
+        })
+    }
+
+    /// Replaces the document matching the key with `doc` and returns the
+    /// replaced document without content, which has the new version.
+    pub fn replace_one_and_get(&self, doc: &Document) -> Result<Option<Document>> {
+        let ctxt = self.coll.conn.ctxt();
+        self.with_options(|options| {
+            let mut replaced = 0;
+            let mut handle = ptr::null_mut();
+            chkerr!(
+                ctxt,
+                dpiSodaColl_replaceOne(
+                    self.coll.handle,
+                    options,
+                    doc.handle,
+                    self.coll.flags(),
+                    &mut replaced,
+                    &mut handle
+                )
+            );
+            Ok((!handle.is_null()).then_some(Document { ctxt, handle }))
+        })
+    }
+}
+
+/// Iterator over documents returned by [`SodaOperation::iter`]
+#[derive(Debug)]
+pub struct DocCursor<'c> {
+    ctxt: &'static Context,
+    handle: *mut dpiSodaDocCursor,
+    // true after the end or an error
+    done: bool,
+    phantom: PhantomData<&'c ()>,
+}
+
+impl Iterator for DocCursor<'_> {
+    type Item = Result<Document>;
+
+    fn next(&mut self) -> Option<Result<Document>> {
+        if self.done {
+            return None;
+        }
+        let mut handle = ptr::null_mut();
+        if unsafe { dpiSodaDocCursor_getNext(self.handle, DPI_SODA_FLAGS_DEFAULT, &mut handle) }
+            != DPI_SUCCESS
+        {
+            self.done = true;
+            return Some(Err(Error::DpiError(self.ctxt.last_error())));
+        }
+        self.done = handle.is_null();
+        let ctxt = self.ctxt;
+        (!handle.is_null()).then_some(Ok(Document { ctxt, handle }))
+    }
+}
+
+impl Drop for DocCursor<'_> {
+    fn drop(&mut self) {
+        unsafe { dpiSodaDocCursor_release(self.handle) };
+    }
+}
+
+/// SODA document
+///
+/// This holds a reference to [`dpiSodaDoc`], which is released when it is
+/// dropped.
+#[derive(Debug)]
+pub struct Document {
+    ctxt: &'static Context,
+    handle: *mut dpiSodaDoc,
+}
+
+impl Document {
+    pub fn handle(&self) -> *mut dpiSodaDoc {
+        self.handle
+    }
+
+    pub fn key(&self) -> Result<String> {
+        get_string(self.ctxt, |p, l| unsafe {
+            dpiSodaDoc_getKey(self.handle, p, l)
+        })
+    }
+
+    pub fn version(&self) -> Result<String> {
+        get_string(self.ctxt, |p, l| unsafe {
+            dpiSodaDoc_getVersion(self.handle, p, l)
+        })
+    }
+
+    /// Returns the creation time in ISO 8601.
+    pub fn created_on(&self) -> Result<String> {
+        get_string(self.ctxt, |p, l| unsafe {
+            dpiSodaDoc_getCreatedOn(self.handle, p, l)
+        })
+    }
+
+    /// Returns the last modified time in ISO 8601.
+    pub fn last_modified(&self) -> Result<String> {
+        get_string(self.ctxt, |p, l| unsafe {
+            dpiSodaDoc_getLastModified(self.handle, p, l)
+        })
+    }
+
+    pub fn media_type(&self) -> Result<String> {
+        get_string(self.ctxt, |p, l| unsafe {
+            dpiSodaDoc_getMediaType(self.handle, p, l)
+        })
+    }
+
+    /// Returns true when the content is of Oracle's native JSON type,
+    /// which is read by [`Document::json_content`].
+    pub fn is_json(&self) -> Result<bool> {
+        let mut is_json = 0;
+        chkerr!(self.ctxt, dpiSodaDoc_getIsJson(self.handle, &mut is_json));
+        Ok(is_json != 0)
+    }
+
+    /// Returns the content of a document whose content isn't of native JSON
+    /// type.
+    pub fn content(&self) -> Result<Vec<u8>> {
+        let mut ptr = ptr::null();
+        let mut len = 0;
+        let mut encoding = ptr::null();
+        chkerr!(
+            self.ctxt,
+            dpiSodaDoc_getContent(self.handle, &mut ptr, &mut len, &mut encoding)
+        );
+        Ok(unsafe { to_bytes(ptr, len) }.to_vec())
+    }
+
+    /// Returns the content of a document whose content isn't of native JSON
+    /// type as a string.
+    pub fn content_as_string(&self) -> Result<String> {
+        String::from_utf8(self.content()?)
+            .map_err(|err| Error::InvalidConversion(format!("content isn't UTF-8: {}", err)))
+    }
+
+    /// Returns the content of a document whose content is of native JSON
+    /// type.
+    pub fn json_content(&self) -> Result<JsonValue> {
+        let mut json = ptr::null_mut();
+        // The reference is owned by the document.
+        chkerr!(self.ctxt, dpiSodaDoc_getJsonContent(self.handle, &mut json));
+        let mut node = ptr::null_mut();
+        chkerr!(
+            self.ctxt,
+            dpiJson_getValue(json, DPI_JSON_OPT_NUMBER_AS_STRING, &mut node)
+        );
+        unsafe { JsonValue::from_dpi_node(&*node) }
+    }
+}
+
+impl Clone for Document {
+    fn clone(&self) -> Document {
+        unsafe { dpiSodaDoc_addRef(self.handle) };
+        Document {
+            ctxt: self.ctxt,
+            handle: self.handle,
+        }
+    }
+}
+
+impl Drop for Document {
+    fn drop(&mut self) {
+        unsafe { dpiSodaDoc_release(self.handle) };
+    }
+}
+
+// Writer of a JSON object, which skips members whose values are `None`
+struct ObjectWriter {
+    out: String,
+}
+
+impl ObjectWriter {
+    fn new() -> ObjectWriter {
+        ObjectWriter { out: "{".into() }
+    }
+
+    fn key(&mut self, key: &str) -> &mut String {
+        if self.out.len() > 1 {
+            self.out.push(',');
+        }
+        write_string(&mut self.out, key);
+        self.out.push(':');
+        &mut self.out
+    }
+
+    fn str(&mut self, key: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            write_string(self.key(key), value);
+        }
+    }
+
+    fn raw(&mut self, key: &str, json: Option<&str>) {
+        if let Some(json) = json {
+            self.key(key).push_str(json);
+        }
+    }
+
+    fn bool(&mut self, key: &str, value: Option<bool>) {
+        self.raw(key, value.map(|v| if v { "true" } else { "false" }));
+    }
+
+    fn num(&mut self, key: &str, value: Option<u32>) {
+        self.raw(key, value.map(|v| v.to_string()).as_deref());
+    }
+
+    fn array<I: IntoIterator<Item = String>>(&mut self, key: &str, elements: I) {
+        let out = self.key(key);
+        out.push('[');
+        for (i, element) in elements.into_iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&element);
+        }
+        out.push(']');
+    }
+
+    fn finish(mut self) -> String {
+        self.out.push('}');
+        self.out
+    }
+}
+
+/// Data type of an indexed field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexDataType {
+    /// `string` with optional maximum length
+    String(Option<u32>),
+    Number,
+    Date,
+    Timestamp,
+}
+
+/// Field of [`BTreeIndex`], [`FunctionalIndex`] and [`MultivalueIndex`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexField {
+    path: String,
+    datatype: Option<IndexDataType>,
+    descending: bool,
+}
+
+impl IndexField {
+    pub fn new(path: &str) -> IndexField {
+        IndexField {
+            path: path.into(),
+            datatype: None,
+            descending: false,
+        }
+    }
+
+    pub fn datatype(mut self, datatype: IndexDataType) -> IndexField {
+        self.datatype = Some(datatype);
+        self
+    }
+
+    /// Sorts values in descending order. This is ignored by
+    /// [`MultivalueIndex`].
+    pub fn desc(mut self) -> IndexField {
+        self.descending = true;
+        self
+    }
+
+    fn to_json(&self, with_order: bool) -> String {
+        let mut w = ObjectWriter::new();
+        w.str("path", Some(&self.path));
+        match self.datatype {
+            Some(IndexDataType::String(max_length)) => {
+                w.str("datatype", Some("string"));
+                w.num("maxLength", max_length);
+            }
+            Some(IndexDataType::Number) => w.str("datatype", Some("number")),
+            Some(IndexDataType::Date) => w.str("datatype", Some("date")),
+            Some(IndexDataType::Timestamp) => w.str("datatype", Some("timestamp")),
+            None => {}
+        }
+        if with_order {
+            w.str("order", Some(if self.descending { "desc" } else { "asc" }));
+        }
+        w.finish()
+    }
+}
+
+/// B-tree index on fields of scalar values
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BTreeIndex {
+    name: String,
+    fields: Vec<IndexField>,
+    unique: Option<bool>,
+    scalar_required: Option<bool>,
+    lax: Option<bool>,
+    index_nulls: Option<bool>,
+}
+
+impl BTreeIndex {
+    pub fn new(name: &str) -> BTreeIndex {
+        BTreeIndex {
+            name: name.into(),
+            fields: Vec::new(),
+            unique: None,
+            scalar_required: None,
+            lax: None,
+            index_nulls: None,
+        }
+    }
+
+    /// Appends a field. Indexes on more than one field are composite.
+    pub fn field(mut self, field: IndexField) -> BTreeIndex {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn unique(mut self, enable: bool) -> BTreeIndex {
+        self.unique = Some(enable);
+        self
+    }
+
+    /// Raises errors when documents lack scalar values at the fields.
+    pub fn scalar_required(mut self, enable: bool) -> BTreeIndex {
+        self.scalar_required = Some(enable);
+        self
+    }
+
+    /// Ignores values which can't be converted to the data types.
+    pub fn lax(mut self, enable: bool) -> BTreeIndex {
+        self.lax = Some(enable);
+        self
+    }
+
+    /// Indexes documents which lack the fields as nulls.
+    pub fn index_nulls(mut self, enable: bool) -> BTreeIndex {
+        self.index_nulls = Some(enable);
+        self
+    }
+}
+
+/// Function-based index on the scalar value of a field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionalIndex {
+    name: String,
+    field: IndexField,
+}
+
+impl FunctionalIndex {
+    pub fn new(name: &str, field: IndexField) -> FunctionalIndex {
+        FunctionalIndex {
+            name: name.into(),
+            field,
+        }
+    }
+}
+
+/// Index on all values of a field, including those in arrays, which is
+/// a multivalue index of Oracle 21c and later
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultivalueIndex {
+    name: String,
+    field: IndexField,
+}
+
+impl MultivalueIndex {
+    pub fn new(name: &str, field: IndexField) -> MultivalueIndex {
+        MultivalueIndex {
+            name: name.into(),
+            field,
+        }
+    }
+}
+
+/// What [`SearchIndex`] indexes for full-text search
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchOn {
+    None,
+    Text,
+    TextValue,
+}
+
+/// JSON search index, which also maintains the data guide
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchIndex {
+    name: String,
+    dataguide: Option<bool>,
+    search_on: Option<SearchOn>,
+    language: Option<String>,
+}
+
+impl SearchIndex {
+    pub fn new(name: &str) -> SearchIndex {
+        SearchIndex {
+            name: name.into(),
+            dataguide: None,
+            search_on: None,
+            language: None,
+        }
+    }
+
+    /// Maintains the data guide read by [`Collection::data_guide`].
+    pub fn dataguide(mut self, enable: bool) -> SearchIndex {
+        self.dataguide = Some(enable);
+        self
+    }
+
+    pub fn search_on(mut self, search_on: SearchOn) -> SearchIndex {
+        self.search_on = Some(search_on);
+        self
+    }
+
+    /// Sets the language of full-text search, such as `english`.
+    pub fn language(mut self, language: &str) -> SearchIndex {
+        self.language = Some(language.into());
+        self
+    }
+}
+
+/// Spatial index on GeoJSON geometries at a field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpatialIndex {
+    name: String,
+    path: String,
+    scalar_required: Option<bool>,
+    lax: Option<bool>,
+}
+
+impl SpatialIndex {
+    pub fn new(name: &str, path: &str) -> SpatialIndex {
+        SpatialIndex {
+            name: name.into(),
+            path: path.into(),
+            scalar_required: None,
+            lax: None,
+        }
+    }
+
+    /// Raises errors when documents lack geometries at the field.
+    pub fn scalar_required(mut self, enable: bool) -> SpatialIndex {
+        self.scalar_required = Some(enable);
+        self
+    }
+
+    /// Ignores values which aren't geometries.
+    pub fn lax(mut self, enable: bool) -> SpatialIndex {
+        self.lax = Some(enable);
+        self
+    }
+}
+
+/// Index specification passed to [`Collection::create_index`]
+///
+/// ```
+/// use odpic::soda::{BTreeIndex, IndexDataType, IndexField, IndexSpec};
+///
+/// let spec: IndexSpec = BTreeIndex::new("ORDERS_QTY")
+///     .field(IndexField::new("qty").datatype(IndexDataType::Number).desc())
+///     .unique(true)
+///     .into();
+/// assert_eq!(
+///     spec.to_json(),
+///     r#"{"name":"ORDERS_QTY","fields":[{"path":"qty","datatype":"number","order":"desc"}],"unique":true}"#
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexSpec {
+    BTree(BTreeIndex),
+    Functional(FunctionalIndex),
+    Multivalue(MultivalueIndex),
+    Search(SearchIndex),
+    Spatial(SpatialIndex),
+}
+
+impl IndexSpec {
+    /// Returns the index name.
+    pub fn name(&self) -> &str {
+        match self {
+            IndexSpec::BTree(index) => &index.name,
+            IndexSpec::Functional(index) => &index.name,
+            IndexSpec::Multivalue(index) => &index.name,
+            IndexSpec::Search(index) => &index.name,
+            IndexSpec::Spatial(index) => &index.name,
+        }
+    }
+
+    /// Returns the specification in JSON.
+    pub fn to_json(&self) -> String {
+        let mut w = ObjectWriter::new();
+        w.str("name", Some(self.name()));
+        match self {
+            IndexSpec::BTree(index) => {
+                w.array("fields", index.fields.iter().map(|f| f.to_json(true)));
+                w.bool("unique", index.unique);
+                w.bool("scalarRequired", index.scalar_required);
+                w.bool("lax", index.lax);
+                w.bool("indexNulls", index.index_nulls);
+            }
+            IndexSpec::Functional(index) => {
+                w.array("fields", [index.field.to_json(true)]);
+            }
+            IndexSpec::Multivalue(index) => {
+                w.bool("multivalue", Some(true));
+                w.array("fields", [index.field.to_json(false)]);
+            }
+            IndexSpec::Search(index) => {
+                w.str(
+                    "dataguide",
+                    index.dataguide.map(|v| if v { "on" } else { "off" }),
+                );
+                w.str(
+                    "search_on",
+                    index.search_on.map(|v| match v {
+                        SearchOn::None => "none",
+                        SearchOn::Text => "text",
+                        SearchOn::TextValue => "text_value",
+                    }),
+                );
+                w.str("language", index.language.as_deref());
+            }
+            IndexSpec::Spatial(index) => {
+                w.str("spatial", Some(&index.path));
+                w.bool("scalarRequired", index.scalar_required);
+                w.bool("lax", index.lax);
+            }
+        }
+        w.finish()
+    }
+}
+
+impl From<BTreeIndex> for IndexSpec {
+    fn from(index: BTreeIndex) -> IndexSpec {
+        IndexSpec::BTree(index)
+    }
+}
+
+impl From<FunctionalIndex> for IndexSpec {
+    fn from(index: FunctionalIndex) -> IndexSpec {
+        IndexSpec::Functional(index)
+    }
+}
+
+impl From<MultivalueIndex> for IndexSpec {
+    fn from(index: MultivalueIndex) -> IndexSpec {
+        IndexSpec::Multivalue(index)
+    }
+}
+
+impl From<SearchIndex> for IndexSpec {
+    fn from(index: SearchIndex) -> IndexSpec {
+        IndexSpec::Search(index)
+    }
+}
+
+impl From<SpatialIndex> for IndexSpec {
+    fn from(index: SpatialIndex) -> IndexSpec {
+        IndexSpec::Spatial(index)
+    }
+}
+
+/// How keys of documents are assigned
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyAssignment {
+    Uuid,
+    Guid,
+    /// values of the sequence of the name
+    Sequence(String),
+    /// keys passed to [`SodaDb::create_document`]
+    Client,
+    EmbeddedOid,
+}
+
+/// SQL type of key columns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeySqlType {
+    Varchar2,
+    Number,
+    Raw,
+}
+
+/// Key column of [`CollectionMetadata`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyColumn {
+    name: Option<String>,
+    sql_type: Option<KeySqlType>,
+    max_length: Option<u32>,
+    assignment: Option<KeyAssignment>,
+}
+
+impl KeyColumn {
+    pub fn new() -> KeyColumn {
+        KeyColumn::default()
+    }
+
+    pub fn name(mut self, name: &str) -> KeyColumn {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn sql_type(mut self, sql_type: KeySqlType) -> KeyColumn {
+        self.sql_type = Some(sql_type);
+        self
+    }
+
+    pub fn max_length(mut self, max_length: u32) -> KeyColumn {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn assignment(mut self, assignment: KeyAssignment) -> KeyColumn {
+        self.assignment = Some(assignment);
+        self
+    }
+
+    fn to_json(&self) -> String {
+        let mut w = ObjectWriter::new();
+        w.str("name", self.name.as_deref());
+        w.str(
+            "sqlType",
+            self.sql_type.map(|t| match t {
+                KeySqlType::Varchar2 => "VARCHAR2",
+                KeySqlType::Number => "NUMBER",
+                KeySqlType::Raw => "RAW",
+            }),
+        );
+        w.num("maxLength", self.max_length);
+        let (method, sequence) = match &self.assignment {
+            Some(KeyAssignment::Uuid) => (Some("UUID"), None),
+            Some(KeyAssignment::Guid) => (Some("GUID"), None),
+            Some(KeyAssignment::Sequence(name)) => (Some("SEQUENCE"), Some(name.as_str())),
+            Some(KeyAssignment::Client) => (Some("CLIENT"), None),
+            Some(KeyAssignment::EmbeddedOid) => (Some("EMBEDDED_OID"), None),
+            None => (None, None),
+        };
+        w.str("assignmentMethod", method);
+        w.str("sequenceName", sequence);
+        w.finish()
+    }
+}
+
+/// SQL type of content columns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentSqlType {
+    Blob,
+    Clob,
+    Varchar2,
+    /// Oracle's native JSON type
+    Json,
+}
+
+/// Validation of JSON content
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Validation {
+    Standard,
+    Strict,
+    Lax,
+}
+
+/// Content column of [`CollectionMetadata`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContentColumn {
+    name: Option<String>,
+    sql_type: Option<ContentSqlType>,
+    max_length: Option<u32>,
+    validation: Option<Validation>,
+}
+
+impl ContentColumn {
+    pub fn new() -> ContentColumn {
+        ContentColumn::default()
+    }
+
+    pub fn name(mut self, name: &str) -> ContentColumn {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn sql_type(mut self, sql_type: ContentSqlType) -> ContentColumn {
+        self.sql_type = Some(sql_type);
+        self
+    }
+
+    /// Sets the maximum length of `VARCHAR2` content.
+    pub fn max_length(mut self, max_length: u32) -> ContentColumn {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn validation(mut self, validation: Validation) -> ContentColumn {
+        self.validation = Some(validation);
+        self
+    }
+
+    fn to_json(&self) -> String {
+        let mut w = ObjectWriter::new();
+        w.str("name", self.name.as_deref());
+        w.str(
+            "sqlType",
+            self.sql_type.map(|t| match t {
+                ContentSqlType::Blob => "BLOB",
+                ContentSqlType::Clob => "CLOB",
+                ContentSqlType::Varchar2 => "VARCHAR2",
+                ContentSqlType::Json => "JSON",
+            }),
+        );
+        w.num("maxLength", self.max_length);
+        w.str(
+            "validation",
+            self.validation.map(|v| match v {
+                Validation::Standard => "STANDARD",
+                Validation::Strict => "STRICT",
+                Validation::Lax => "LAX",
+            }),
+        );
+        w.finish()
+    }
+}
+
+/// How versions of documents are generated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionMethod {
+    Uuid,
+    Timestamp,
+    Md5,
+    Sha256,
+    Sequential,
+    None,
+}
+
+/// Collection metadata passed to [`SodaDb::create_collection_with`] and
+/// [`SodaDb::map_collection`]
+///
+/// Unset properties take the defaults of the database.
+///
+/// ```
+/// use odpic::soda::{
+///     CollectionMetadata, ContentColumn, ContentSqlType, KeyAssignment, KeyColumn, VersionMethod,
+/// };
+///
+/// let metadata = CollectionMetadata::new()
+///     .table_name("ORDERS")
+///     .key_column(KeyColumn::new().name("ID").assignment(KeyAssignment::Client))
+///     .content_column(ContentColumn::new().sql_type(ContentSqlType::Json))
+///     .version_column("VERSION", VersionMethod::Uuid);
+/// assert_eq!(
+///     metadata.to_json(),
+///     r#"{"tableName":"ORDERS","keyColumn":{"name":"ID","assignmentMethod":"CLIENT"},"contentColumn":{"sqlType":"JSON"},"versionColumn":{"name":"VERSION","method":"UUID"}}"#
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CollectionMetadata {
+    schema_name: Option<String>,
+    table_name: Option<String>,
+    key_column: Option<KeyColumn>,
+    content_column: Option<ContentColumn>,
+    version_column: Option<(String, VersionMethod)>,
+    last_modified_column: Option<String>,
+    creation_time_column: Option<String>,
+    media_type_column: Option<String>,
+    read_only: Option<bool>,
+}
+
+impl CollectionMetadata {
+    pub fn new() -> CollectionMetadata {
+        CollectionMetadata::default()
+    }
+
+    pub fn schema_name(mut self, name: &str) -> CollectionMetadata {
+        self.schema_name = Some(name.into());
+        self
+    }
+
+    pub fn table_name(mut self, name: &str) -> CollectionMetadata {
+        self.table_name = Some(name.into());
+        self
+    }
+
+    pub fn key_column(mut self, column: KeyColumn) -> CollectionMetadata {
+        self.key_column = Some(column);
+        self
+    }
+
+    pub fn content_column(mut self, column: ContentColumn) -> CollectionMetadata {
+        self.content_column = Some(column);
+        self
+    }
+
+    pub fn version_column(mut self, name: &str, method: VersionMethod) -> CollectionMetadata {
+        self.version_column = Some((name.into(), method));
+        self
+    }
+
+    pub fn last_modified_column(mut self, name: &str) -> CollectionMetadata {
+        self.last_modified_column = Some(name.into());
+        self
+    }
+
+    pub fn creation_time_column(mut self, name: &str) -> CollectionMetadata {
+        self.creation_time_column = Some(name.into());
+        self
+    }
+
+    /// Sets the column of media types, which is needed to store non-JSON
+    /// documents.
+    pub fn media_type_column(mut self, name: &str) -> CollectionMetadata {
+        self.media_type_column = Some(name.into());
+        self
+    }
+
+    pub fn read_only(mut self, enable: bool) -> CollectionMetadata {
+        self.read_only = Some(enable);
+        self
+    }
+
+    /// Returns the metadata in JSON.
+    pub fn to_json(&self) -> String {
+        fn column(name: &Option<String>) -> Option<String> {
+            name.as_ref().map(|name| {
+                let mut w = ObjectWriter::new();
+                w.str("name", Some(name));
+                w.finish()
+            })
+        }
+        let mut w = ObjectWriter::new();
+        w.str("schemaName", self.schema_name.as_deref());
+        w.str("tableName", self.table_name.as_deref());
+        w.raw(
+            "keyColumn",
+            self.key_column.as_ref().map(KeyColumn::to_json).as_deref(),
+        );
+        w.raw(
+            "contentColumn",
+            self.content_column
+                .as_ref()
+                .map(ContentColumn::to_json)
+                .as_deref(),
+        );
+        if let Some((name, method)) = &self.version_column {
+            let mut v = ObjectWriter::new();
+            v.str("name", Some(name));
+            v.str(
+                "method",
+                Some(match method {
+                    VersionMethod::Uuid => "UUID",
+                    VersionMethod::Timestamp => "TIMESTAMP",
+                    VersionMethod::Md5 => "MD5",
+                    VersionMethod::Sha256 => "SHA256",
+                    VersionMethod::Sequential => "SEQUENTIAL",
+                    VersionMethod::None => "NONE",
+                }),
+            );
+            w.raw("versionColumn", Some(&v.finish()));
+        }
+        w.raw(
+            "lastModifiedColumn",
+            column(&self.last_modified_column).as_deref(),
+        );
+        w.raw(
+            "creationTimeColumn",
+            column(&self.creation_time_column).as_deref(),
+        );
+        w.raw(
+            "mediaTypeColumn",
+            column(&self.media_type_column).as_deref(),
+        );
+        w.bool("readOnly", self.read_only);
+        w.finish()
+    }
+}
+
+/// Type of [`SchemaNode`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaType {
+    Object,
+    Array,
+    String,
+    Number,
+    Boolean,
+    Null,
+    /// values of more than one type, listed in `oneOf`
+    OneOf(Vec<SchemaNode>),
+    /// other types such as `double` and `timestamp`
+    Other(String),
+}
+
+/// Node of the schema tree of [`DataGuide`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaNode {
+    pub schema_type: SchemaType,
+    /// `o:length`, the maximum length of values
+    pub length: Option<u64>,
+    /// `o:preferred_column_name`
+    pub preferred_column_name: Option<String>,
+    /// `o:frequency`, the percentage of documents with the field
+    pub frequency: Option<f64>,
+    /// fields of objects in their order
+    pub properties: Vec<(String, SchemaNode)>,
+    /// elements of arrays
+    pub items: Option<Box<SchemaNode>>,
+}
+
+impl SchemaNode {
+    fn from_json(value: &JsonValue, path: &str) -> Result<SchemaNode> {
+        let JsonValue::Object(fields) = value else {
+            return Err(invalid_data_guide(path, "isn't an object"));
+        };
+        let mut node = SchemaNode {
+            schema_type: SchemaType::Null,
+            length: None,
+            preferred_column_name: None,
+            frequency: None,
+            properties: Vec::new(),
+            items: None,
+        };
+        let mut schema_type = None;
+        for (key, value) in fields {
+            match (key.as_str(), value) {
+                ("type", JsonValue::String(t)) => {
+                    schema_type = Some(match t.as_str() {
+                        "object" => SchemaType::Object,
+                        "array" => SchemaType::Array,
+                        "string" => SchemaType::String,
+                        "number" => SchemaType::Number,
+                        "boolean" => SchemaType::Boolean,
+                        "null" => SchemaType::Null,
+                        _ => SchemaType::Other(t.clone()),
+                    })
+                }
+                ("oneOf", JsonValue::Array(nodes)) => {
+                    schema_type = Some(SchemaType::OneOf(
+                        nodes
+                            .iter()
+                            .map(|n| SchemaNode::from_json(n, path))
+                            .collect::<Result<_>>()?,
+                    ))
+                }
+                ("o:length", value) => node.length = json_number(value).map(|n| n as u64),
+                ("o:frequency", value) => node.frequency = json_number(value),
+                ("o:preferred_column_name", JsonValue::String(name)) => {
+                    node.preferred_column_name = Some(name.clone())
+                }
+                ("properties", JsonValue::Object(props)) => {
+                    for (name, value) in props {
+                        let path = if path.is_empty() {
+                            name.clone()
+                        } else {
+                            format!("{}.{}", path, name)
+                        };
+                        node.properties
+                            .push((name.clone(), SchemaNode::from_json(value, &path)?));
+                    }
+                }
+                ("items", value) => {
+                    node.items = Some(Box::new(SchemaNode::from_json(
+                        value,
+                        &format!("{}[*]", path),
+                    )?))
+                }
+                _ => {}
+            }
+        }
+        node.schema_type =
+            schema_type.ok_or_else(|| invalid_data_guide(path, "has neither type nor oneOf"))?;
+        Ok(node)
+    }
+
+    fn collect_paths<'a>(&'a self, path: &str, paths: &mut Vec<(String, &'a SchemaNode)>) {
+        for (name, node) in &self.properties {
+            let path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", path, name)
+            };
+            paths.push((path.clone(), node));
+            node.collect_paths(&path, paths);
+        }
+        if let Some(items) = &self.items {
+            let path = format!("{}[*]", path);
+            paths.push((path.clone(), items));
+            items.collect_paths(&path, paths);
+        }
+        if let SchemaType::OneOf(nodes) = &self.schema_type {
+            for node in nodes {
+                node.collect_paths(path, paths);
+            }
+        }
+    }
+}
+
+fn json_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.parse().ok(),
+        JsonValue::Float(n) => Some(*n as f64),
+        JsonValue::Double(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn invalid_data_guide(path: &str, msg: &str) -> Error {
+    Error::InvalidConversion(format!(
+        "data guide node at '{}' {}",
+        if path.is_empty() { "$" } else { path },
+        msg
+    ))
+}
+
+/// Data guide of a collection in hierarchical format, returned by
+/// [`Collection::data_guide`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataGuide {
+    pub root: SchemaNode,
+}
+
+impl DataGuide {
+    /// Parses a data guide in hierarchical format.
+    pub fn from_json(value: &JsonValue) -> Result<DataGuide> {
+        Ok(DataGuide {
+            root: SchemaNode::from_json(value, "")?,
+        })
+    }
+
+    /// Returns paths and nodes of all fields in depth-first order. Elements
+    /// of arrays are at paths ending with `[*]`.
+    pub fn paths(&self) -> Vec<(String, &SchemaNode)> {
+        let mut paths = Vec::new();
+        self.root.collect_paths("", &mut paths);
+        paths
+    }
+}
@@ -0,0 +1,797 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::lob::{BFile, Lob, LobStream, TempLob};
+use crate::{
+    Context, Error, IntervalDS, IntervalYM, JsonValue, Result, SqlValue, Timestamp, Vector,
+};
+use std::io::Read;
+use std::os::raw::{c_char, c_int};
+use std::str::FromStr;
+
+const DEFAULT_VARCHAR_SIZE: u32 = 4000;
+const DEFAULT_RAW_SIZE: u32 = 2000;
+
+/// Oracle type, native type and buffer size of a variable
+///
+/// This determines the arguments of [`dpiConn_newVar`] used to bind values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SqlType {
+    pub oracle_type: dpiOracleTypeNum,
+    pub native_type: dpiNativeTypeNum,
+    /// buffer size in bytes for `DPI_NATIVE_TYPE_BYTES`. It is ignored for
+    /// other native types.
+    pub size: u32,
+}
+
+impl SqlType {
+    pub const fn new(
+        oracle_type: dpiOracleTypeNum,
+        native_type: dpiNativeTypeNum,
+        size: u32,
+    ) -> SqlType {
+        SqlType {
+            oracle_type,
+            native_type,
+            size,
+        }
+    }
+
+    /// `VARCHAR2` of `size` bytes
+    pub const fn varchar(size: u32) -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_VARCHAR, DPI_NATIVE_TYPE_BYTES, size)
+    }
+
+    /// `RAW` of `size` bytes
+    pub const fn raw(size: u32) -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_RAW, DPI_NATIVE_TYPE_BYTES, size)
+    }
+
+    /// Returns the type with `size` changed.
+    pub const fn with_size(self, size: u32) -> SqlType {
+        SqlType { size, ..self }
+    }
+}
+
+/// A value in a variable which [`ToSql::to_sql`] sets
+///
+/// Setters fail when the native type of the variable doesn't match.
+#[derive(Debug)]
+pub struct SqlData<'a> {
+    pub(crate) ctxt: &'static Context,
+    pub(crate) var: *mut dpiVar,
+    pub(crate) data: *mut dpiData,
+    pub(crate) pos: u32,
+    pub(crate) sql_type: &'a SqlType,
+}
+
+impl SqlData<'_> {
+    /// Returns the type of the variable.
+    pub fn sql_type(&self) -> &SqlType {
+        self.sql_type
+    }
+
+    fn check_native_type(&self, native_type: dpiNativeTypeNum, name: &str) -> Result<()> {
+        if self.sql_type.native_type == native_type {
+            Ok(())
+        } else {
+            Err(Error::InvalidConversion(format!(
+                "cannot set {} to a variable of native type {}",
+                name, self.sql_type.native_type
+            )))
+        }
+    }
+
+    pub fn set_null(&mut self) -> Result<()> {
+        unsafe { dpiData_setNull(self.data) };
+        Ok(())
+    }
+
+    pub fn set_bool(&mut self, value: bool) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_BOOLEAN, "bool")?;
+        unsafe { dpiData_setBool(self.data, value as c_int) };
+        Ok(())
+    }
+
+    pub fn set_int64(&mut self, value: i64) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_INT64, "i64")?;
+        unsafe { dpiData_setInt64(self.data, value) };
+        Ok(())
+    }
+
+    pub fn set_uint64(&mut self, value: u64) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_UINT64, "u64")?;
+        unsafe { dpiData_setUint64(self.data, value) };
+        Ok(())
+    }
+
+    pub fn set_float(&mut self, value: f32) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_FLOAT, "f32")?;
+        unsafe { dpiData_setFloat(self.data, value) };
+        Ok(())
+    }
+
+    pub fn set_double(&mut self, value: f64) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_DOUBLE, "f64")?;
+        unsafe { dpiData_setDouble(self.data, value) };
+        Ok(())
+    }
+
+    /// Sets bytes, which are copied into the variable. They must be in
+    /// UTF-8 for character types.
+    pub fn set_bytes(&mut self, value: &[u8]) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_BYTES, "bytes")?;
+        chkerr!(
+            self.ctxt,
+            dpiVar_setFromBytes(
+                self.var,
+                self.pos,
+                value.as_ptr() as *const c_char,
+                value.len() as u32
+            )
+        );
+        Ok(())
+    }
+
+    pub fn set_timestamp(&mut self, value: &Timestamp) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_TIMESTAMP, "timestamp")?;
+        unsafe {
+            (*self.data).isNull = 0;
+            (*self.data).value.asTimestamp = (*value).into();
+        }
+        Ok(())
+    }
+
+    pub fn set_interval_ds(&mut self, value: &IntervalDS) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_INTERVAL_DS, "interval day to second")?;
+        unsafe {
+            dpiData_setIntervalDS(
+                self.data,
+                value.days,
+                value.hours,
+                value.minutes,
+                value.seconds,
+                value.nanoseconds,
+            )
+        };
+        Ok(())
+    }
+
+    pub fn set_interval_ym(&mut self, value: &IntervalYM) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_INTERVAL_YM, "interval year to month")?;
+        unsafe { dpiData_setIntervalYM(self.data, value.years, value.months) };
+        Ok(())
+    }
+
+    pub fn set_lob(&mut self, value: &Lob) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_LOB, "LOB")?;
+        chkerr!(
+            self.ctxt,
+            dpiVar_setFromLob(self.var, self.pos, value.handle())
+        );
+        Ok(())
+    }
+
+    pub fn set_vector(&mut self, value: &Vector) -> Result<()> {
+        self.check_native_type(DPI_NATIVE_TYPE_VECTOR, "vector")?;
+        let mut info = value.to_dpi()?;
+        chkerr!(
+            self.ctxt,
+            dpiVector_setValue(dpiData_getVector(self.data), info.as_mut_ptr())
+        );
+        unsafe { (*self.data).isNull = 0 };
+        Ok(())
+    }
+}
+
+/// Conversion from Rust values to Oracle values
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let mut stmt = conn.prepare("insert into emp(empno, ename, mgr) values (:1, :2, :3)")?;
+/// stmt.bind(1, &7369)?;
+/// stmt.bind(2, &"SMITH")?;
+/// stmt.bind(3, &None::<i32>)?;
+/// stmt.execute()?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ToSql {
+    /// Returns the type of a variable to bind the value.
+    fn sql_type(&self) -> SqlType;
+
+    /// Sets the value to a variable whose type is [`ToSql::sql_type`] of
+    /// this value or another value of the same Rust type.
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()>;
+
+    /// Returns true for null values, which may be set to variables of any
+    /// type.
+    fn is_null(&self) -> bool {
+        false
+    }
+}
+
+/// Type of null values
+///
+/// This gives the type of variables used to bind `None` of `Option<T>`.
+pub trait ToSqlNull {
+    fn sql_null_type() -> SqlType;
+}
+
+/// Conversion from Oracle values to Rust values
+pub trait FromSql: Sized {
+    /// Returns the preferred type of variables to receive values, such as
+    /// OUT bind variables.
+    fn sql_type() -> SqlType;
+
+    /// Converts a value. This fails for null unless `Self` is `Option<T>`.
+    fn from_sql(value: &SqlValue) -> Result<Self>;
+}
+
+fn conversion_error(value: &SqlValue, to: &str) -> Error {
+    if value.is_null() {
+        Error::NullValue
+    } else {
+        Error::InvalidConversion(format!("cannot convert {} to {}", value.type_name(), to))
+    }
+}
+
+fn parse<T: FromStr>(value: &SqlValue, s: &str, to: &str) -> Result<T> {
+    s.parse().map_err(|_| {
+        Error::InvalidConversion(format!(
+            "cannot convert {} {:?} to {}",
+            value.type_name(),
+            s,
+            to
+        ))
+    })
+}
+
+impl<T: ToSql + ?Sized> ToSql for &T {
+    fn sql_type(&self) -> SqlType {
+        (**self).sql_type()
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        (**self).to_sql(data)
+    }
+
+    fn is_null(&self) -> bool {
+        (**self).is_null()
+    }
+}
+
+impl<T: ToSql + ToSqlNull> ToSql for Option<T> {
+    fn sql_type(&self) -> SqlType {
+        match self {
+            Some(value) => value.sql_type(),
+            None => T::sql_null_type(),
+        }
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        match self {
+            Some(value) => value.to_sql(data),
+            None => data.set_null(),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        match self {
+            Some(value) => value.is_null(),
+            None => true,
+        }
+    }
+}
+
+impl<T: ToSqlNull> ToSqlNull for Option<T> {
+    fn sql_null_type() -> SqlType {
+        T::sql_null_type()
+    }
+}
+
+impl<T: FromSql> FromSql for Option<T> {
+    fn sql_type() -> SqlType {
+        T::sql_type()
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<Option<T>> {
+        match value {
+            SqlValue::Null => Ok(None),
+            _ => T::from_sql(value).map(Some),
+        }
+    }
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty),* => $native_type:ident, $setter:ident, $as_ty:ty) => {
+        $(
+            impl ToSql for $ty {
+                fn sql_type(&self) -> SqlType {
+                    <$ty>::sql_null_type()
+                }
+
+                fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+                    data.$setter(*self as $as_ty)
+                }
+            }
+
+            impl ToSqlNull for $ty {
+                fn sql_null_type() -> SqlType {
+                    SqlType::new(DPI_ORACLE_TYPE_NUMBER, $native_type, 0)
+                }
+            }
+
+            impl FromSql for $ty {
+                fn sql_type() -> SqlType {
+                    <$ty>::sql_null_type()
+                }
+
+                fn from_sql(value: &SqlValue) -> Result<$ty> {
+                    let name = stringify!($ty);
+                    let out_of_range =
+                        || Error::InvalidConversion(format!("{:?} is out of range of {}", value, name));
+                    match value {
+                        SqlValue::Int64(v) => <$ty>::try_from(*v).map_err(|_| out_of_range()),
+                        SqlValue::Uint64(v) => <$ty>::try_from(*v).map_err(|_| out_of_range()),
+                        SqlValue::Float(v) if v.fract() == 0.0 => {
+                            <$ty>::try_from(*v as i128).map_err(|_| out_of_range())
+                        }
+                        SqlValue::Double(v) if v.fract() == 0.0 => {
+                            <$ty>::try_from(*v as i128).map_err(|_| out_of_range())
+                        }
+                        SqlValue::Number(s) | SqlValue::String(s) => parse(value, s, name),
+                        _ => Err(conversion_error(value, name)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, isize => DPI_NATIVE_TYPE_INT64, set_int64, i64);
+impl_integer!(u8, u16, u32, u64, usize => DPI_NATIVE_TYPE_UINT64, set_uint64, u64);
+
+macro_rules! impl_float {
+    ($ty:ty, $oracle_type:ident, $native_type:ident, $setter:ident) => {
+        impl ToSql for $ty {
+            fn sql_type(&self) -> SqlType {
+                <$ty>::sql_null_type()
+            }
+
+            fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+                data.$setter(*self)
+            }
+        }
+
+        impl ToSqlNull for $ty {
+            fn sql_null_type() -> SqlType {
+                SqlType::new($oracle_type, $native_type, 0)
+            }
+        }
+
+        impl FromSql for $ty {
+            fn sql_type() -> SqlType {
+                <$ty>::sql_null_type()
+            }
+
+            fn from_sql(value: &SqlValue) -> Result<$ty> {
+                let name = stringify!($ty);
+                match value {
+                    SqlValue::Int64(v) => Ok(*v as $ty),
+                    SqlValue::Uint64(v) => Ok(*v as $ty),
+                    SqlValue::Float(v) => Ok(*v as $ty),
+                    SqlValue::Double(v) => Ok(*v as $ty),
+                    SqlValue::Number(s) | SqlValue::String(s) => parse(value, s, name),
+                    _ => Err(conversion_error(value, name)),
+                }
+            }
+        }
+    };
+}
+
+impl_float!(
+    f32,
+    DPI_ORACLE_TYPE_NATIVE_FLOAT,
+    DPI_NATIVE_TYPE_FLOAT,
+    set_float
+);
+impl_float!(
+    f64,
+    DPI_ORACLE_TYPE_NATIVE_DOUBLE,
+    DPI_NATIVE_TYPE_DOUBLE,
+    set_double
+);
+
+impl ToSql for bool {
+    fn sql_type(&self) -> SqlType {
+        bool::sql_null_type()
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        data.set_bool(*self)
+    }
+}
+
+impl ToSqlNull for bool {
+    fn sql_null_type() -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_BOOLEAN, DPI_NATIVE_TYPE_BOOLEAN, 0)
+    }
+}
+
+impl FromSql for bool {
+    fn sql_type() -> SqlType {
+        bool::sql_null_type()
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<bool> {
+        match value {
+            SqlValue::Boolean(v) => Ok(*v),
+            _ => Err(conversion_error(value, "bool")),
+        }
+    }
+}
+
+impl ToSql for str {
+    fn sql_type(&self) -> SqlType {
+        SqlType::varchar(self.len() as u32)
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        data.set_bytes(self.as_bytes())
+    }
+}
+
+impl ToSql for String {
+    fn sql_type(&self) -> SqlType {
+        self.as_str().sql_type()
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        self.as_str().to_sql(data)
+    }
+}
+
+impl ToSqlNull for String {
+    fn sql_null_type() -> SqlType {
+        SqlType::varchar(1)
+    }
+}
+
+impl ToSqlNull for &str {
+    fn sql_null_type() -> SqlType {
+        SqlType::varchar(1)
+    }
+}
+
+impl FromSql for String {
+    fn sql_type() -> SqlType {
+        SqlType::varchar(DEFAULT_VARCHAR_SIZE)
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<String> {
+        match value {
+            SqlValue::String(s) | SqlValue::Number(s) | SqlValue::Rowid(s) => Ok(s.clone()),
+            SqlValue::Int64(v) => Ok(v.to_string()),
+            SqlValue::Uint64(v) => Ok(v.to_string()),
+            SqlValue::Float(v) => Ok(v.to_string()),
+            SqlValue::Double(v) => Ok(v.to_string()),
+            SqlValue::Lob(lob) if lob.is_char()? => {
+                let mut s = String::new();
+                LobStream::new(lob.clone())?.read_to_string(&mut s)?;
+                Ok(s)
+            }
+            _ => Err(conversion_error(value, "String")),
+        }
+    }
+}
+
+impl ToSql for [u8] {
+    fn sql_type(&self) -> SqlType {
+        SqlType::raw(self.len() as u32)
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        data.set_bytes(self)
+    }
+}
+
+impl ToSql for Vec<u8> {
+    fn sql_type(&self) -> SqlType {
+        self.as_slice().sql_type()
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        self.as_slice().to_sql(data)
+    }
+}
+
+impl ToSqlNull for Vec<u8> {
+    fn sql_null_type() -> SqlType {
+        SqlType::raw(1)
+    }
+}
+
+impl ToSqlNull for &[u8] {
+    fn sql_null_type() -> SqlType {
+        SqlType::raw(1)
+    }
+}
+
+impl FromSql for Vec<u8> {
+    fn sql_type() -> SqlType {
+        SqlType::raw(DEFAULT_RAW_SIZE)
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<Vec<u8>> {
+        match value {
+            SqlValue::Raw(v) => Ok(v.clone()),
+            SqlValue::Lob(lob) if !lob.is_char()? => {
+                let mut v = Vec::new();
+                LobStream::new(lob.clone())?.read_to_end(&mut v)?;
+                Ok(v)
+            }
+            _ => Err(conversion_error(value, "Vec<u8>")),
+        }
+    }
+}
+
+macro_rules! impl_fixed {
+    ($ty:ty, $variant:ident, $oracle_type:ident, $native_type:ident, $setter:ident) => {
+        impl ToSql for $ty {
+            fn sql_type(&self) -> SqlType {
+                <$ty>::sql_null_type()
+            }
+
+            fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+                data.$setter(self)
+            }
+        }
+
+        impl ToSqlNull for $ty {
+            fn sql_null_type() -> SqlType {
+                SqlType::new($oracle_type, $native_type, 0)
+            }
+        }
+
+        impl FromSql for $ty {
+            fn sql_type() -> SqlType {
+                <$ty>::sql_null_type()
+            }
+
+            fn from_sql(value: &SqlValue) -> Result<$ty> {
+                match value {
+                    SqlValue::$variant(v) => Ok(v.clone()),
+                    _ => Err(conversion_error(value, stringify!($ty))),
+                }
+            }
+        }
+    };
+}
+
+// Widens `t`, the type of a variable holding values seen so far, to hold a
+// value of `value` type and returns false when the types don't match.
+// `only_nulls` tells whether the values seen so far are all null. Null
+// values match any types.
+pub(crate) fn widen_sql_type(
+    t: &mut SqlType,
+    only_nulls: &mut bool,
+    value: &SqlType,
+    is_null: bool,
+) -> bool {
+    if !is_null {
+        if *only_nulls {
+            t.oracle_type = value.oracle_type;
+            t.native_type = value.native_type;
+            *only_nulls = false;
+        } else if t.oracle_type != value.oracle_type || t.native_type != value.native_type {
+            return false;
+        }
+    }
+    t.size = t.size.max(value.size);
+    true
+}
+
+// Timestamps are bound as `TIMESTAMP WITH TIME ZONE` only when they have
+// time zones. Binding zone-less ones at offset 0 would shift them into the
+// session time zone when compared with or stored into `DATE` and
+// `TIMESTAMP` columns.
+impl ToSql for Timestamp {
+    fn sql_type(&self) -> SqlType {
+        if self.has_tz {
+            SqlType::new(DPI_ORACLE_TYPE_TIMESTAMP_TZ, DPI_NATIVE_TYPE_TIMESTAMP, 0)
+        } else {
+            Timestamp::sql_null_type()
+        }
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        data.set_timestamp(self)
+    }
+}
+
+impl ToSqlNull for Timestamp {
+    fn sql_null_type() -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_TIMESTAMP, DPI_NATIVE_TYPE_TIMESTAMP, 0)
+    }
+}
+
+impl FromSql for Timestamp {
+    // Out binds keep time zones of `TIMESTAMP WITH TIME ZONE`.
+    fn sql_type() -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_TIMESTAMP_TZ, DPI_NATIVE_TYPE_TIMESTAMP, 0)
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<Timestamp> {
+        match value {
+            SqlValue::Timestamp(v) => Ok(*v),
+            _ => Err(conversion_error(value, "Timestamp")),
+        }
+    }
+}
+
+impl_fixed!(
+    IntervalDS,
+    IntervalDS,
+    DPI_ORACLE_TYPE_INTERVAL_DS,
+    DPI_NATIVE_TYPE_INTERVAL_DS,
+    set_interval_ds
+);
+impl_fixed!(
+    IntervalYM,
+    IntervalYM,
+    DPI_ORACLE_TYPE_INTERVAL_YM,
+    DPI_NATIVE_TYPE_INTERVAL_YM,
+    set_interval_ym
+);
+impl_fixed!(
+    Vector,
+    Vector,
+    DPI_ORACLE_TYPE_VECTOR,
+    DPI_NATIVE_TYPE_VECTOR,
+    set_vector
+);
+
+impl FromSql for JsonValue {
+    fn sql_type() -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_JSON, DPI_NATIVE_TYPE_JSON, 0)
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<JsonValue> {
+        match value {
+            SqlValue::Json(v) => Ok(v.clone()),
+            _ => Err(conversion_error(value, "JsonValue")),
+        }
+    }
+}
+
+impl ToSql for Lob {
+    fn sql_type(&self) -> SqlType {
+        let oracle_type = self.oracle_type().unwrap_or(DPI_ORACLE_TYPE_BLOB);
+        SqlType::new(oracle_type, DPI_NATIVE_TYPE_LOB, 0)
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        data.set_lob(self)
+    }
+}
+
+impl FromSql for Lob {
+    fn sql_type() -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_BLOB, DPI_NATIVE_TYPE_LOB, 0)
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<Lob> {
+        match value {
+            SqlValue::Lob(lob) => Ok(lob.clone()),
+            _ => Err(conversion_error(value, "Lob")),
+        }
+    }
+}
+
+impl ToSql for TempLob {
+    fn sql_type(&self) -> SqlType {
+        (**self).sql_type()
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        data.set_lob(self)
+    }
+}
+
+impl ToSql for BFile {
+    fn sql_type(&self) -> SqlType {
+        <BFile as FromSql>::sql_type()
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        data.set_lob(self.lob())
+    }
+}
+
+impl FromSql for BFile {
+    fn sql_type() -> SqlType {
+        SqlType::new(DPI_ORACLE_TYPE_BFILE, DPI_NATIVE_TYPE_LOB, 0)
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<BFile> {
+        match value {
+            SqlValue::Lob(lob) => lob.clone().try_into(),
+            _ => Err(conversion_error(value, "BFile")),
+        }
+    }
+}
+
+impl ToSql for SqlValue {
+    fn sql_type(&self) -> SqlType {
+        match self {
+            SqlValue::Null => SqlType::varchar(1),
+            SqlValue::Boolean(v) => v.sql_type(),
+            SqlValue::Int64(v) => v.sql_type(),
+            SqlValue::Uint64(v) => v.sql_type(),
+            SqlValue::Float(v) => v.sql_type(),
+            SqlValue::Double(v) => v.sql_type(),
+            SqlValue::Number(s) => SqlType::new(
+                DPI_ORACLE_TYPE_NUMBER,
+                DPI_NATIVE_TYPE_BYTES,
+                s.len() as u32,
+            ),
+            SqlValue::String(s) => s.sql_type(),
+            SqlValue::Raw(v) => v.sql_type(),
+            SqlValue::Timestamp(v) => v.sql_type(),
+            SqlValue::IntervalDS(v) => v.sql_type(),
+            SqlValue::IntervalYM(v) => v.sql_type(),
+            SqlValue::Lob(v) => v.sql_type(),
+            SqlValue::Json(_) => JsonValue::sql_type(),
+            SqlValue::Vector(v) => v.sql_type(),
+            SqlValue::Rowid(s) => s.sql_type(),
+        }
+    }
+
+    fn to_sql(&self, data: &mut SqlData<'_>) -> Result<()> {
+        match self {
+            SqlValue::Null => data.set_null(),
+            SqlValue::Boolean(v) => v.to_sql(data),
+            SqlValue::Int64(v) => v.to_sql(data),
+            SqlValue::Uint64(v) => v.to_sql(data),
+            SqlValue::Float(v) => v.to_sql(data),
+            SqlValue::Double(v) => v.to_sql(data),
+            SqlValue::Number(s) | SqlValue::String(s) | SqlValue::Rowid(s) => s.to_sql(data),
+            SqlValue::Raw(v) => v.to_sql(data),
+            SqlValue::Timestamp(v) => v.to_sql(data),
+            SqlValue::IntervalDS(v) => v.to_sql(data),
+            SqlValue::IntervalYM(v) => v.to_sql(data),
+            SqlValue::Lob(v) => v.to_sql(data),
+            SqlValue::Json(_) => Err(Error::UnsupportedType("JSON values cannot be bound".into())),
+            SqlValue::Vector(v) => v.to_sql(data),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self, SqlValue::Null)
+    }
+}
+
+impl FromSql for SqlValue {
+    fn sql_type() -> SqlType {
+        SqlType::varchar(DEFAULT_VARCHAR_SIZE)
+    }
+
+    fn from_sql(value: &SqlValue) -> Result<SqlValue> {
+        Ok(value.clone())
+    }
+}
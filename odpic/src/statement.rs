@@ -0,0 +1,613 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::var::Var;
+use crate::{Connection, Error, FromSql, Result, SqlValue, ToSql};
+use std::mem;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for &str {}
+}
+
+/// Position or name of a bind parameter
+///
+/// Positions are 1-based. Names are without the leading colon.
+pub trait BindIndex: private::Sealed {
+    #[doc(hidden)]
+    unsafe fn bind(&self, stmt: *mut dpiStmt, var: *mut dpiVar) -> i32;
+    // key to identify the bind parameter in the statement
+    #[doc(hidden)]
+    fn key(&self) -> String;
+}
+
+impl BindIndex for usize {
+    unsafe fn bind(&self, stmt: *mut dpiStmt, var: *mut dpiVar) -> i32 {
+        dpiStmt_bindByPos(stmt, *self as u32, var)
+    }
+
+    fn key(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl BindIndex for &str {
+    unsafe fn bind(&self, stmt: *mut dpiStmt, var: *mut dpiVar) -> i32 {
+        dpiStmt_bindByName(stmt, self.as_ptr() as *const c_char, self.len() as u32, var)
+    }
+
+    fn key(&self) -> String {
+        self.to_uppercase()
+    }
+}
+
+/// Index or name of a query column
+///
+/// Indexes are 0-based. Names must match [`ColumnInfo::name`].
+pub trait ColumnIndex: private::Sealed {
+    #[doc(hidden)]
+    fn index(&self, columns: &[ColumnInfo]) -> Result<usize>;
+}
+
+impl ColumnIndex for usize {
+    fn index(&self, columns: &[ColumnInfo]) -> Result<usize> {
+        if *self < columns.len() {
+            Ok(*self)
+        } else {
+            Err(Error::InvalidIndex(format!(
+                "column index {} is out of range of {} columns",
+                self,
+                columns.len()
+            )))
+        }
+    }
+}
+
+impl ColumnIndex for &str {
+    fn index(&self, columns: &[ColumnInfo]) -> Result<usize> {
+        columns
+            .iter()
+            .position(|column| column.name == *self)
+            .ok_or_else(|| Error::InvalidIndex(format!("no such column {:?}", self)))
+    }
+}
+
+/// Properties of a prepared statement
+///
+/// This is an owned counterpart of [`dpiStmtInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatementInfo {
+    pub is_query: bool,
+    pub is_plsql: bool,
+    pub is_ddl: bool,
+    pub is_dml: bool,
+    /// true when the statement has a `RETURNING INTO` clause
+    pub is_returning: bool,
+    /// one of `DPI_STMT_TYPE_*` constants
+    pub statement_type: dpiStatementType,
+}
+
+impl From<&dpiStmtInfo> for StatementInfo {
+    fn from(info: &dpiStmtInfo) -> StatementInfo {
+        StatementInfo {
+            is_query: info.isQuery != 0,
+            is_plsql: info.isPLSQL != 0,
+            is_ddl: info.isDDL != 0,
+            is_dml: info.isDML != 0,
+            is_returning: info.isReturning != 0,
+            statement_type: info.statementType,
+        }
+    }
+}
+
+/// Metadata of a query column
+///
+/// This is an owned counterpart of [`dpiQueryInfo`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub oracle_type: dpiOracleTypeNum,
+    /// native type which the column is fetched as
+    pub native_type: dpiNativeTypeNum,
+    pub db_size_in_bytes: u32,
+    pub client_size_in_bytes: u32,
+    pub size_in_chars: u32,
+    pub precision: i16,
+    pub scale: i8,
+    pub fs_precision: u8,
+    /// true when the column is known to contain JSON data
+    pub is_json: bool,
+    pub nullable: bool,
+}
+
+impl ColumnInfo {
+    unsafe fn from_dpi(info: &dpiQueryInfo) -> ColumnInfo {
+        let name = slice::from_raw_parts(info.name as *const u8, info.nameLength as usize);
+        let type_info = &info.typeInfo;
+        ColumnInfo {
+            name: String::from_utf8_lossy(name).into_owned(),
+            oracle_type: type_info.oracleTypeNum,
+            native_type: type_info.defaultNativeTypeNum,
+            db_size_in_bytes: type_info.dbSizeInBytes,
+            client_size_in_bytes: type_info.clientSizeInBytes,
+            size_in_chars: type_info.sizeInChars,
+            precision: type_info.precision,
+            scale: type_info.scale,
+            fs_precision: type_info.fsPrecision,
+            is_json: type_info.isJson != 0,
+            nullable: info.nullOk != 0,
+        }
+    }
+}
+
+/// Prepared statement
+///
+/// This holds a reference to [`dpiStmt`], which is released when it is
+/// dropped. A statement cannot outlive the connection it is prepared on.
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let mut stmt = conn.prepare("select empno, ename from emp")?;
+/// for row in stmt.query()? {
+///     let row = row?;
+///     println!("{:?}", row.values());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Statement<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiStmt,
+    info: StatementInfo,
+    bind_vars: Vec<BoundVar>,
+    // values returned by DML RETURNING INTO per OUT variable and iteration
+    returned: Vec<(String, Vec<Vec<SqlValue>>)>,
+}
+
+#[derive(Debug)]
+struct BoundVar {
+    key: String,
+    var: Var,
+    is_out: bool,
+}
+
+impl<'conn> Statement<'conn> {
+    /// Prepares `sql` on `conn`.
+    pub fn new(conn: &'conn Connection, sql: &str) -> Result<Statement<'conn>> {
+        Statement::prepare(conn, sql, false)
+    }
+
+    pub(crate) fn prepare(
+        conn: &'conn Connection,
+        sql: &str,
+        scrollable: bool,
+    ) -> Result<Statement<'conn>> {
+        let ctxt = conn.ctxt();
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiConn_prepareStmt(
+                conn.handle(),
+                scrollable as i32,
+                sql.as_ptr() as *const c_char,
+                sql.len() as u32,
+                ptr::null(),
+                0,
+                &mut handle
+            )
+        );
+        unsafe { Statement::from_prepared(conn, handle) }
+    }
+
+    // Creates a statement from a newly prepared statement, taking over the
+    // reference to it.
+    pub(crate) unsafe fn from_prepared(
+        conn: &'conn Connection,
+        handle: *mut dpiStmt,
+    ) -> Result<Statement<'conn>> {
+        let mut info = mem::zeroed::<dpiStmtInfo>();
+        if dpiStmt_getInfo(handle, &mut info) != DPI_SUCCESS {
+            let err = conn.ctxt().last_error();
+            dpiStmt_release(handle);
+            return Err(Error::DpiError(err));
+        }
+        Ok(Statement {
+            conn,
+            handle,
+            info: (&info).into(),
+            bind_vars: Vec::new(),
+            returned: Vec::new(),
+        })
+    }
+
+    // Creates a statement of a REF CURSOR or an implicit result, adding a
+    // reference to it.
+    pub(crate) unsafe fn from_cursor(
+        conn: &'conn Connection,
+        handle: *mut dpiStmt,
+    ) -> Result<Statement<'conn>> {
+        chkerr!(conn.ctxt(), dpiStmt_addRef(handle));
+        Ok(Statement {
+            conn,
+            handle,
+            info: StatementInfo {
+                is_query: true,
+                is_plsql: false,
+                is_ddl: false,
+                is_dml: false,
+                is_returning: false,
+                statement_type: DPI_STMT_TYPE_SELECT,
+            },
+            bind_vars: Vec::new(),
+            returned: Vec::new(),
+        })
+    }
+
+    pub fn handle(&self) -> *mut dpiStmt {
+        self.handle
+    }
+
+    pub fn connection(&self) -> &'conn Connection {
+        self.conn
+    }
+
+    pub fn info(&self) -> &StatementInfo {
+        &self.info
+    }
+
+    /// Returns the number of rows fetched from the database in a round-trip.
+    pub fn fetch_array_size(&self) -> Result<u32> {
+        let mut size = 0;
+        chkerr!(
+            self.conn.ctxt(),
+            dpiStmt_getFetchArraySize(self.handle, &mut size)
+        );
+        Ok(size)
+    }
+
+    /// Sets the number of rows fetched from the database in a round-trip.
+    /// It must be set before [`Statement::query`].
+    pub fn set_fetch_array_size(&mut self, size: u32) -> Result<()> {
+        chkerr!(
+            self.conn.ctxt(),
+            dpiStmt_setFetchArraySize(self.handle, size)
+        );
+        Ok(())
+    }
+
+    /// Binds a value to the parameter at `index`.
+    ///
+    /// The value is copied into a variable of [`ToSql::sql_type`], which
+    /// the statement keeps until the parameter is bound again.
+    pub fn bind<I: BindIndex>(&mut self, index: I, value: &dyn ToSql) -> Result<()> {
+        let mut var = Var::new(self.conn, value.sql_type(), 1)?;
+        var.set(0, value)?;
+        self.bind_var(index, var)
+    }
+
+    pub(crate) fn bind_var<I: BindIndex>(&mut self, index: I, var: Var) -> Result<()> {
+        self.bind_var_with_direction(index, var, false)
+    }
+
+    pub(crate) fn bind_var_with_direction<I: BindIndex>(
+        &mut self,
+        index: I,
+        var: Var,
+        is_out: bool,
+    ) -> Result<()> {
+        chkerr!(self.conn.ctxt(), index.bind(self.handle, var.handle()));
+        let key = index.key();
+        match self.bind_vars.iter_mut().find(|bv| bv.key == key) {
+            Some(bv) => {
+                bv.var = var;
+                bv.is_out = is_out;
+            }
+            None => self.bind_vars.push(BoundVar { key, var, is_out }),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn bound_var<I: BindIndex>(&self, index: I) -> Result<&Var> {
+        self.bound_var_by_key(&index.key())
+    }
+
+    pub(crate) fn bound_var_by_key(&self, key: &str) -> Result<&Var> {
+        self.bind_vars
+            .iter()
+            .find(|bv| bv.key == key)
+            .map(|bv| &bv.var)
+            .ok_or_else(|| Error::InvalidIndex(format!("no variable is bound at {}", key)))
+    }
+
+    // Replaces OUT variables which cannot hold values of `num_iters`
+    // iterations of array DML.
+    pub(crate) fn reserve_out_vars(&mut self, num_iters: u32) -> Result<()> {
+        for bv in self.bind_vars.iter_mut() {
+            if bv.is_out && !bv.var.is_array() && bv.var.max_array_size() < num_iters {
+                let var = Var::new(self.conn, *bv.var.sql_type(), num_iters)?;
+                let rc = match bv.key.parse::<usize>() {
+                    Ok(pos) => unsafe { pos.bind(self.handle, var.handle()) },
+                    Err(_) => unsafe { bv.key.as_str().bind(self.handle, var.handle()) },
+                };
+                if rc != DPI_SUCCESS {
+                    return Err(Error::DpiError(self.conn.ctxt().last_error()));
+                }
+                bv.var = var;
+            }
+        }
+        Ok(())
+    }
+
+    // Collects values returned by DML RETURNING INTO in OUT variables.
+    pub(crate) fn collect_returned(&mut self, num_iters: u32, append: bool) -> Result<()> {
+        if !append {
+            self.returned.clear();
+        }
+        if !self.info.is_returning {
+            return Ok(());
+        }
+        for bv in self.bind_vars.iter().filter(|bv| bv.is_out) {
+            let values = match self.returned.iter_mut().find(|(k, _)| *k == bv.key) {
+                Some((_, values)) => values,
+                None => {
+                    self.returned.push((bv.key.clone(), Vec::new()));
+                    &mut self.returned.last_mut().unwrap().1
+                }
+            };
+            for pos in 0..num_iters {
+                values.push(bv.var.returned_values(pos)?);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn returned_by_key(&self, key: &str) -> Option<&[Vec<SqlValue>]> {
+        self.returned
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, values)| values.as_slice())
+    }
+
+    /// Executes the statement.
+    pub fn execute(&mut self) -> Result<()> {
+        self.exec(DPI_MODE_EXEC_DEFAULT)?;
+        self.collect_returned(1, false)
+    }
+
+    pub(crate) fn exec(&mut self, mode: dpiExecMode) -> Result<u32> {
+        let mut num_cols = 0;
+        if unsafe { dpiStmt_execute(self.handle, mode, &mut num_cols) } != DPI_SUCCESS {
+            let err = self.conn.ctxt().last_error();
+            // ORA-06513: PL/SQL: index for PL/SQL table out of range for host language array
+            if err.code == 6513 {
+                // IN arrays are never exceeded.
+                let arrays = self
+                    .bind_vars
+                    .iter()
+                    .filter(|bv| bv.is_out && bv.var.is_array())
+                    .map(|bv| (bv.key.clone(), bv.var.max_array_size()))
+                    .collect::<Vec<_>>();
+                if !arrays.is_empty() {
+                    return Err(Error::ArraySizeExceeded {
+                        arrays,
+                        error: Box::new(err),
+                    });
+                }
+            }
+            return Err(Error::DpiError(err));
+        }
+        Ok(num_cols)
+    }
+
+    /// Executes the query and returns an iterator of rows.
+    ///
+    /// This fails for statements other than queries.
+    pub fn query(&mut self) -> Result<Rows<'_>> {
+        if !self.info.is_query {
+            return Err(Error::InvalidOperation(
+                "rows cannot be fetched from statements other than queries".into(),
+            ));
+        }
+        let num_cols = self.exec(DPI_MODE_EXEC_DEFAULT)?;
+        let columns = self.define_columns(num_cols)?;
+        Ok(Rows {
+            stmt: self,
+            columns,
+            more_rows: true,
+        })
+    }
+
+    // Returns rows of a statement which has been executed.
+    pub(crate) fn rows(&mut self) -> Result<Rows<'_>> {
+        let mut num_cols = 0;
+        chkerr!(
+            self.conn.ctxt(),
+            dpiStmt_getNumQueryColumns(self.handle, &mut num_cols)
+        );
+        let columns = self.define_columns(num_cols)?;
+        Ok(Rows {
+            stmt: self,
+            columns,
+            more_rows: true,
+        })
+    }
+
+    // Gets column metadata and changes native types of NUMBER columns which
+    // cannot be fetched as integers to keep precision.
+    pub(crate) fn define_columns(&mut self, num_cols: u32) -> Result<Arc<[ColumnInfo]>> {
+        let ctxt = self.conn.ctxt();
+        let mut columns = Vec::with_capacity(num_cols as usize);
+        for pos in 1..=num_cols {
+            let mut info = dpiQueryInfo::default();
+            chkerr!(ctxt, dpiStmt_getQueryInfo(self.handle, pos, &mut info));
+            let mut column = unsafe { ColumnInfo::from_dpi(&info) };
+            if column.oracle_type == DPI_ORACLE_TYPE_NUMBER
+                && column.native_type == DPI_NATIVE_TYPE_DOUBLE
+            {
+                chkerr!(
+                    ctxt,
+                    dpiStmt_defineValue(
+                        self.handle,
+                        pos,
+                        DPI_ORACLE_TYPE_NUMBER,
+                        DPI_NATIVE_TYPE_BYTES,
+                        0,
+                        0,
+                        ptr::null_mut()
+                    )
+                );
+                column.native_type = DPI_NATIVE_TYPE_BYTES;
+            }
+            columns.push(column);
+        }
+        Ok(columns.into())
+    }
+
+    // Reads values of the current row.
+    pub(crate) fn read_row(&self, columns: &Arc<[ColumnInfo]>) -> Result<Row> {
+        let ctxt = self.conn.ctxt();
+        let mut values = Vec::with_capacity(columns.len());
+        for (pos, column) in (1..).zip(columns.iter()) {
+            let mut native_type = 0;
+            let mut data = ptr::null_mut();
+            chkerr!(
+                ctxt,
+                dpiStmt_getQueryValue(self.handle, pos, &mut native_type, &mut data)
+            );
+            values.push(unsafe { SqlValue::from_dpi(column.oracle_type, native_type, &*data)? });
+        }
+        Ok(Row {
+            columns: columns.clone(),
+            values,
+        })
+    }
+
+    /// Returns the number of rows affected by DML statements or the number
+    /// of rows fetched so far by queries.
+    pub fn row_count(&self) -> Result<u64> {
+        let mut count = 0;
+        chkerr!(
+            self.conn.ctxt(),
+            dpiStmt_getRowCount(self.handle, &mut count)
+        );
+        Ok(count)
+    }
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        unsafe { dpiStmt_release(self.handle) };
+    }
+}
+
+/// Iterator of rows returned by [`Statement::query`]
+///
+/// ODPI-C fetches rows from the database in batches of
+/// [`Statement::fetch_array_size`] rows. Each call of `next` takes a row from
+/// the batch.
+#[derive(Debug)]
+pub struct Rows<'a> {
+    stmt: &'a Statement<'a>,
+    columns: Arc<[ColumnInfo]>,
+    more_rows: bool,
+}
+
+impl Rows<'_> {
+    pub fn column_info(&self) -> &[ColumnInfo] {
+        &self.columns
+    }
+
+    fn fetch(&mut self) -> Result<Option<Row>> {
+        let ctxt = self.stmt.conn.ctxt();
+        let handle = self.stmt.handle;
+        let mut buffer_row_index = 0;
+        let mut num_rows = 0;
+        let mut more_rows = 0;
+        chkerr!(
+            ctxt,
+            dpiStmt_fetchRows(
+                handle,
+                1,
+                &mut buffer_row_index,
+                &mut num_rows,
+                &mut more_rows
+            )
+        );
+        self.more_rows = more_rows != 0;
+        if num_rows == 0 {
+            return Ok(None);
+        }
+        self.stmt.read_row(&self.columns).map(Some)
+    }
+}
+
+impl Iterator for Rows<'_> {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Result<Row>> {
+        if !self.more_rows {
+            return None;
+        }
+        match self.fetch() {
+            Ok(row) => row.map(Ok),
+            Err(err) => {
+                self.more_rows = false;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Row fetched by [`Rows`]
+#[derive(Clone, Debug)]
+pub struct Row {
+    columns: Arc<[ColumnInfo]>,
+    values: Vec<SqlValue>,
+}
+
+impl Row {
+    pub fn column_info(&self) -> &[ColumnInfo] {
+        &self.columns
+    }
+
+    pub fn values(&self) -> &[SqlValue] {
+        &self.values
+    }
+
+    /// Gets the value of the column at `index` converted to `T`.
+    ///
+    /// ```no_run
+    /// # fn f(row: &odpic::Row) -> odpic::Result<()> {
+    /// let empno: i32 = row.get(0)?;
+    /// let mgr: Option<i32> = row.get("MGR")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get<I: ColumnIndex, T: FromSql>(&self, index: I) -> Result<T> {
+        T::from_sql(&self.values[index.index(&self.columns)?])
+    }
+
+    pub fn into_values(self) -> Vec<SqlValue> {
+        self.values
+    }
+}
@@ -0,0 +1,768 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Subscriptions to database events
+//!
+//! [`Subscription`] calls a Rust closure with an owned [`SubscrMessage`] for
+//! each notification of continuous query notification (CQN) or Advanced
+//! Queuing (AQ). The connection must be created by
+//! [`Connection::connect_with_events`].
+//!
+//! ```no_run
+//! # use odpic::Connection;
+//! # use odpic::subscr::{Namespace, OpCode, Qos, SubscrCreateParams};
+//! # fn f(conn: &Connection) -> odpic::Result<()> {
+//! let params = SubscrCreateParams::new(Namespace::DbChange)
+//!     .qos(Qos::ROWIDS)
+//!     .operations(OpCode::INSERT | OpCode::UPDATE);
+//! let subscr = conn.subscribe(&params, |msg| {
+//!     for table in &msg.tables {
+//!         println!("{} changed: {:?}", table.name, table.operation);
+//!     }
+//! })?;
+//! let mut stmt = subscr.prepare("select * from emp")?;
+//! stmt.execute()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ffi::*;
+use crate::{Connection, DpiError, Result, Statement};
+use std::ffi::c_void;
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::result;
+use std::slice;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{
+    self, Receiver, RecvError, RecvTimeoutError, SyncSender, TryRecvError, TrySendError,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Namespace of subscriptions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Namespace {
+    /// Advanced Queuing
+    Aq,
+    /// continuous query notification
+    DbChange,
+}
+
+impl Namespace {
+    fn to_dpi(self) -> dpiSubscrNamespace {
+        match self {
+            Namespace::Aq => DPI_SUBSCR_NAMESPACE_AQ,
+            Namespace::DbChange => DPI_SUBSCR_NAMESPACE_DBCHANGE,
+        }
+    }
+}
+
+/// How notifications are sent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// calls the closure. This is the default.
+    Callback,
+    /// sends mails to the recipient name
+    Mail,
+    /// calls the PL/SQL procedure named by the recipient name
+    PlSql,
+    /// posts to the URL of the recipient name
+    Http,
+}
+
+impl Protocol {
+    fn to_dpi(self) -> dpiSubscrProtocol {
+        match self {
+            Protocol::Callback => DPI_SUBSCR_PROTO_CALLBACK,
+            Protocol::Mail => DPI_SUBSCR_PROTO_MAIL,
+            Protocol::PlSql => DPI_SUBSCR_PROTO_PLSQL,
+            Protocol::Http => DPI_SUBSCR_PROTO_HTTP,
+        }
+    }
+}
+
+macro_rules! define_flags {
+    ($(#[$attr:meta])* $name:ident($ty:ty) { $($(#[$cattr:meta])* $cname:ident = $value:expr,)* }) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct $name(pub $ty);
+
+        impl $name {
+            $($(#[$cattr])* pub const $cname: $name = $name($value);)*
+
+            /// Returns true when all flags in `other` are set.
+            pub fn contains(self, other: $name) -> bool {
+                self.0 & other.0 == other.0
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, rhs: $name) -> $name {
+                $name(self.0 | rhs.0)
+            }
+        }
+
+        impl BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: $name) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let names: Vec<_> = [$((stringify!($cname), $value)),*]
+                    .into_iter()
+                    .filter(|(_, value)| *value != 0 && self.0 & value == *value)
+                    .map(|(name, _)| name)
+                    .collect();
+                write!(f, "{}({:#x}: {})", stringify!($name), self.0, names.join(" | "))
+            }
+        }
+    };
+}
+
+define_flags! {
+    /// Quality of service flags of subscriptions
+    Qos(dpiSubscrQOS) {
+        /// Notifications survive database restarts.
+        RELIABLE = DPI_SUBSCR_QOS_RELIABLE,
+        /// The subscription is unregistered after the first notification.
+        DEREG_NFY = DPI_SUBSCR_QOS_DEREG_NFY,
+        /// Notifications include rowids of changed rows.
+        ROWIDS = DPI_SUBSCR_QOS_ROWIDS,
+        /// Notifications are sent only when results of registered queries
+        /// change.
+        QUERY = DPI_SUBSCR_QOS_QUERY,
+        /// Notifications may be sent for changes which don't change
+        /// results.
+        BEST_EFFORT = DPI_SUBSCR_QOS_BEST_EFFORT,
+    }
+}
+
+define_flags! {
+    /// Operations of database changes
+    OpCode(dpiOpCode) {
+        /// all operations when used in [`SubscrCreateParams::operations`]
+        ALL_OPS = DPI_OPCODE_ALL_OPS,
+        /// All rows changed, so rowids aren't included.
+        ALL_ROWS = DPI_OPCODE_ALL_ROWS,
+        INSERT = DPI_OPCODE_INSERT,
+        UPDATE = DPI_OPCODE_UPDATE,
+        DELETE = DPI_OPCODE_DELETE,
+        ALTER = DPI_OPCODE_ALTER,
+        DROP = DPI_OPCODE_DROP,
+        UNKNOWN = DPI_OPCODE_UNKNOWN,
+    }
+}
+
+/// How grouped notifications are sent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupingType {
+    /// a summary of the notifications in the group
+    Summary,
+    /// the last notification in the group
+    Last,
+}
+
+impl GroupingType {
+    fn to_dpi(self) -> u8 {
+        match self {
+            GroupingType::Summary => DPI_SUBSCR_GROUPING_TYPE_SUMMARY,
+            GroupingType::Last => DPI_SUBSCR_GROUPING_TYPE_LAST,
+        }
+    }
+}
+
+/// Parameters of [`Connection::subscribe`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubscrCreateParams {
+    namespace: Namespace,
+    protocol: Protocol,
+    qos: Qos,
+    operations: OpCode,
+    port_number: u32,
+    timeout: u32,
+    name: String,
+    recipient_name: String,
+    ip_address: String,
+    grouping: Option<(u32, GroupingType)>,
+    client_initiated: bool,
+}
+
+impl SubscrCreateParams {
+    pub fn new(namespace: Namespace) -> SubscrCreateParams {
+        SubscrCreateParams {
+            namespace,
+            protocol: Protocol::Callback,
+            qos: Qos::default(),
+            operations: OpCode::ALL_OPS,
+            port_number: 0,
+            timeout: 0,
+            name: String::new(),
+            recipient_name: String::new(),
+            ip_address: String::new(),
+            grouping: None,
+            client_initiated: false,
+        }
+    }
+
+    pub fn namespace(mut self, namespace: Namespace) -> SubscrCreateParams {
+        self.namespace = namespace;
+        self
+    }
+
+    pub fn protocol(mut self, protocol: Protocol) -> SubscrCreateParams {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn qos(mut self, qos: Qos) -> SubscrCreateParams {
+        self.qos = qos;
+        self
+    }
+
+    /// Adds `qos` to the current flags.
+    pub fn add_qos(mut self, qos: Qos) -> SubscrCreateParams {
+        self.qos |= qos;
+        self
+    }
+
+    /// Sets operations to be notified of. The default is
+    /// [`OpCode::ALL_OPS`].
+    pub fn operations(mut self, operations: OpCode) -> SubscrCreateParams {
+        self.operations = operations;
+        self
+    }
+
+    /// Sets the port on which the client listens for notifications. The
+    /// default is 0, which means a random port.
+    pub fn port_number(mut self, port: u32) -> SubscrCreateParams {
+        self.port_number = port;
+        self
+    }
+
+    /// Sets the number of seconds after which the subscription is
+    /// unregistered automatically. The default is 0, which means never.
+    pub fn timeout(mut self, seconds: u32) -> SubscrCreateParams {
+        self.timeout = seconds;
+        self
+    }
+
+    /// Sets the name of the subscription. For [`Namespace::Aq`], this is
+    /// the queue name, followed by `:CONSUMER_NAME` for multi-consumer
+    /// queues.
+    pub fn name(mut self, name: &str) -> SubscrCreateParams {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the recipient of [`Protocol::Mail`], [`Protocol::PlSql`] and
+    /// [`Protocol::Http`].
+    pub fn recipient_name(mut self, name: &str) -> SubscrCreateParams {
+        self.recipient_name = name.into();
+        self
+    }
+
+    /// Sets the IP address on which the client listens for notifications.
+    pub fn ip_address(mut self, address: &str) -> SubscrCreateParams {
+        self.ip_address = address.into();
+        self
+    }
+
+    /// Groups notifications sent within `seconds`.
+    pub fn grouping(mut self, seconds: u32, grouping_type: GroupingType) -> SubscrCreateParams {
+        self.grouping = Some((seconds, grouping_type));
+        self
+    }
+
+    /// Receives notifications over the connection initiated by the client
+    /// instead of a connection from the database, which firewalls may
+    /// block.
+    pub fn client_initiated(mut self, enable: bool) -> SubscrCreateParams {
+        self.client_initiated = enable;
+        self
+    }
+}
+
+/// Type of events
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventType {
+    None,
+    Startup,
+    Shutdown,
+    ShutdownAny,
+    /// The subscription was unregistered.
+    Dereg,
+    /// Objects, such as tables, changed.
+    ObjChange,
+    /// Results of registered queries changed.
+    QueryChange,
+    /// Messages are available in a queue.
+    Aq,
+    /// event type unknown to this crate
+    Unknown(dpiEventType),
+}
+
+impl EventType {
+    fn from_dpi(event_type: dpiEventType) -> EventType {
+        match event_type {
+            DPI_EVENT_NONE => EventType::None,
+            DPI_EVENT_STARTUP => EventType::Startup,
+            DPI_EVENT_SHUTDOWN => EventType::Shutdown,
+            DPI_EVENT_SHUTDOWN_ANY => EventType::ShutdownAny,
+            DPI_EVENT_DEREG => EventType::Dereg,
+            DPI_EVENT_OBJCHANGE => EventType::ObjChange,
+            DPI_EVENT_QUERYCHANGE => EventType::QueryChange,
+            DPI_EVENT_AQ => EventType::Aq,
+            _ => EventType::Unknown(event_type),
+        }
+    }
+}
+
+/// Changed table in [`SubscrMessage`]
+///
+/// This is an owned counterpart of [`dpiSubscrMessageTable`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageTable {
+    pub operation: OpCode,
+    /// `SCHEMA.TABLE_NAME`
+    pub name: String,
+    /// changed rows when [`Qos::ROWIDS`] is set
+    pub rows: Vec<MessageRow>,
+}
+
+/// Changed row in [`MessageTable`]
+///
+/// This is an owned counterpart of [`dpiSubscrMessageRow`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageRow {
+    pub operation: OpCode,
+    pub rowid: String,
+}
+
+/// Query whose result changed in [`SubscrMessage`]
+///
+/// This is an owned counterpart of [`dpiSubscrMessageQuery`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageQuery {
+    /// query id got by [`Statement::subscr_query_id`]
+    pub id: u64,
+    pub operation: OpCode,
+    pub tables: Vec<MessageTable>,
+}
+
+/// Notification passed to subscription callbacks
+///
+/// This is an owned counterpart of [`dpiSubscrMessage`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubscrMessage {
+    pub event_type: EventType,
+    pub db_name: String,
+    /// changed tables of [`EventType::ObjChange`]
+    pub tables: Vec<MessageTable>,
+    /// queries of [`EventType::QueryChange`]
+    pub queries: Vec<MessageQuery>,
+    /// error which occurred while the notification was created
+    pub error: Option<DpiError>,
+    /// id of the transaction which made changes
+    pub tx_id: Vec<u8>,
+    /// false when the subscription was unregistered by this notification
+    pub registered: bool,
+    /// queue name of [`EventType::Aq`]
+    pub queue_name: String,
+    /// consumer name of [`EventType::Aq`]
+    pub consumer_name: String,
+    /// message id of [`EventType::Aq`]
+    pub msg_id: Vec<u8>,
+}
+
+impl SubscrMessage {
+    /// Creates an owned message from a message passed by ODPI-C.
+    ///
+    /// # Safety
+    ///
+    /// Pointers in `msg` must be valid, as they are in callbacks.
+    pub unsafe fn from_dpi(msg: &dpiSubscrMessage) -> SubscrMessage {
+        SubscrMessage {
+            event_type: EventType::from_dpi(msg.eventType),
+            db_name: to_string(msg.dbName, msg.dbNameLength),
+            tables: to_tables(msg.tables, msg.numTables),
+            queries: raw_slice(msg.queries, msg.numQueries)
+                .iter()
+                .map(|query| MessageQuery {
+                    id: query.id,
+                    operation: OpCode(query.operation),
+                    tables: to_tables(query.tables, query.numTables),
+                })
+                .collect(),
+            error: msg.errorInfo.as_ref().map(|info| DpiError::from_dpi(info)),
+            tx_id: raw_slice(msg.txId as *const u8, msg.txIdLength).to_vec(),
+            registered: msg.registered != 0,
+            queue_name: to_string(msg.queueName, msg.queueNameLength),
+            consumer_name: to_string(msg.consumerName, msg.consumerNameLength),
+            msg_id: raw_slice(msg.aqMsgId as *const u8, msg.aqMsgIdLength).to_vec(),
+        }
+    }
+}
+
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: u32) -> &'a [T] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len as usize)
+    }
+}
+
+unsafe fn to_string(ptr: *const c_char, len: u32) -> String {
+    String::from_utf8_lossy(raw_slice(ptr as *const u8, len)).into_owned()
+}
+
+unsafe fn to_tables(ptr: *const dpiSubscrMessageTable, len: u32) -> Vec<MessageTable> {
+    raw_slice(ptr, len)
+        .iter()
+        .map(|table| MessageTable {
+            operation: OpCode(table.operation),
+            name: to_string(table.name, table.nameLength),
+            rows: raw_slice(table.rows, table.numRows)
+                .iter()
+                .map(|row| MessageRow {
+                    operation: OpCode(row.operation),
+                    rowid: to_string(row.rowid, row.rowidLength),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+type Callback = Box<dyn Fn(SubscrMessage) + Send + Sync + 'static>;
+
+// Called by ODPI-C in its notification threads. Panics must not unwind into
+// C, so they are caught and discarded.
+unsafe extern "C" fn trampoline(context: *mut c_void, message: *mut dpiSubscrMessage) {
+    if context.is_null() || message.is_null() {
+        return;
+    }
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let callback = &*(context as *const Callback);
+        callback(SubscrMessage::from_dpi(&*message));
+    }));
+}
+
+/// Subscription to database events
+///
+/// This holds a reference to [`dpiSubscr`] and the callback closure. The
+/// subscription is unregistered and released when it is dropped. The
+/// closure is dropped after that, as it may be called until then.
+pub struct Subscription<'conn> {
+    conn: &'conn Connection,
+    handle: *mut dpiSubscr,
+    reg_id: u64,
+    callback: Option<Box<Callback>>,
+}
+
+impl<'conn> Subscription<'conn> {
+    fn new<F>(
+        conn: &'conn Connection,
+        params: &SubscrCreateParams,
+        callback: F,
+    ) -> Result<Subscription<'conn>>
+    where
+        F: Fn(SubscrMessage) + Send + Sync + 'static,
+    {
+        let ctxt = conn.ctxt();
+        let callback: Box<Callback> = Box::new(Box::new(callback));
+        let mut dpi_params = dpiSubscrCreateParams::default();
+        chkerr!(
+            ctxt,
+            dpiContext_initSubscrCreateParams(ctxt.handle(), &mut dpi_params)
+        );
+        dpi_params.subscrNamespace = params.namespace.to_dpi();
+        dpi_params.protocol = params.protocol.to_dpi();
+        dpi_params.qos = params.qos.0;
+        dpi_params.operations = params.operations.0;
+        dpi_params.portNumber = params.port_number;
+        dpi_params.timeout = params.timeout;
+        dpi_params.name = params.name.as_ptr() as *const c_char;
+        dpi_params.nameLength = params.name.len() as u32;
+        if params.protocol == Protocol::Callback {
+            dpi_params.callback = Some(trampoline);
+            dpi_params.callbackContext = &*callback as *const Callback as *mut c_void;
+        }
+        dpi_params.recipientName = params.recipient_name.as_ptr() as *const c_char;
+        dpi_params.recipientNameLength = params.recipient_name.len() as u32;
+        dpi_params.ipAddress = params.ip_address.as_ptr() as *const c_char;
+        dpi_params.ipAddressLength = params.ip_address.len() as u32;
+        if let Some((seconds, grouping_type)) = params.grouping {
+            dpi_params.groupingClass = DPI_SUBSCR_GROUPING_CLASS_TIME;
+            dpi_params.groupingValue = seconds;
+            dpi_params.groupingType = grouping_type.to_dpi();
+        }
+        dpi_params.clientInitiated = params.client_initiated.into();
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiConn_subscribe(conn.handle(), &mut dpi_params, &mut handle)
+        );
+        Ok(Subscription {
+            conn,
+            handle,
+            reg_id: dpi_params.outRegId,
+            callback: Some(callback),
+        })
+    }
+
+    pub fn handle(&self) -> *mut dpiSubscr {
+        self.handle
+    }
+
+    /// Returns the registration id, which is `REGID` of
+    /// `USER_CHANGE_NOTIFICATION_REGS`.
+    pub fn reg_id(&self) -> u64 {
+        self.reg_id
+    }
+
+    /// Prepares a query registered to the subscription when it is
+    /// executed. The subscription must be in [`Namespace::DbChange`].
+    pub fn prepare(&self, sql: &str) -> Result<Statement<'conn>> {
+        let mut handle = ptr::null_mut();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiSubscr_prepareStmt(
+                self.handle,
+                sql.as_ptr() as *const c_char,
+                sql.len() as u32,
+                &mut handle
+            )
+        );
+        unsafe { Statement::from_prepared(self.conn, handle) }
+    }
+}
+
+impl Drop for Subscription<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            if dpiConn_unsubscribe(self.conn.handle(), self.handle) != DPI_SUCCESS {
+                // The callback may still be called, so it must not be freed.
+                if let Some(callback) = self.callback.take() {
+                    Box::leak(callback);
+                }
+            }
+            dpiSubscr_release(self.handle);
+        }
+    }
+}
+
+impl fmt::Debug for Subscription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("handle", &self.handle)
+            .field("reg_id", &self.reg_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Connection {
+    /// Subscribes to events and calls `callback` for each notification.
+    ///
+    /// `callback` is called in threads of ODPI-C. Panics in it are caught
+    /// and discarded.
+    pub fn subscribe<F>(&self, params: &SubscrCreateParams, callback: F) -> Result<Subscription<'_>>
+    where
+        F: Fn(SubscrMessage) + Send + Sync + 'static,
+    {
+        Subscription::new(self, params, callback)
+    }
+}
+
+impl Statement<'_> {
+    /// Returns the query id of the query registered to a subscription,
+    /// which is [`MessageQuery::id`] of notifications.
+    pub fn subscr_query_id(&self) -> Result<u64> {
+        let mut id = 0;
+        chkerr!(
+            self.connection().ctxt(),
+            dpiStmt_getSubscrQueryId(self.handle(), &mut id)
+        );
+        Ok(id)
+    }
+}
+
+/// Notification of messages available in a queue
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AqNotification {
+    pub queue_name: String,
+    pub consumer_name: String,
+    pub msg_id: Vec<u8>,
+}
+
+// State shared with the callback
+#[derive(Debug, Default)]
+struct AqShared {
+    sender: Mutex<Option<SyncSender<AqNotification>>>,
+    dropped: AtomicU64,
+    #[cfg(feature = "futures")]
+    waker: Mutex<Option<std::task::Waker>>,
+}
+
+impl AqShared {
+    fn notify(&self, msg: SubscrMessage) {
+        let mut sender = self.sender.lock().unwrap_or_else(|err| err.into_inner());
+        if msg.event_type == EventType::Aq {
+            if let Some(tx) = &*sender {
+                let notification = AqNotification {
+                    queue_name: msg.queue_name,
+                    consumer_name: msg.consumer_name,
+                    msg_id: msg.msg_id,
+                };
+                if let Err(TrySendError::Full(_)) = tx.try_send(notification) {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        if msg.event_type == EventType::Dereg || !msg.registered {
+            // Receivers see disconnection after buffered notifications.
+            *sender = None;
+        }
+        drop(sender);
+        #[cfg(feature = "futures")]
+        if let Some(waker) = self.waker.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Receiver of notifications of an AQ subscription
+///
+/// Notifications are buffered in a bounded channel. When it is full,
+/// new notifications are dropped and counted by
+/// [`AqReceiver::dropped_count`] instead of blocking threads of ODPI-C or
+/// growing memory. A notification tells only that messages may be
+/// available, so receivers should dequeue until the queue is empty rather
+/// than once per notification. The receiver is disconnected when the
+/// subscription is unregistered.
+///
+/// With the `futures` feature, this is also a [`Stream`] of notifications.
+///
+/// ```no_run
+/// # use odpic::Connection;
+/// # use odpic::aq::{DeqOptions, Wait};
+/// # use odpic::subscr::{AqReceiver, Namespace, SubscrCreateParams};
+/// # fn f(conn: &Connection) -> odpic::Result<()> {
+/// let queue = conn.queue("RAW_QUEUE")?;
+/// queue.set_deq_options(&DeqOptions::new().wait(Wait::NoWait))?;
+/// let receiver = AqReceiver::new(conn, SubscrCreateParams::new(Namespace::Aq).name("RAW_QUEUE"), 16)?;
+/// for _ in receiver.iter() {
+///     while let Some(msg) = queue.dequeue()? {
+///         println!("{:?}", msg.payload);
+///     }
+///     conn.commit()?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Stream`]: https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html
+#[derive(Debug)]
+pub struct AqReceiver<'conn> {
+    subscr: Subscription<'conn>,
+    receiver: Receiver<AqNotification>,
+    shared: Arc<AqShared>,
+}
+
+impl<'conn> AqReceiver<'conn> {
+    /// Subscribes with `params`, whose namespace is overwritten with
+    /// [`Namespace::Aq`], and buffers up to `capacity` notifications.
+    pub fn new(
+        conn: &'conn Connection,
+        params: SubscrCreateParams,
+        capacity: usize,
+    ) -> Result<AqReceiver<'conn>> {
+        let (sender, receiver) = mpsc::sync_channel(capacity.max(1));
+        let shared = Arc::new(AqShared {
+            sender: Mutex::new(Some(sender)),
+            ..AqShared::default()
+        });
+        let params = params.namespace(Namespace::Aq).protocol(Protocol::Callback);
+        let callback_shared = shared.clone();
+        let subscr = conn.subscribe(&params, move |msg| callback_shared.notify(msg))?;
+        Ok(AqReceiver {
+            subscr,
+            receiver,
+            shared,
+        })
+    }
+
+    pub fn subscription(&self) -> &Subscription<'conn> {
+        &self.subscr
+    }
+
+    /// Waits for a notification. It returns an error when the subscription
+    /// was unregistered.
+    pub fn recv(&self) -> result::Result<AqNotification, RecvError> {
+        self.receiver.recv()
+    }
+
+    pub fn recv_timeout(
+        &self,
+        timeout: Duration,
+    ) -> result::Result<AqNotification, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    pub fn try_recv(&self) -> result::Result<AqNotification, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// Returns an iterator waiting for notifications until the subscription
+    /// is unregistered.
+    pub fn iter(&self) -> mpsc::Iter<'_, AqNotification> {
+        self.receiver.iter()
+    }
+
+    /// Returns the number of notifications dropped because the channel was
+    /// full.
+    pub fn dropped_count(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for AqReceiver<'_> {
+    type Item = AqNotification;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<AqNotification>> {
+        use std::task::Poll;
+        let poll = |receiver: &Receiver<AqNotification>| match receiver.try_recv() {
+            Ok(notification) => Some(Poll::Ready(Some(notification))),
+            Err(TryRecvError::Disconnected) => Some(Poll::Ready(None)),
+            Err(TryRecvError::Empty) => None,
+        };
+        if let Some(poll) = poll(&self.receiver) {
+            return poll;
+        }
+        *self.shared.waker.lock().unwrap_or_else(|e| e.into_inner()) = Some(cx.waker().clone());
+        // A notification may have arrived before the waker was set.
+        poll(&self.receiver).unwrap_or(Poll::Pending)
+    }
+}
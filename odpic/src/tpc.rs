@@ -0,0 +1,424 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Two-phase commit (TPC) with XA transaction ids
+//!
+//! [`TpcBranch`] drives a transaction branch through
+//! begin, end, prepare and commit or rollback, rejecting calls in illegal
+//! orders before they reach the database.
+//!
+//! ```no_run
+//! # use odpic::Connection;
+//! # use odpic::tpc::{BeginMode, PrepareOutcome, TpcBranch, Xid};
+//! # fn f(conn: &Connection) -> odpic::Result<()> {
+//! let xid = Xid::new(0x1234, b"global-tx-1", b"branch-1")?;
+//! let mut branch = TpcBranch::begin(conn, xid, 60, BeginMode::New)?;
+//! conn.prepare("update emp set sal = sal * 1.1")?.execute()?;
+//! branch.end()?;
+//! if branch.prepare()? == PrepareOutcome::CommitNeeded {
+//!     branch.commit()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ffi::*;
+use crate::{Connection, Error, Result};
+use std::fmt;
+use std::os::raw::{c_char, c_long};
+
+/// Maximum length of global transaction ids and branch qualifiers
+pub const XA_MAX_ID_SIZE: usize = 64;
+
+/// XA transaction id
+///
+/// This is an owned counterpart of [`dpiXid`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Xid {
+    format_id: i32,
+    global_transaction_id: Vec<u8>,
+    branch_qualifier: Vec<u8>,
+}
+
+impl Xid {
+    /// Creates a transaction id. The format id must not be -1, which means
+    /// the null XID. The global transaction id must be 1 to 64 bytes and
+    /// the branch qualifier up to 64 bytes.
+    pub fn new<G, B>(format_id: i32, global_transaction_id: G, branch_qualifier: B) -> Result<Xid>
+    where
+        G: Into<Vec<u8>>,
+        B: Into<Vec<u8>>,
+    {
+        let global_transaction_id = global_transaction_id.into();
+        let branch_qualifier = branch_qualifier.into();
+        if format_id == -1 {
+            return Err(Error::InvalidXid(
+                "format id -1 is reserved for the null XID".into(),
+            ));
+        }
+        if global_transaction_id.is_empty() || global_transaction_id.len() > XA_MAX_ID_SIZE {
+            return Err(Error::InvalidXid(format!(
+                "global transaction id must be 1 to {} bytes but is {} bytes",
+                XA_MAX_ID_SIZE,
+                global_transaction_id.len()
+            )));
+        }
+        if branch_qualifier.len() > XA_MAX_ID_SIZE {
+            return Err(Error::InvalidXid(format!(
+                "branch qualifier must be up to {} bytes but is {} bytes",
+                XA_MAX_ID_SIZE,
+                branch_qualifier.len()
+            )));
+        }
+        Ok(Xid {
+            format_id,
+            global_transaction_id,
+            branch_qualifier,
+        })
+    }
+
+    pub fn format_id(&self) -> i32 {
+        self.format_id
+    }
+
+    pub fn global_transaction_id(&self) -> &[u8] {
+        &self.global_transaction_id
+    }
+
+    pub fn branch_qualifier(&self) -> &[u8] {
+        &self.branch_qualifier
+    }
+
+    /// Returns [`dpiXid`] pointing to ids in `self`, which is valid while
+    /// `self` is borrowed.
+    pub fn to_dpi(&self) -> dpiXid {
+        dpiXid {
+            formatId: self.format_id as c_long,
+            globalTransactionId: self.global_transaction_id.as_ptr() as *const c_char,
+            globalTransactionIdLength: self.global_transaction_id.len() as u32,
+            branchQualifier: self.branch_qualifier.as_ptr() as *const c_char,
+            branchQualifierLength: self.branch_qualifier.len() as u32,
+        }
+    }
+}
+
+/// Formats as `FORMAT_ID.GTRID.BQUAL` with ids in hexadecimal.
+impl fmt::Display for Xid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.", self.format_id)?;
+        for b in &self.global_transaction_id {
+            write!(f, "{:02X}", b)?;
+        }
+        f.write_str(".")?;
+        for b in &self.branch_qualifier {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// How [`TpcBranch::begin`] starts a branch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BeginMode {
+    /// starts a new transaction branch
+    New,
+    /// joins an existing branch started on another connection
+    Join,
+    /// promotes the local transaction to a branch
+    Promote,
+}
+
+impl BeginMode {
+    fn to_dpi(self) -> u32 {
+        match self {
+            BeginMode::New => DPI_TPC_BEGIN_NEW,
+            BeginMode::Join => DPI_TPC_BEGIN_JOIN,
+            BeginMode::Promote => DPI_TPC_BEGIN_PROMOTE,
+        }
+    }
+}
+
+/// Result of [`TpcBranch::prepare`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrepareOutcome {
+    /// The branch made changes and must be committed or rolled back.
+    CommitNeeded,
+    /// The branch made no changes and is complete.
+    ReadOnly,
+}
+
+/// State of [`TpcBranch`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchState {
+    /// associated with the connection
+    Active,
+    /// detached by [`TpcBranch::suspend`]
+    Suspended,
+    /// detached by [`TpcBranch::end`]
+    Ended,
+    /// prepared and waiting for commit or rollback
+    Prepared,
+    /// complete because prepare found no changes
+    ReadOnly,
+    Committed,
+    RolledBack,
+    /// forgotten after a heuristic completion
+    Forgotten,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Suspend,
+    Resume,
+    End,
+    Prepare,
+    Commit,
+    CommitOnePhase,
+    Rollback,
+    Forget,
+}
+
+impl BranchState {
+    // Returns an error when `op` is illegal in this state.
+    fn check(self, op: Op) -> Result<()> {
+        use BranchState::*;
+        let legal = match op {
+            Op::Suspend | Op::End => self == Active,
+            Op::Resume => self == Suspended,
+            Op::Prepare | Op::CommitOnePhase => matches!(self, Active | Ended),
+            Op::Commit | Op::Forget => self == Prepared,
+            Op::Rollback => matches!(self, Active | Suspended | Ended | Prepared),
+        };
+        if legal {
+            Ok(())
+        } else {
+            Err(Error::InvalidOperation(format!(
+                "{:?} is illegal for a transaction branch in the {:?} state",
+                op, self
+            )))
+        }
+    }
+}
+
+/// Transaction branch of two-phase commit
+///
+/// Each method checks the current [`BranchState`] and returns
+/// [`Error::InvalidOperation`] for illegal calls. The state changes only
+/// when the database call succeeds. Dropping a branch doesn't end or roll
+/// it back, so prepared branches are left for recovery.
+#[derive(Debug)]
+pub struct TpcBranch<'conn> {
+    conn: &'conn Connection,
+    xid: Xid,
+    state: BranchState,
+}
+
+impl<'conn> TpcBranch<'conn> {
+    /// Begins a branch with `timeout` seconds for which it may be
+    /// inactive.
+    pub fn begin(
+        conn: &'conn Connection,
+        xid: Xid,
+        timeout: u32,
+        mode: BeginMode,
+    ) -> Result<TpcBranch<'conn>> {
+        let mut dpi_xid = xid.to_dpi();
+        chkerr!(
+            conn.ctxt(),
+            dpiConn_tpcBegin(conn.handle(), &mut dpi_xid, timeout, mode.to_dpi())
+        );
+        Ok(TpcBranch {
+            conn,
+            xid,
+            state: BranchState::Active,
+        })
+    }
+
+    /// Creates a branch in the [`BranchState::Prepared`] state to commit or
+    /// roll back a branch prepared on another connection, such as after a
+    /// coordinator restarts.
+    pub fn recover(conn: &'conn Connection, xid: Xid) -> TpcBranch<'conn> {
+        TpcBranch {
+            conn,
+            xid,
+            state: BranchState::Prepared,
+        }
+    }
+
+    pub fn xid(&self) -> &Xid {
+        &self.xid
+    }
+
+    pub fn state(&self) -> BranchState {
+        self.state
+    }
+
+    /// Detaches the branch from the connection to resume it later.
+    pub fn suspend(&mut self) -> Result<()> {
+        self.state.check(Op::Suspend)?;
+        self.end_with(DPI_TPC_END_SUSPEND)?;
+        self.state = BranchState::Suspended;
+        Ok(())
+    }
+
+    /// Attaches the suspended branch to the connection again.
+    pub fn resume(&mut self, timeout: u32) -> Result<()> {
+        self.state.check(Op::Resume)?;
+        let mut xid = self.xid.to_dpi();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiConn_tpcBegin(self.conn.handle(), &mut xid, timeout, DPI_TPC_BEGIN_RESUME)
+        );
+        self.state = BranchState::Active;
+        Ok(())
+    }
+
+    /// Ends work on the branch.
+    pub fn end(&mut self) -> Result<()> {
+        self.state.check(Op::End)?;
+        self.end_with(DPI_TPC_END_NORMAL)?;
+        self.state = BranchState::Ended;
+        Ok(())
+    }
+
+    fn end_with(&self, flags: u32) -> Result<()> {
+        let mut xid = self.xid.to_dpi();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiConn_tpcEnd(self.conn.handle(), &mut xid, flags)
+        );
+        Ok(())
+    }
+
+    /// Prepares the branch, the first phase of two-phase commit.
+    pub fn prepare(&mut self) -> Result<PrepareOutcome> {
+        self.state.check(Op::Prepare)?;
+        let mut xid = self.xid.to_dpi();
+        let mut commit_needed = 0;
+        chkerr!(
+            self.conn.ctxt(),
+            dpiConn_tpcPrepare(self.conn.handle(), &mut xid, &mut commit_needed)
+        );
+        if commit_needed != 0 {
+            self.state = BranchState::Prepared;
+            Ok(PrepareOutcome::CommitNeeded)
+        } else {
+            self.state = BranchState::ReadOnly;
+            Ok(PrepareOutcome::ReadOnly)
+        }
+    }
+
+    /// Commits the prepared branch, the second phase of two-phase commit.
+    pub fn commit(&mut self) -> Result<()> {
+        self.state.check(Op::Commit)?;
+        self.commit_with(false)
+    }
+
+    /// Commits the branch without preparing it, when it is the only
+    /// resource in the transaction.
+    pub fn commit_one_phase(&mut self) -> Result<()> {
+        self.state.check(Op::CommitOnePhase)?;
+        self.commit_with(true)
+    }
+
+    fn commit_with(&mut self, one_phase: bool) -> Result<()> {
+        let mut xid = self.xid.to_dpi();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiConn_tpcCommit(self.conn.handle(), &mut xid, one_phase.into())
+        );
+        self.state = BranchState::Committed;
+        Ok(())
+    }
+
+    pub fn rollback(&mut self) -> Result<()> {
+        self.state.check(Op::Rollback)?;
+        let mut xid = self.xid.to_dpi();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiConn_tpcRollback(self.conn.handle(), &mut xid)
+        );
+        self.state = BranchState::RolledBack;
+        Ok(())
+    }
+
+    /// Forgets the prepared branch which the database completed
+    /// heuristically.
+    pub fn forget(&mut self) -> Result<()> {
+        self.state.check(Op::Forget)?;
+        let mut xid = self.xid.to_dpi();
+        chkerr!(
+            self.conn.ctxt(),
+            dpiConn_tpcForget(self.conn.handle(), &mut xid)
+        );
+        self.state = BranchState::Forgotten;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legal_operations() {
+        use BranchState::*;
+        let ops = [
+            Op::Suspend,
+            Op::Resume,
+            Op::End,
+            Op::Prepare,
+            Op::Commit,
+            Op::CommitOnePhase,
+            Op::Rollback,
+            Op::Forget,
+        ];
+        // whether each of `ops` is legal
+        let table = [
+            (Active, [true, false, true, true, false, true, true, false]),
+            (
+                Suspended,
+                [false, true, false, false, false, false, true, false],
+            ),
+            (Ended, [false, false, false, true, false, true, true, false]),
+            (
+                Prepared,
+                [false, false, false, false, true, false, true, true],
+            ),
+            (ReadOnly, [false; 8]),
+            (Committed, [false; 8]),
+            (RolledBack, [false; 8]),
+            (Forgotten, [false; 8]),
+        ];
+        for (state, legal) in table {
+            for (op, legal) in ops.into_iter().zip(legal) {
+                match state.check(op) {
+                    Ok(()) => assert!(legal, "{:?} in {:?}", op, state),
+                    Err(Error::InvalidOperation(msg)) => {
+                        assert!(!legal, "{:?} in {:?}: {}", op, state, msg);
+                        assert_eq!(
+                            msg,
+                            format!(
+                                "{:?} is illegal for a transaction branch in the {:?} state",
+                                op, state
+                            )
+                        );
+                    }
+                    Err(err) => panic!("{:?} in {:?}: {}", op, state, err),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::lob::Lob;
+use crate::{Context, Error, IntervalDS, IntervalYM, JsonValue, Result, Timestamp, Vector};
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+/// Owned value of a column or a bind variable
+///
+/// The variant is chosen by the native type which the value is fetched as.
+/// `NUMBER` values which don't fit in 64-bit integers are fetched as
+/// [`SqlValue::Number`] to keep precision.
+#[derive(Clone, Debug)]
+pub enum SqlValue {
+    Null,
+    Boolean(bool),
+    Int64(i64),
+    Uint64(u64),
+    Float(f32),
+    Double(f64),
+    /// `NUMBER` in decimal text
+    Number(String),
+    /// Character types such as `VARCHAR2`, `CHAR` and `LONG`
+    String(String),
+    /// `RAW` and `LONG RAW`
+    Raw(Vec<u8>),
+    /// `DATE`, `TIMESTAMP`, `TIMESTAMP WITH TIME ZONE` and
+    /// `TIMESTAMP WITH LOCAL TIME ZONE`
+    Timestamp(Timestamp),
+    IntervalDS(IntervalDS),
+    IntervalYM(IntervalYM),
+    /// `CLOB`, `NCLOB`, `BLOB` and `BFILE`
+    Lob(Lob),
+    Json(JsonValue),
+    Vector(Vector),
+    /// `ROWID` and `UROWID` in the string representation
+    Rowid(String),
+}
+
+impl SqlValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, SqlValue::Null)
+    }
+
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            SqlValue::Null => "null",
+            SqlValue::Boolean(_) => "boolean",
+            SqlValue::Int64(_) => "i64",
+            SqlValue::Uint64(_) => "u64",
+            SqlValue::Float(_) => "f32",
+            SqlValue::Double(_) => "f64",
+            SqlValue::Number(_) => "number",
+            SqlValue::String(_) => "string",
+            SqlValue::Raw(_) => "raw",
+            SqlValue::Timestamp(_) => "timestamp",
+            SqlValue::IntervalDS(_) => "interval day to second",
+            SqlValue::IntervalYM(_) => "interval year to month",
+            SqlValue::Lob(_) => "LOB",
+            SqlValue::Json(_) => "JSON",
+            SqlValue::Vector(_) => "vector",
+            SqlValue::Rowid(_) => "rowid",
+        }
+    }
+
+    /// Converts a value filled by ODPI-C.
+    ///
+    /// # Safety
+    ///
+    /// `data` must hold a value of `native_type` unless it is null.
+    pub unsafe fn from_dpi(
+        oracle_type: dpiOracleTypeNum,
+        native_type: dpiNativeTypeNum,
+        data: &dpiData,
+    ) -> Result<SqlValue> {
+        if data.isNull != 0 {
+            return Ok(SqlValue::Null);
+        }
+        let value = &data.value;
+        Ok(match native_type {
+            DPI_NATIVE_TYPE_BOOLEAN => SqlValue::Boolean(value.asBoolean != 0),
+            DPI_NATIVE_TYPE_INT64 => SqlValue::Int64(value.asInt64),
+            DPI_NATIVE_TYPE_UINT64 => SqlValue::Uint64(value.asUint64),
+            DPI_NATIVE_TYPE_FLOAT => SqlValue::Float(value.asFloat),
+            DPI_NATIVE_TYPE_DOUBLE => SqlValue::Double(value.asDouble),
+            DPI_NATIVE_TYPE_BYTES => {
+                let bytes = bytes_slice(&value.asBytes);
+                match oracle_type {
+                    DPI_ORACLE_TYPE_RAW | DPI_ORACLE_TYPE_LONG_RAW => SqlValue::Raw(bytes.to_vec()),
+                    DPI_ORACLE_TYPE_NUMBER => {
+                        SqlValue::Number(String::from_utf8_lossy(bytes).into_owned())
+                    }
+                    _ => SqlValue::String(String::from_utf8_lossy(bytes).into_owned()),
+                }
+            }
+            DPI_NATIVE_TYPE_TIMESTAMP => {
+                let ts = Timestamp::from(value.asTimestamp);
+                SqlValue::Timestamp(match oracle_type {
+                    DPI_ORACLE_TYPE_TIMESTAMP_TZ | DPI_ORACLE_TYPE_TIMESTAMP_LTZ => {
+                        ts.and_tz_offset(ts.tz_hour_offset, ts.tz_minute_offset)
+                    }
+                    _ => ts,
+                })
+            }
+            DPI_NATIVE_TYPE_INTERVAL_DS => SqlValue::IntervalDS(value.asIntervalDS.into()),
+            DPI_NATIVE_TYPE_INTERVAL_YM => SqlValue::IntervalYM(value.asIntervalYM.into()),
+            DPI_NATIVE_TYPE_LOB => SqlValue::Lob(Lob::from_raw(value.asLOB)?),
+            DPI_NATIVE_TYPE_JSON => {
+                let mut node = ptr::null_mut();
+                chkerr!(
+                    Context::get()?,
+                    dpiJson_getValue(value.asJson, DPI_JSON_OPT_NUMBER_AS_STRING, &mut node)
+                );
+                SqlValue::Json(JsonValue::from_dpi_node(&*node)?)
+            }
+            DPI_NATIVE_TYPE_VECTOR => {
+                let mut info = dpiVectorInfo::default();
+                chkerr!(
+                    Context::get()?,
+                    dpiVector_getValue(value.asVector, &mut info)
+                );
+                SqlValue::Vector(Vector::from_dpi(&info)?)
+            }
+            DPI_NATIVE_TYPE_ROWID => {
+                let mut ptr: *const c_char = ptr::null();
+                let mut len = 0;
+                chkerr!(
+                    Context::get()?,
+                    dpiRowid_getStringValue(value.asRowid, &mut ptr, &mut len)
+                );
+                let bytes = slice::from_raw_parts(ptr as *const u8, len as usize);
+                SqlValue::Rowid(String::from_utf8_lossy(bytes).into_owned())
+            }
+            _ => {
+                return Err(Error::UnsupportedType(format!(
+                    "value of Oracle type {} and native type {}",
+                    oracle_type, native_type
+                )))
+            }
+        })
+    }
+}
+
+pub(crate) unsafe fn bytes_slice<'a>(bytes: &dpiBytes) -> &'a [u8] {
+    if bytes.ptr.is_null() || bytes.length == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(bytes.ptr as *const u8, bytes.length as usize)
+    }
+}
@@ -0,0 +1,160 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use crate::ffi::*;
+use crate::{Connection, Context, Result, SqlData, SqlType, SqlValue, ToSql};
+use std::ptr;
+
+/// Owned reference to [`dpiVar`] which holds an array of values
+#[derive(Debug)]
+pub(crate) struct Var {
+    ctxt: &'static Context,
+    handle: *mut dpiVar,
+    data: *mut dpiData,
+    sql_type: SqlType,
+    max_array_size: u32,
+    is_array: bool,
+}
+
+impl Var {
+    pub(crate) fn new(conn: &Connection, sql_type: SqlType, max_array_size: u32) -> Result<Var> {
+        Var::with_array_flag(conn, sql_type, max_array_size, false)
+    }
+
+    /// Creates a variable bound to a PL/SQL associative array.
+    pub(crate) fn new_array(
+        conn: &Connection,
+        sql_type: SqlType,
+        max_array_size: u32,
+    ) -> Result<Var> {
+        Var::with_array_flag(conn, sql_type, max_array_size, true)
+    }
+
+    fn with_array_flag(
+        conn: &Connection,
+        sql_type: SqlType,
+        max_array_size: u32,
+        is_array: bool,
+    ) -> Result<Var> {
+        let ctxt = conn.ctxt();
+        let mut handle = ptr::null_mut();
+        let mut data = ptr::null_mut();
+        chkerr!(
+            ctxt,
+            dpiConn_newVar(
+                conn.handle(),
+                sql_type.oracle_type,
+                sql_type.native_type,
+                max_array_size,
+                sql_type.size.max(1),
+                1,
+                is_array.into(),
+                ptr::null_mut(),
+                &mut handle,
+                &mut data
+            )
+        );
+        Ok(Var {
+            ctxt,
+            handle,
+            data,
+            sql_type,
+            max_array_size,
+            is_array,
+        })
+    }
+
+    pub(crate) fn handle(&self) -> *mut dpiVar {
+        self.handle
+    }
+
+    pub(crate) fn sql_type(&self) -> &SqlType {
+        &self.sql_type
+    }
+
+    pub(crate) fn max_array_size(&self) -> u32 {
+        self.max_array_size
+    }
+
+    pub(crate) fn is_array(&self) -> bool {
+        self.is_array
+    }
+
+    pub(crate) fn num_elements(&self) -> Result<u32> {
+        let mut num = 0;
+        chkerr!(
+            self.ctxt,
+            dpiVar_getNumElementsInArray(self.handle, &mut num)
+        );
+        Ok(num)
+    }
+
+    pub(crate) fn set_num_elements(&mut self, num: u32) -> Result<()> {
+        chkerr!(self.ctxt, dpiVar_setNumElementsInArray(self.handle, num));
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, pos: u32) -> Result<SqlValue> {
+        unsafe {
+            SqlValue::from_dpi(
+                self.sql_type.oracle_type,
+                self.sql_type.native_type,
+                self.data(pos),
+            )
+        }
+    }
+
+    // Gets values returned by DML RETURNING INTO at the iteration `pos`.
+    pub(crate) fn returned_values(&self, pos: u32) -> Result<Vec<SqlValue>> {
+        let mut num_elements = 0;
+        let mut data = ptr::null_mut();
+        chkerr!(
+            self.ctxt,
+            dpiVar_getReturnedData(self.handle, pos, &mut num_elements, &mut data)
+        );
+        (0..num_elements as usize)
+            .map(|i| unsafe {
+                SqlValue::from_dpi(
+                    self.sql_type.oracle_type,
+                    self.sql_type.native_type,
+                    &*data.add(i),
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn data(&self, pos: u32) -> &dpiData {
+        assert!(pos < self.max_array_size);
+        unsafe { &*self.data.add(pos as usize) }
+    }
+
+    pub(crate) fn set(&mut self, pos: u32, value: &dyn ToSql) -> Result<()> {
+        assert!(pos < self.max_array_size);
+        let mut data = SqlData {
+            ctxt: self.ctxt,
+            var: self.handle,
+            data: unsafe { self.data.add(pos as usize) },
+            pos,
+            sql_type: &self.sql_type,
+        };
+        value.to_sql(&mut data)
+    }
+}
+
+impl Drop for Var {
+    fn drop(&mut self) {
+        unsafe { dpiVar_release(self.handle) };
+    }
+}
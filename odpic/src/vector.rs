@@ -0,0 +1,788 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+//! Vector values of Oracle 23ai `VECTOR` data type
+
+use crate::number::{
+    decode_binary_double, decode_binary_float, encode_binary_double, encode_binary_float,
+};
+use crate::{Error, Result};
+use odpic_sys::*;
+use std::fmt;
+use std::marker::PhantomData;
+
+const VECTOR_MAGIC_BYTE: u8 = 0xDB;
+const VECTOR_VERSION_BASE: u8 = 0;
+const VECTOR_VERSION_WITH_BINARY: u8 = 1;
+const VECTOR_VERSION_WITH_SPARSE: u8 = 2;
+const VECTOR_FLAG_NORM: u16 = 0x0002;
+const VECTOR_FLAG_NORM_RESERVED: u16 = 0x0010;
+const VECTOR_FLAG_SPARSE: u16 = 0x0020;
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+    impl Sealed for i8 {}
+}
+
+/// Dimension type of [`DenseVector`] and [`SparseVector`]
+///
+/// This is implemented for `f32`, `f64` and `i8`, which correspond to
+/// `DPI_VECTOR_FORMAT_FLOAT32`, `DPI_VECTOR_FORMAT_FLOAT64` and
+/// `DPI_VECTOR_FORMAT_INT8` respectively.
+pub trait VectorElement: Copy + PartialEq + fmt::Debug + private::Sealed {
+    /// storage format of vectors whose dimensions are this type
+    const FORMAT: dpiVectorFormat;
+
+    #[doc(hidden)]
+    fn dense_into(vec: DenseVector<Self>) -> Vector;
+    #[doc(hidden)]
+    fn sparse_into(vec: SparseVector<Self>) -> Vector;
+    #[doc(hidden)]
+    fn dense_from(vec: Vector) -> std::result::Result<DenseVector<Self>, Vector>;
+    #[doc(hidden)]
+    fn sparse_from(vec: Vector) -> std::result::Result<SparseVector<Self>, Vector>;
+    #[doc(hidden)]
+    fn write_image(self, buf: &mut Vec<u8>);
+    #[doc(hidden)]
+    fn read_image(r: &mut ImageReader) -> Result<Self>;
+}
+
+macro_rules! impl_vector_element {
+    ($ty:ty, $format:ident, $dense:ident, $sparse:ident, $write:expr, $read:expr) => {
+        impl VectorElement for $ty {
+            const FORMAT: dpiVectorFormat = $format;
+
+            fn dense_into(vec: DenseVector<Self>) -> Vector {
+                Vector::$dense(vec)
+            }
+
+            fn sparse_into(vec: SparseVector<Self>) -> Vector {
+                Vector::$sparse(vec)
+            }
+
+            fn dense_from(vec: Vector) -> std::result::Result<DenseVector<Self>, Vector> {
+                match vec {
+                    Vector::$dense(vec) => Ok(vec),
+                    _ => Err(vec),
+                }
+            }
+
+            fn sparse_from(vec: Vector) -> std::result::Result<SparseVector<Self>, Vector> {
+                match vec {
+                    Vector::$sparse(vec) => Ok(vec),
+                    _ => Err(vec),
+                }
+            }
+
+            fn write_image(self, buf: &mut Vec<u8>) {
+                let write: fn($ty, &mut Vec<u8>) = $write;
+                write(self, buf)
+            }
+
+            fn read_image(r: &mut ImageReader) -> Result<Self> {
+                let read: fn(&mut ImageReader) -> Result<$ty> = $read;
+                read(r)
+            }
+        }
+    };
+}
+
+impl_vector_element!(
+    f32,
+    DPI_VECTOR_FORMAT_FLOAT32,
+    Float32,
+    SparseFloat32,
+    |v, buf| buf.extend_from_slice(&encode_binary_float(v)),
+    |r| Ok(decode_binary_float(r.array()?))
+);
+impl_vector_element!(
+    f64,
+    DPI_VECTOR_FORMAT_FLOAT64,
+    Float64,
+    SparseFloat64,
+    |v, buf| buf.extend_from_slice(&encode_binary_double(v)),
+    |r| Ok(decode_binary_double(r.array()?))
+);
+impl_vector_element!(
+    i8,
+    DPI_VECTOR_FORMAT_INT8,
+    Int8,
+    SparseInt8,
+    |v, buf| buf.push(v as u8),
+    |r| Ok(r.u8()? as i8)
+);
+
+/// Dense vector
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DenseVector<T: VectorElement> {
+    values: Vec<T>,
+}
+
+impl<T: VectorElement> DenseVector<T> {
+    pub fn new(values: Vec<T>) -> DenseVector<T> {
+        DenseVector { values }
+    }
+
+    /// Returns the number of dimensions.
+    pub fn num_dimensions(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<T: VectorElement> From<Vec<T>> for DenseVector<T> {
+    fn from(values: Vec<T>) -> DenseVector<T> {
+        DenseVector::new(values)
+    }
+}
+
+impl<T: VectorElement> TryFrom<Vector> for DenseVector<T> {
+    type Error = Error;
+
+    fn try_from(vec: Vector) -> Result<DenseVector<T>> {
+        T::dense_from(vec).map_err(|vec| format_mismatch(&vec, T::FORMAT, false))
+    }
+}
+
+/// Sparse vector
+///
+/// Dimensions not in `indices` are zero.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SparseVector<T: VectorElement> {
+    num_dimensions: u32,
+    indices: Vec<u32>,
+    values: Vec<T>,
+}
+
+impl<T: VectorElement> SparseVector<T> {
+    /// Creates a sparse vector.
+    ///
+    /// `indices` must be in ascending order without duplicates, less than
+    /// `num_dimensions` and have the same length with `values`, which must not
+    /// be empty.
+    pub fn new(num_dimensions: u32, indices: Vec<u32>, values: Vec<T>) -> Result<SparseVector<T>> {
+        if indices.len() != values.len() {
+            return Err(Error::InvalidVector(format!(
+                "{} indices for {} values",
+                indices.len(),
+                values.len()
+            )));
+        }
+        // ODPI-C takes vectors without sparse values as dense ones.
+        if values.is_empty() {
+            return Err(Error::InvalidVector(
+                "sparse vectors need at least one non-zero dimension".into(),
+            ));
+        }
+        if indices.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(Error::InvalidVector(
+                "indices must be in strictly ascending order".into(),
+            ));
+        }
+        if let Some(idx) = indices.last().filter(|idx| **idx >= num_dimensions) {
+            return Err(Error::InvalidVector(format!(
+                "index {} out of {} dimensions",
+                idx, num_dimensions
+            )));
+        }
+        Ok(SparseVector {
+            num_dimensions,
+            indices,
+            values,
+        })
+    }
+
+    /// Returns the number of dimensions including zero ones.
+    pub fn num_dimensions(&self) -> u32 {
+        self.num_dimensions
+    }
+
+    /// Returns the indices of non-zero dimensions.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Returns the values of non-zero dimensions.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the value at `index`, or `None` when it is zero.
+    pub fn get(&self, index: u32) -> Option<T> {
+        self.indices
+            .binary_search(&index)
+            .ok()
+            .map(|pos| self.values[pos])
+    }
+}
+
+impl<T: VectorElement> TryFrom<Vector> for SparseVector<T> {
+    type Error = Error;
+
+    fn try_from(vec: Vector) -> Result<SparseVector<T>> {
+        T::sparse_from(vec).map_err(|vec| format_mismatch(&vec, T::FORMAT, true))
+    }
+}
+
+/// Binary vector, whose dimensions are single bits
+///
+/// Each byte packs eight dimensions. The first dimension is the most
+/// significant bit of the first byte.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BinaryVector {
+    bytes: Vec<u8>,
+}
+
+impl BinaryVector {
+    pub fn new(bytes: Vec<u8>) -> BinaryVector {
+        BinaryVector { bytes }
+    }
+
+    /// Creates a binary vector from bits. The number of bits must be a
+    /// multiple of eight.
+    pub fn from_bits<I>(bits: I) -> Result<BinaryVector>
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let mut bytes = Vec::new();
+        let mut num_bits = 0usize;
+        for bit in bits {
+            if num_bits.is_multiple_of(8) {
+                bytes.push(0);
+            }
+            if bit {
+                *bytes.last_mut().unwrap() |= 0x80 >> (num_bits % 8);
+            }
+            num_bits += 1;
+        }
+        if !num_bits.is_multiple_of(8) {
+            return Err(Error::InvalidVector(format!(
+                "the number of dimensions {} isn't a multiple of 8",
+                num_bits
+            )));
+        }
+        Ok(BinaryVector { bytes })
+    }
+
+    /// Returns the number of dimensions, which is eight times of the number
+    /// of bytes.
+    pub fn num_dimensions(&self) -> usize {
+        self.bytes.len() * 8
+    }
+
+    /// Returns the bit at `index`.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.bytes
+            .get(index / 8)
+            .map(|b| b & (0x80 >> (index % 8)) != 0)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl From<BinaryVector> for Vector {
+    fn from(vec: BinaryVector) -> Vector {
+        Vector::Binary(vec)
+    }
+}
+
+impl TryFrom<Vector> for BinaryVector {
+    type Error = Error;
+
+    fn try_from(vec: Vector) -> Result<BinaryVector> {
+        match vec {
+            Vector::Binary(vec) => Ok(vec),
+            _ => Err(format_mismatch(&vec, DPI_VECTOR_FORMAT_BINARY, false)),
+        }
+    }
+}
+
+/// Vector value
+///
+/// This is an owned counterpart of [`dpiVectorInfo`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Vector {
+    /// `DPI_VECTOR_FORMAT_FLOAT32`
+    Float32(DenseVector<f32>),
+    /// `DPI_VECTOR_FORMAT_FLOAT64`
+    Float64(DenseVector<f64>),
+    /// `DPI_VECTOR_FORMAT_INT8`
+    Int8(DenseVector<i8>),
+    /// `DPI_VECTOR_FORMAT_BINARY`
+    Binary(BinaryVector),
+    /// sparse `DPI_VECTOR_FORMAT_FLOAT32`
+    SparseFloat32(SparseVector<f32>),
+    /// sparse `DPI_VECTOR_FORMAT_FLOAT64`
+    SparseFloat64(SparseVector<f64>),
+    /// sparse `DPI_VECTOR_FORMAT_INT8`
+    SparseInt8(SparseVector<i8>),
+}
+
+impl<T: VectorElement> From<DenseVector<T>> for Vector {
+    fn from(vec: DenseVector<T>) -> Vector {
+        T::dense_into(vec)
+    }
+}
+
+impl<T: VectorElement> From<SparseVector<T>> for Vector {
+    fn from(vec: SparseVector<T>) -> Vector {
+        T::sparse_into(vec)
+    }
+}
+
+impl Vector {
+    /// Returns the storage format of the vector.
+    pub fn format(&self) -> dpiVectorFormat {
+        match self {
+            Vector::Float32(_) | Vector::SparseFloat32(_) => DPI_VECTOR_FORMAT_FLOAT32,
+            Vector::Float64(_) | Vector::SparseFloat64(_) => DPI_VECTOR_FORMAT_FLOAT64,
+            Vector::Int8(_) | Vector::SparseInt8(_) => DPI_VECTOR_FORMAT_INT8,
+            Vector::Binary(_) => DPI_VECTOR_FORMAT_BINARY,
+        }
+    }
+
+    /// Returns true when the vector is sparse.
+    pub fn is_sparse(&self) -> bool {
+        matches!(
+            self,
+            Vector::SparseFloat32(_) | Vector::SparseFloat64(_) | Vector::SparseInt8(_)
+        )
+    }
+
+    /// Returns the number of dimensions.
+    pub fn num_dimensions(&self) -> usize {
+        match self {
+            Vector::Float32(v) => v.num_dimensions(),
+            Vector::Float64(v) => v.num_dimensions(),
+            Vector::Int8(v) => v.num_dimensions(),
+            Vector::Binary(v) => v.num_dimensions(),
+            Vector::SparseFloat32(v) => v.num_dimensions() as usize,
+            Vector::SparseFloat64(v) => v.num_dimensions() as usize,
+            Vector::SparseInt8(v) => v.num_dimensions() as usize,
+        }
+    }
+
+    /// Creates a vector by copying dimensions which `info` points to.
+    ///
+    /// # Safety
+    ///
+    /// `info` must be filled by ODPI-C, for example by [`dpiVector_getValue`],
+    /// and its buffers must be still valid.
+    pub unsafe fn from_dpi(info: &dpiVectorInfo) -> Result<Vector> {
+        unsafe fn slice<'a, T>(ptr: *const T, len: u32) -> &'a [T] {
+            if len == 0 {
+                &[]
+            } else {
+                std::slice::from_raw_parts(ptr, len as usize)
+            }
+        }
+        unsafe fn from_dpi_as<T: VectorElement>(info: &dpiVectorInfo) -> Result<Vector> {
+            let ptr = info.dimensions.asPtr as *const T;
+            if info.numSparseValues == 0 {
+                let values = slice(ptr, info.numDimensions).to_vec();
+                Ok(DenseVector::new(values).into())
+            } else {
+                let values = slice(ptr, info.numSparseValues).to_vec();
+                let indices = slice(info.sparseIndices, info.numSparseValues).to_vec();
+                Ok(SparseVector::new(info.numDimensions, indices, values)?.into())
+            }
+        }
+        match info.format {
+            DPI_VECTOR_FORMAT_FLOAT32 => from_dpi_as::<f32>(info),
+            DPI_VECTOR_FORMAT_FLOAT64 => from_dpi_as::<f64>(info),
+            DPI_VECTOR_FORMAT_INT8 => from_dpi_as::<i8>(info),
+            DPI_VECTOR_FORMAT_BINARY => {
+                if !info.numDimensions.is_multiple_of(8) {
+                    return Err(Error::InvalidVector(format!(
+                        "the number of dimensions {} isn't a multiple of 8",
+                        info.numDimensions
+                    )));
+                }
+                let bytes = slice(info.dimensions.asPtr as *const u8, info.numDimensions / 8);
+                Ok(BinaryVector::new(bytes.to_vec()).into())
+            }
+            format => Err(Error::InvalidVector(format!(
+                "unsupported vector format {}",
+                format
+            ))),
+        }
+    }
+
+    /// Returns [`dpiVectorInfo`] pointing to buffers in the vector, which is
+    /// passed to [`dpiVector_setValue`] or [`dpiConn_newVector`].
+    pub fn to_dpi(&self) -> Result<VectorInfoRef<'_>> {
+        fn ptr_of<T>(values: &[T]) -> dpiVectorDimensionBuffer {
+            dpiVectorDimensionBuffer {
+                asPtr: values.as_ptr() as *mut _,
+            }
+        }
+        fn len_of(len: usize) -> Result<u32> {
+            u32::try_from(len).map_err(|_| Error::InvalidVector("too many dimensions".into()))
+        }
+        let mut info = dpiVectorInfo {
+            format: self.format(),
+            ..Default::default()
+        };
+        match self {
+            Vector::Float32(v) => info.dimensions = ptr_of(v.as_slice()),
+            Vector::Float64(v) => info.dimensions = ptr_of(v.as_slice()),
+            Vector::Int8(v) => info.dimensions = ptr_of(v.as_slice()),
+            Vector::Binary(v) => info.dimensions = ptr_of(v.as_bytes()),
+            Vector::SparseFloat32(v) => {
+                info.dimensions = ptr_of(v.values());
+                info.sparseIndices = v.indices().as_ptr() as *mut u32;
+                info.numSparseValues = len_of(v.values().len())?;
+            }
+            Vector::SparseFloat64(v) => {
+                info.dimensions = ptr_of(v.values());
+                info.sparseIndices = v.indices().as_ptr() as *mut u32;
+                info.numSparseValues = len_of(v.values().len())?;
+            }
+            Vector::SparseInt8(v) => {
+                info.dimensions = ptr_of(v.values());
+                info.sparseIndices = v.indices().as_ptr() as *mut u32;
+                info.numSparseValues = len_of(v.values().len())?;
+            }
+        }
+        if self.is_sparse() && info.numSparseValues == 0 {
+            return Err(Error::InvalidVector(
+                "sparse vectors need at least one non-zero dimension".into(),
+            ));
+        }
+        info.numDimensions = len_of(self.num_dimensions())?;
+        Ok(VectorInfoRef {
+            info,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// [`dpiVectorInfo`] borrowing buffers in a [`Vector`]
+///
+/// ODPI-C doesn't modify buffers passed to [`dpiVector_setValue`] and
+/// [`dpiConn_newVector`] though their pointers are `*mut`.
+pub struct VectorInfoRef<'a> {
+    info: dpiVectorInfo,
+    _marker: PhantomData<&'a Vector>,
+}
+
+impl VectorInfoRef<'_> {
+    pub fn as_ptr(&self) -> *const dpiVectorInfo {
+        &self.info
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut dpiVectorInfo {
+        &mut self.info
+    }
+}
+
+/// Definition of a `VECTOR` column
+///
+/// This checks whether vectors fit in columns before they are sent to the
+/// database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VectorType {
+    /// storage format, or `None` for `VECTOR(n, *)`
+    pub format: Option<dpiVectorFormat>,
+    /// number of dimensions, or `None` for `VECTOR(*, f)`
+    pub num_dimensions: Option<u32>,
+    /// `DPI_VECTOR_FLAGS_SPARSE` or not
+    pub is_sparse: bool,
+}
+
+impl VectorType {
+    /// Gets vector information in [`dpiDataTypeInfo`] of `VECTOR` columns.
+    pub fn from_data_type_info(info: &dpiDataTypeInfo) -> VectorType {
+        VectorType {
+            format: Some(info.vectorFormat).filter(|f| *f != 0),
+            num_dimensions: if info.vectorFlags & DPI_VECTOR_FLAGS_FLEXIBLE_DIM != 0 {
+                None
+            } else {
+                Some(info.vectorDimensions)
+            },
+            is_sparse: info.vectorFlags & DPI_VECTOR_FLAGS_SPARSE != 0,
+        }
+    }
+
+    /// Returns `vectorFlags` of [`dpiDataTypeInfo`].
+    pub fn flags(&self) -> dpiVectorFlags {
+        let mut flags = 0;
+        if self.num_dimensions.is_none() {
+            flags |= DPI_VECTOR_FLAGS_FLEXIBLE_DIM;
+        }
+        if self.is_sparse {
+            flags |= DPI_VECTOR_FLAGS_SPARSE;
+        }
+        flags
+    }
+
+    /// Checks whether `vector` can be stored in the column.
+    pub fn validate(&self, vector: &Vector) -> Result<()> {
+        if let Some(format) = self.format.filter(|f| *f != vector.format()) {
+            return Err(Error::InvalidVector(format!(
+                "format {} is required but {}",
+                format,
+                vector.format()
+            )));
+        }
+        if let Some(num_dims) = self.num_dimensions {
+            if num_dims as usize != vector.num_dimensions() {
+                return Err(Error::InvalidVector(format!(
+                    "{} dimensions are required but {}",
+                    num_dims,
+                    vector.num_dimensions()
+                )));
+            }
+        }
+        if self.is_sparse != vector.is_sparse() {
+            return Err(Error::InvalidVector(if self.is_sparse {
+                "sparse vector is required".into()
+            } else {
+                "dense vector is required".into()
+            }));
+        }
+        Ok(())
+    }
+}
+
+fn format_mismatch(vec: &Vector, format: dpiVectorFormat, sparse: bool) -> Error {
+    Error::InvalidVector(format!(
+        "{} vector of format {} isn't {} vector of format {}",
+        if vec.is_sparse() { "sparse" } else { "dense" },
+        vec.format(),
+        if sparse { "sparse" } else { "dense" },
+        format
+    ))
+}
+
+/// Converts the image format of Oracle VECTOR, which is also used in OSON,
+/// to a vector.
+pub(crate) fn decode_image(data: &[u8]) -> Result<Vector> {
+    fn read_values<T: VectorElement>(r: &mut ImageReader, len: usize) -> Result<Vec<T>> {
+        (0..len).map(|_| T::read_image(r)).collect()
+    }
+    fn read_sparse<T: VectorElement>(r: &mut ImageReader, num_dims: u32) -> Result<Vector> {
+        let len = r.u16()? as usize;
+        let indices = (0..len).map(|_| r.u32()).collect::<Result<_>>()?;
+        let values = read_values(r, len)?;
+        Ok(SparseVector::<T>::new(num_dims, indices, values)?.into())
+    }
+    fn read_dense<T: VectorElement>(r: &mut ImageReader, num_dims: u32) -> Result<Vector> {
+        Ok(DenseVector::<T>::new(read_values(r, num_dims as usize)?).into())
+    }
+
+    let mut r = ImageReader { data, pos: 0 };
+    if r.u8()? != VECTOR_MAGIC_BYTE {
+        return Err(Error::InvalidVector("invalid magic byte".into()));
+    }
+    let version = r.u8()?;
+    if version > VECTOR_VERSION_WITH_SPARSE {
+        return Err(Error::InvalidVector(format!(
+            "unsupported image version {}",
+            version
+        )));
+    }
+    let flags = r.u16()?;
+    let format = r.u8()?;
+    let num_dims = r.u32()?;
+    if flags & VECTOR_FLAG_NORM != 0 {
+        r.bytes(8)?;
+    }
+    let is_sparse = flags & VECTOR_FLAG_SPARSE != 0;
+    match (format, is_sparse) {
+        (DPI_VECTOR_FORMAT_FLOAT32, false) => read_dense::<f32>(&mut r, num_dims),
+        (DPI_VECTOR_FORMAT_FLOAT64, false) => read_dense::<f64>(&mut r, num_dims),
+        (DPI_VECTOR_FORMAT_INT8, false) => read_dense::<i8>(&mut r, num_dims),
+        (DPI_VECTOR_FORMAT_FLOAT32, true) => read_sparse::<f32>(&mut r, num_dims),
+        (DPI_VECTOR_FORMAT_FLOAT64, true) => read_sparse::<f64>(&mut r, num_dims),
+        (DPI_VECTOR_FORMAT_INT8, true) => read_sparse::<i8>(&mut r, num_dims),
+        (DPI_VECTOR_FORMAT_BINARY, false) => {
+            let bytes = r.bytes(num_dims as usize / 8)?;
+            Ok(BinaryVector::new(bytes.to_vec()).into())
+        }
+        _ => Err(Error::InvalidVector(format!(
+            "unsupported {}vector format {}",
+            if is_sparse { "sparse " } else { "" },
+            format
+        ))),
+    }
+}
+
+/// Converts a vector to the image format of Oracle VECTOR.
+pub(crate) fn encode_image(vector: &Vector) -> Result<Vec<u8>> {
+    fn write_sparse<T: VectorElement>(buf: &mut Vec<u8>, vec: &SparseVector<T>) -> Result<()> {
+        let len = u16::try_from(vec.values().len())
+            .map_err(|_| Error::InvalidVector("sparse vector has more than 65535 values".into()))?;
+        buf.extend_from_slice(&len.to_be_bytes());
+        vec.indices()
+            .iter()
+            .for_each(|idx| buf.extend_from_slice(&idx.to_be_bytes()));
+        vec.values().iter().for_each(|v| v.write_image(buf));
+        Ok(())
+    }
+    fn write_dense<T: VectorElement>(buf: &mut Vec<u8>, vec: &DenseVector<T>) {
+        vec.as_slice().iter().for_each(|v| v.write_image(buf));
+    }
+
+    let num_dims = u32::try_from(vector.num_dimensions())
+        .map_err(|_| Error::InvalidVector("too many dimensions".into()))?;
+    let (version, flags) = match vector {
+        Vector::Binary(_) => (VECTOR_VERSION_WITH_BINARY, VECTOR_FLAG_NORM_RESERVED),
+        _ if vector.is_sparse() => (
+            VECTOR_VERSION_WITH_SPARSE,
+            VECTOR_FLAG_NORM_RESERVED | VECTOR_FLAG_NORM | VECTOR_FLAG_SPARSE,
+        ),
+        _ => (
+            VECTOR_VERSION_BASE,
+            VECTOR_FLAG_NORM_RESERVED | VECTOR_FLAG_NORM,
+        ),
+    };
+    let mut buf = vec![VECTOR_MAGIC_BYTE, version];
+    buf.extend_from_slice(&flags.to_be_bytes());
+    buf.push(vector.format());
+    buf.extend_from_slice(&num_dims.to_be_bytes());
+    if flags & VECTOR_FLAG_NORM != 0 {
+        buf.extend_from_slice(&[0; 8]);
+    }
+    match vector {
+        Vector::Float32(v) => write_dense(&mut buf, v),
+        Vector::Float64(v) => write_dense(&mut buf, v),
+        Vector::Int8(v) => write_dense(&mut buf, v),
+        Vector::Binary(v) => buf.extend_from_slice(v.as_bytes()),
+        Vector::SparseFloat32(v) => write_sparse(&mut buf, v)?,
+        Vector::SparseFloat64(v) => write_sparse(&mut buf, v)?,
+        Vector::SparseInt8(v) => write_sparse(&mut buf, v)?,
+    }
+    Ok(buf)
+}
+
+#[doc(hidden)]
+pub struct ImageReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ImageReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error::InvalidVector("unexpected end of data".into()))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+}
+
+#[cfg(feature = "ndarray")]
+mod ndarray_support {
+    use super::*;
+    use ndarray::{Array1, ArrayView1};
+
+    impl<T: VectorElement> DenseVector<T> {
+        /// Returns a view of the vector without copying dimensions.
+        pub fn view(&self) -> ArrayView1<'_, T> {
+            ArrayView1::from(self.as_slice())
+        }
+    }
+
+    impl<T: VectorElement> From<Array1<T>> for DenseVector<T> {
+        /// Converts the array without copying when it is contiguous.
+        fn from(array: Array1<T>) -> DenseVector<T> {
+            let array = if array.is_standard_layout() {
+                array
+            } else {
+                array.as_standard_layout().into_owned()
+            };
+            let len = array.len();
+            let (mut values, offset) = array.into_raw_vec_and_offset();
+            let offset = offset.unwrap_or(0);
+            values.truncate(offset + len);
+            values.drain(..offset);
+            DenseVector::new(values)
+        }
+    }
+
+    impl<T: VectorElement> From<DenseVector<T>> for Array1<T> {
+        fn from(vec: DenseVector<T>) -> Array1<T> {
+            Array1::from(vec.into_vec())
+        }
+    }
+
+    /// Returns a view of dimensions in a buffer owned by ODPI-C without
+    /// copying them.
+    ///
+    /// # Safety
+    ///
+    /// `info` must be filled by ODPI-C, for example by [`dpiVector_getValue`],
+    /// and its buffers must be valid and unchanged during `'a`.
+    pub unsafe fn view_dpi<'a, T: VectorElement>(
+        info: &'a dpiVectorInfo,
+    ) -> Result<ArrayView1<'a, T>> {
+        if info.format != T::FORMAT || info.numSparseValues != 0 {
+            return Err(Error::InvalidVector(format!(
+                "{} vector of format {} isn't dense vector of format {}",
+                if info.numSparseValues != 0 {
+                    "sparse"
+                } else {
+                    "dense"
+                },
+                info.format,
+                T::FORMAT
+            )));
+        }
+        let ptr = info.dimensions.asPtr as *const T;
+        Ok(if info.numDimensions == 0 {
+            ArrayView1::from(&[][..])
+        } else {
+            ArrayView1::from_shape_ptr(info.numDimensions as usize, ptr)
+        })
+    }
+}
+
+#[cfg(feature = "ndarray")]
+pub use ndarray_support::view_dpi;
@@ -0,0 +1,253 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::aq::{
+    CommitMode, Consumer, DeliveryMode, DeqOptions, Dequeue, Message, MessageState, Wait,
+};
+use odpic::{Error, Result, Timestamp};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+type DeqResult<P> = Result<Vec<(P, i32)>>;
+
+// Queue returning scripted results of dequeue_many, which are lists of
+// payloads and numbers of attempts, and logging calls.
+struct MockQueue<P> {
+    results: RefCell<VecDeque<DeqResult<P>>>,
+    log: RefCell<Vec<String>>,
+}
+
+impl<P> MockQueue<P> {
+    fn new(results: Vec<DeqResult<P>>) -> MockQueue<P> {
+        MockQueue {
+            results: RefCell::new(results.into()),
+            log: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn take_log(&self) -> Vec<String> {
+        self.log.take()
+    }
+}
+
+impl<P> Dequeue for MockQueue<P> {
+    type Payload = P;
+
+    fn commit(&self) -> Result<()> {
+        self.log.borrow_mut().push("commit".into());
+        Ok(())
+    }
+
+    fn set_deq_options(&self, options: &DeqOptions) -> Result<()> {
+        for wait in [Wait::NoWait, Wait::Forever, Wait::Seconds(1)] {
+            if *options == DeqOptions::new().wait(wait) {
+                self.log.borrow_mut().push(format!("wait {:?}", wait));
+            }
+        }
+        Ok(())
+    }
+
+    fn dequeue_many(&self, max_messages: u32) -> Result<Vec<Message<P>>> {
+        self.log.borrow_mut().push(format!("deq {}", max_messages));
+        let messages = self
+            .results
+            .borrow_mut()
+            .pop_front()
+            .unwrap_or(Ok(vec![]))?;
+        Ok(messages
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (payload, num_attempts))| Message {
+                msg_id: vec![idx as u8],
+                original_msg_id: vec![],
+                correlation: String::new(),
+                exception_queue: String::new(),
+                priority: 0,
+                delay: 0,
+                expiration: -1,
+                num_attempts,
+                enq_time: Timestamp::new(2024, 1, 1, 0, 0, 0),
+                state: MessageState::Ready,
+                delivery_mode: DeliveryMode::Persistent,
+                payload,
+            })
+            .collect())
+    }
+}
+
+fn payloads(batch: Result<Vec<Message<u32>>>) -> Vec<u32> {
+    batch.unwrap().into_iter().map(|msg| msg.payload).collect()
+}
+
+#[test]
+fn commit_per_batch() {
+    let queue = MockQueue::new(vec![Ok(vec![(1, 0), (2, 0)]), Ok(vec![(3, 0)])]);
+    let mut consumer = Consumer::new(&queue).batch_size(2).wait(Wait::NoWait);
+    assert_eq!(payloads(consumer.next().unwrap()), [1, 2]);
+    assert_eq!(queue.take_log(), ["wait NoWait", "deq 2"]);
+    assert_eq!(payloads(consumer.next().unwrap()), [3]);
+    assert_eq!(queue.take_log(), ["commit", "deq 2"]);
+    // The iteration ends when the queue is empty with NoWait.
+    assert!(consumer.next().is_none());
+    assert_eq!(queue.take_log(), ["commit", "deq 2"]);
+    assert!(consumer.next().is_none());
+    assert!(queue.take_log().is_empty());
+}
+
+#[test]
+fn commit_per_message() {
+    let queue = MockQueue::new(vec![Ok(vec![(1, 0)]), Ok(vec![(2, 0)])]);
+    let consumer = Consumer::new(&queue)
+        .batch_size(10)
+        .wait(Wait::NoWait)
+        .commit_mode(CommitMode::PerMessage);
+    assert_eq!(consumer.map(payloads).collect::<Vec<_>>(), [[1], [2]]);
+    assert_eq!(
+        queue.take_log(),
+        ["wait NoWait", "deq 1", "commit", "deq 1", "commit", "deq 1"]
+    );
+}
+
+#[test]
+fn commit_manually() {
+    let queue = MockQueue::new(vec![Ok(vec![(1, 0)]), Ok(vec![(2, 0)])]);
+    let mut consumer = Consumer::new(&queue)
+        .wait(Wait::NoWait)
+        .commit_mode(CommitMode::Manual);
+    assert_eq!((&mut consumer).map(payloads).count(), 2);
+    consumer.commit().unwrap();
+    assert_eq!(
+        queue.take_log(),
+        ["wait NoWait", "deq 100", "deq 100", "deq 100"]
+    );
+
+    // Consumer::commit commits the current batch in other modes.
+    let queue = MockQueue::new(vec![Ok(vec![(1, 0)])]);
+    let mut consumer = Consumer::new(&queue).wait(Wait::NoWait);
+    assert_eq!(payloads(consumer.next().unwrap()), [1]);
+    consumer.commit().unwrap();
+    consumer.commit().unwrap();
+    assert_eq!(queue.take_log(), ["wait NoWait", "deq 100", "commit"]);
+}
+
+#[test]
+fn retry_threshold() {
+    let queue = MockQueue::new(vec![
+        Ok(vec![(1, 0), (2, 4), (3, 3)]),
+        Ok(vec![(4, 5)]),
+        Ok(vec![(5, 1)]),
+    ]);
+    let exceeded = RefCell::new(Vec::new());
+    let consumer = Consumer::new(&queue)
+        .wait(Wait::NoWait)
+        .on_retry_exceeded(3, |msg| {
+            exceeded.borrow_mut().push(msg.payload);
+            Ok(())
+        });
+    // A batch of only exceeded messages is committed without being returned.
+    assert_eq!(
+        consumer.map(payloads).collect::<Vec<_>>(),
+        [vec![1, 3], vec![5]]
+    );
+    assert_eq!(*exceeded.borrow(), [2, 4]);
+    assert_eq!(
+        queue.take_log(),
+        [
+            "wait NoWait",
+            "deq 100",
+            "commit",
+            "deq 100",
+            "commit",
+            "deq 100",
+            "commit",
+            "deq 100"
+        ]
+    );
+
+    // An error of the hook ends the iteration without committing.
+    let queue = MockQueue::new(vec![Ok(vec![(1, 9)])]);
+    let mut consumer = Consumer::new(&queue)
+        .wait(Wait::NoWait)
+        .on_retry_exceeded(3, |_| Err(Error::InvalidOperation("hook".into())));
+    assert!(matches!(
+        consumer.next(),
+        Some(Err(Error::InvalidOperation(_)))
+    ));
+    assert!(consumer.next().is_none());
+    assert_eq!(queue.take_log(), ["wait NoWait", "deq 100"]);
+}
+
+#[test]
+fn stop_handle() {
+    let queue = MockQueue::new(vec![Ok(vec![]), Ok(vec![(1, 0)]), Ok(vec![(2, 0)])]);
+    let mut consumer = Consumer::new(&queue).wait(Wait::Seconds(1));
+    let stop = consumer.stop_handle();
+    // Empty results are dequeued again unless the wait is NoWait.
+    assert_eq!(payloads(consumer.next().unwrap()), [1]);
+    stop.stop();
+    assert!(stop.is_stopped());
+    // The last batch is committed before stopping.
+    assert!(consumer.next().is_none());
+    assert_eq!(
+        queue.take_log(),
+        ["wait Seconds(1)", "deq 100", "deq 100", "commit"]
+    );
+}
+
+#[test]
+fn dequeue_errors() {
+    let queue = MockQueue::new(vec![
+        Ok(vec![(1, 0)]),
+        Err(Error::InvalidOperation("dequeue".into())),
+    ]);
+    let mut consumer = Consumer::new(&queue);
+    assert_eq!(payloads(consumer.next().unwrap()), [1]);
+    assert!(matches!(
+        consumer.next(),
+        Some(Err(Error::InvalidOperation(_)))
+    ));
+    assert!(consumer.next().is_none());
+    assert_eq!(
+        queue.take_log(),
+        ["wait Forever", "deq 100", "commit", "deq 100"]
+    );
+}
+
+#[test]
+fn undecodable_payloads() {
+    // payloads of JsonQueue
+    let invalid = || Err(Error::InvalidConversion("payload".into()));
+    let queue = MockQueue::new(vec![Ok(vec![(Ok(1), 0), (invalid(), 0), (invalid(), 4)])]);
+    let exceeded = RefCell::new(Vec::new());
+    let mut consumer = Consumer::new(&queue).wait(Wait::NoWait).on_retry_exceeded(
+        3,
+        |msg: Message<Result<u32>>| {
+            exceeded.borrow_mut().push(msg.payload.is_err());
+            Ok(())
+        },
+    );
+    // Messages whose payloads aren't deserialized are in batches or passed
+    // to the hook and committed with others.
+    let batch = consumer.next().unwrap().unwrap();
+    assert!(matches!(batch[0].payload, Ok(1)));
+    assert!(matches!(batch[1].payload, Err(Error::InvalidConversion(_))));
+    assert_eq!(batch.len(), 2);
+    assert!(consumer.next().is_none());
+    assert_eq!(*exceeded.borrow(), [true]);
+    assert_eq!(
+        queue.take_log(),
+        ["wait NoWait", "deq 100", "commit", "deq 100"]
+    );
+}
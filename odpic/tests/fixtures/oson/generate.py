@@ -0,0 +1,208 @@
+# Generates the OSON images in this directory.
+#
+# The images are written byte by byte from the OSON layout implemented by
+# python-oracledb (src/oracledb/impl/base/oson.pyx) without using odpic, so
+# that tests/oson.rs checks odpic's codec against an independent encoder.
+# They are not images produced by an Oracle server.
+#
+# Usage: python3 generate.py
+import os
+import struct
+
+OUT = os.path.dirname(os.path.abspath(__file__))
+
+MAGIC = b'\xff\x4a\x5a\x01'
+
+# header flags
+FLAG_REL_OFFSET_MODE = 0x0001
+FLAG_INLINE_LEAF = 0x0002
+FLAG_IS_SCALAR = 0x0010
+FLAG_HASH_ID_UINT8 = 0x0100
+FLAG_TINY_NODES_STAT = 0x2000
+
+
+def u16(value):
+    return struct.pack('>H', value)
+
+
+def u32(value):
+    return struct.pack('>I', value)
+
+
+def sortable(packed):
+    # BINARY_FLOAT and BINARY_DOUBLE are stored in the byte-comparable form.
+    b = bytearray(packed)
+    if b[0] & 0x80 == 0:
+        b[0] |= 0x80
+    else:
+        b = bytearray(x ^ 0xff for x in b)
+    return bytes(b)
+
+
+def binary_double(value):
+    return sortable(struct.pack('>d', value))
+
+
+def binary_float(value):
+    return sortable(struct.pack('>f', value))
+
+
+# FNV-1a hash of a field name
+def hash_id(name):
+    h = 0x811c9dc5
+    for c in name.encode():
+        h = ((h ^ c) * 16777619) & 0xffffffff
+    return h
+
+
+def sorted_names(names):
+    return sorted(set(names), key=lambda n: (hash_id(n) & 0xff, len(n.encode()), n.encode()))
+
+
+def scalar(tree):
+    return MAGIC + u16(FLAG_INLINE_LEAF | FLAG_IS_SCALAR) + u16(len(tree)) + tree
+
+
+def names_seg(names):
+    seg = b''
+    offsets = []
+    for name in names:
+        offsets.append(len(seg))
+        seg += bytes([len(name.encode())]) + name.encode()
+    return bytes(hash_id(n) & 0xff for n in names) + b''.join(u16(o) for o in offsets), seg
+
+
+def with_names(flags, names, tree):
+    ids, seg = names_seg(names)
+    return (MAGIC + u16(flags) + bytes([len(names)]) + u16(len(seg))
+            + u16(len(tree)) + u16(0) + ids + seg + bytes(tree))
+
+
+# Tree writer using uint32 offsets and uint8 numbers of children.
+class Tree:
+    def __init__(self, ids):
+        self.buf = bytearray()
+        self.ids = ids
+
+    def node(self, value):
+        kind = value[0]
+        if kind == 'null':
+            self.buf += b'\x30'
+        elif kind == 'bool':
+            self.buf += b'\x31' if value[1] else b'\x32'
+        elif kind == 'str':
+            data = value[1].encode()
+            self.buf += b'\x33' + bytes([len(data)]) + data
+        elif kind == 'num':
+            self.buf += b'\x34' + bytes([len(value[1])]) + value[1]
+        elif kind == 'raw':
+            self.buf += b'\x3a' + u16(len(value[1])) + value[1]
+        elif kind == 'arr':
+            self.buf += bytes([0xe0, len(value[1])])
+            pos = len(self.buf)
+            self.buf += b'\0' * 4 * len(value[1])
+            for elem in value[1]:
+                self.buf[pos:pos + 4] = u32(len(self.buf))
+                pos += 4
+                self.node(elem)
+        elif kind == 'obj':
+            self.buf += bytes([0xa4, len(value[1])])
+            id_pos = len(self.buf)
+            offset_pos = id_pos + len(value[1])
+            self.buf += b'\0' * 5 * len(value[1])
+            for name, elem in value[1]:
+                self.buf[id_pos] = self.ids[name]
+                id_pos += 1
+                self.buf[offset_pos:offset_pos + 4] = u32(len(self.buf))
+                offset_pos += 4
+                self.node(elem)
+
+
+def container(value, names):
+    names = sorted_names(names)
+    tree = Tree({n: i + 1 for i, n in enumerate(names)})
+    tree.node(value)
+    return with_names(FLAG_INLINE_LEAF | FLAG_HASH_ID_UINT8 | FLAG_TINY_NODES_STAT, names, tree.buf)
+
+
+# An array of two objects using inline lengths, uint16 offsets,
+# relative offsets and shared field ids. oson::encode doesn't create them.
+def compact():
+    names = sorted_names(['x', 'y'])
+    ids = {n: i + 1 for i, n in enumerate(names)}
+    tree = bytearray()
+
+    def rel(base, pos):
+        return u16(pos - base)
+
+    # root array with 2 elements and uint16 offsets
+    tree += bytes([0xc0, 2])
+    slots = len(tree)
+    tree += b'\0' * 4
+    # {"x": "ab", "y": 7} whose values have lengths in their node types
+    obj1 = len(tree)
+    tree += bytes([0x84, 2, ids[names[0]], ids[names[1]]])
+    obj1_slots = len(tree)
+    tree += b'\0' * 4
+    x = len(tree)
+    tree += b'\x02ab'
+    y = len(tree)
+    tree += b'\x42\xc1\x08'
+    values = {'x': x, 'y': y}
+    tree[obj1_slots:obj1_slots + 2] = rel(obj1, values[names[0]])
+    tree[obj1_slots + 2:obj1_slots + 4] = rel(obj1, values[names[1]])
+    # {"x": true, "y": 2} sharing field ids with the first object
+    obj2 = len(tree)
+    tree += bytes([0x84 | 0x18]) + u16(obj1)
+    obj2_slots = len(tree)
+    tree += b'\0' * 4
+    v1 = len(tree)
+    tree += b'\x31'
+    v2 = len(tree)
+    tree += b'\x21\xc1\x03'
+    tree[obj2_slots:obj2_slots + 2] = rel(obj2, v1)
+    tree[obj2_slots + 2:obj2_slots + 4] = rel(obj2, v2)
+    tree[slots:slots + 2] = rel(0, obj1)
+    tree[slots + 2:slots + 4] = rel(0, obj2)
+    return with_names(FLAG_REL_OFFSET_MODE | FLAG_INLINE_LEAF | FLAG_HASH_ID_UINT8, names, tree)
+
+
+def write(name, data):
+    with open(os.path.join(OUT, name + '.bin'), 'wb') as f:
+        f.write(data)
+
+
+# 2024-02-29 13:45:30
+date = bytes([120, 124, 2, 29, 14, 46, 31])
+# VECTOR FLOAT32 [1.0, -2.5]
+vector = b'\xdb\x00' + u16(0x12) + b'\x02' + u32(2) + b'\0' * 8 + binary_float(1.0) + binary_float(-2.5)
+
+write('null', scalar(b'\x30'))
+write('true', scalar(b'\x31'))
+write('string', scalar(b'\x33\x05hello'))
+write('number', scalar(b'\x34\x04\xc2\x02\x18\x2e'))  # 123.45
+write('negative_number', scalar(b'\x34\x04\x3e\x60\x33\x66'))  # -5.5
+write('binary_double', scalar(b'\x36' + binary_double(-1.25)))
+write('binary_float', scalar(b'\x7f' + binary_float(0.5)))
+write('raw', scalar(b'\x3a' + u16(3) + b'\x01\x02\xff'))
+write('date', scalar(b'\x3c' + date))
+# 2024-02-29 13:45:30.123456789
+write('timestamp', scalar(b'\x39' + date + u32(123456789)))
+# 2024-03-01 01:15:00 +09:30, stored as 2024-02-29 15:45:00 UTC
+write('timestamp_tz', scalar(b'\x7c' + bytes([120, 124, 2, 29, 16, 46, 1]) + u32(0) + bytes([9 + 20, 30 + 60])))
+# -1 02:03:04.5
+write('interval_ds', scalar(b'\x3e' + u32(0x80000000 - 1) + bytes([62, 63, 64]) + u32(0x80000000 + 500000000)))
+# 3-7
+write('interval_ym', scalar(b'\x3d' + u32(0x80000000 + 3) + bytes([67])))
+write('vector_float32', scalar(b'\x7b\x01' + u32(len(vector)) + vector))
+write('object', container(
+    ('obj', [
+        ('name', ('str', 'Scott')),
+        ('age', ('num', b'\xc1\x2b')),
+        ('tags', ('arr', [('str', 'a'), ('bool', True), ('null',)])),
+        ('nested', ('obj', [('name', ('raw', b'\xca\xfe'))])),
+    ]),
+    ['name', 'age', 'tags', 'nested'],
+))
+write('empty_array', container(('arr', []), []))
+write('compact', compact())
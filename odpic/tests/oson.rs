@@ -0,0 +1,260 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::oson;
+use odpic::vector::{BinaryVector, SparseVector};
+use odpic::{Error, IntervalDS, IntervalYM, JsonValue, Timestamp, Vector};
+use std::fs;
+use std::path::PathBuf;
+
+// The images in tests/fixtures/oson are written by tests/fixtures/oson/generate.py,
+// which lays out bytes following the OSON format implemented by python-oracledb
+// without using odpic. They aren't produced by an Oracle server. Run the script
+// to regenerate them after editing it.
+fn fixture(name: &str) -> Vec<u8> {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/fixtures/oson");
+    path.push(format!("{}.bin", name));
+    fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+fn number(text: &str) -> JsonValue {
+    JsonValue::Number(text.into())
+}
+
+fn obj(fields: Vec<(&str, JsonValue)>) -> JsonValue {
+    JsonValue::Object(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    )
+}
+
+// fixtures whose images are same with ones created by oson::encode
+fn canonical_fixtures() -> Vec<(&'static str, JsonValue)> {
+    let date = Timestamp::new(2024, 2, 29, 13, 45, 30);
+    vec![
+        ("null", JsonValue::Null),
+        ("true", JsonValue::Boolean(true)),
+        ("string", "hello".into()),
+        ("number", number("123.45")),
+        ("negative_number", number("-5.5")),
+        ("binary_double", JsonValue::Double(-1.25)),
+        ("binary_float", JsonValue::Float(0.5)),
+        ("raw", JsonValue::Raw(vec![1, 2, 0xff])),
+        ("date", JsonValue::Date(date)),
+        (
+            "timestamp",
+            JsonValue::Timestamp(date.and_nanosecond(123456789)),
+        ),
+        (
+            "timestamp_tz",
+            JsonValue::TimestampTz(Timestamp::new(2024, 3, 1, 1, 15, 0).and_tz_offset(9, 30)),
+        ),
+        (
+            "interval_ds",
+            JsonValue::IntervalDS(IntervalDS::new(-1, 2, 3, 4, 500000000)),
+        ),
+        ("interval_ym", JsonValue::IntervalYM(IntervalYM::new(3, 7))),
+        (
+            "vector_float32",
+            JsonValue::Vector(Vector::Float32(vec![1.0, -2.5].into())),
+        ),
+        (
+            "object",
+            obj(vec![
+                ("name", "Scott".into()),
+                ("age", number("42")),
+                (
+                    "tags",
+                    vec!["a".into(), true.into(), JsonValue::Null].into(),
+                ),
+                (
+                    "nested",
+                    obj(vec![("name", JsonValue::Raw(vec![0xca, 0xfe]))]),
+                ),
+            ]),
+        ),
+        ("empty_array", JsonValue::Array(vec![])),
+    ]
+}
+
+#[test]
+fn decode_fixtures() {
+    for (name, expected) in canonical_fixtures() {
+        assert_eq!(oson::decode(&fixture(name)).unwrap(), expected, "{}", name);
+    }
+}
+
+#[test]
+fn encode_fixtures() {
+    for (name, value) in canonical_fixtures() {
+        assert_eq!(oson::encode(&value).unwrap(), fixture(name), "{}", name);
+    }
+}
+
+#[test]
+fn decode_compact_nodes() {
+    // inline lengths, uint16 offsets, relative offsets and shared field ids
+    assert_eq!(
+        oson::decode(&fixture("compact")).unwrap(),
+        vec![
+            obj(vec![("x", "ab".into()), ("y", number("7"))]),
+            obj(vec![("x", true.into()), ("y", number("2"))]),
+        ]
+        .into()
+    );
+}
+
+#[test]
+fn round_trip() {
+    let long_name = "n".repeat(300);
+    let many_fields = (0..300)
+        .map(|i| (format!("f{}", i), JsonValue::from(i as i64)))
+        .collect();
+    let values = vec![
+        number("0"),
+        number("-0.000123"),
+        number("12345678901234567890123456789012345678"),
+        JsonValue::Number(format!("1{}", "0".repeat(125))),
+        JsonValue::Id(vec![0; 12]),
+        JsonValue::String("x".repeat(70000)),
+        JsonValue::Vector(Vector::Float64(vec![0.0, -0.0, f64::MAX].into())),
+        JsonValue::Vector(Vector::Int8(vec![-128, 0, 127].into())),
+        JsonValue::Vector(Vector::Binary(BinaryVector::new(vec![0xa5, 0x0f]))),
+        JsonValue::Vector(
+            SparseVector::new(1000, vec![3, 999], vec![1.5f32, -2.0])
+                .unwrap()
+                .into(),
+        ),
+        obj(vec![
+            (long_name.as_str(), JsonValue::Null),
+            ("short", 1i64.into()),
+        ]),
+        JsonValue::Object(many_fields),
+    ];
+    for value in values {
+        assert_eq!(oson::decode(&oson::encode(&value).unwrap()).unwrap(), value);
+    }
+}
+
+#[test]
+fn invalid_images() {
+    let image = fixture("object");
+    assert!(oson::decode(&image[..image.len() - 1]).is_err());
+    assert!(oson::decode(&[0xff, 0x4a, 0x5b, 0x01]).is_err());
+    assert!(oson::encode(&number("1e126")).is_err());
+    assert!(oson::encode(&number("12a")).is_err());
+    assert!(oson::encode(&number("1e2147483647")).is_err());
+    assert!(oson::encode(&number("1e2147483646")).is_err());
+    assert!(oson::encode(&number("0.01e-2147483648")).is_err());
+    assert_eq!(
+        oson::decode(&oson::encode(&number("0e2147483647")).unwrap()).unwrap(),
+        number("0")
+    );
+    // a container whose child is itself
+    assert!(matches!(
+        oson::decode(&[0xff, 0x4a, 0x5a, 0x01, 0x00, 0x10, 0x00, 0x04, 0xc0, 0x01, 0x00, 0x00]),
+        Err(Error::InvalidOson(_))
+    ));
+    // a timestamp with time zone whose minute offset is 0x80
+    let mut image = vec![0xff, 0x4a, 0x5a, 0x01, 0x00, 0x10, 0x00, 0x0e, 0x7c];
+    image.extend_from_slice(&[120, 124, 1, 2, 1, 1, 1, 0, 0, 0, 0, 20, 0x80]);
+    assert!(matches!(oson::decode(&image), Err(Error::InvalidOson(_))));
+}
+
+#[test]
+fn nesting_depth() {
+    fn nested(depth: usize) -> JsonValue {
+        (0..depth).fold(JsonValue::Null, |value, _| vec![value].into())
+    }
+    // Unoptimized builds need more stack than the default size of test threads.
+    std::thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(|| {
+            let image = oson::encode(&nested(1000)).unwrap();
+            assert_eq!(oson::decode(&image).unwrap(), nested(1000));
+            let image = oson::encode(&nested(1001)).unwrap();
+            assert!(matches!(oson::decode(&image), Err(Error::InvalidOson(_))));
+
+            // 10000 arrays each of which contains the next one
+            let depth = 10000;
+            let tree_seg_size = (depth * 4 + 1) as u16;
+            let mut image = vec![0xff, 0x4a, 0x5a, 0x01, 0x00, 0x10];
+            image.extend_from_slice(&tree_seg_size.to_be_bytes());
+            for i in 0..depth {
+                image.extend_from_slice(&[0xc0, 0x01]);
+                image.extend_from_slice(&((i + 1) as u16 * 4).to_be_bytes());
+            }
+            image.push(0x30);
+            assert!(matches!(oson::decode(&image), Err(Error::InvalidOson(_))));
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn out_of_range_fields() {
+    for it in [
+        IntervalDS::new(1_000_000_000, 0, 0, 0, 0),
+        IntervalDS::new(0, 24, 0, 0, 0),
+        IntervalDS::new(0, 0, -60, 0, 0),
+        IntervalDS::new(0, 0, 0, 60, 0),
+        IntervalDS::new(0, 0, 0, 0, -1_000_000_000),
+    ] {
+        assert!(matches!(
+            oson::encode(&JsonValue::IntervalDS(it)),
+            Err(Error::InvalidOson(_))
+        ));
+    }
+    for it in [IntervalYM::new(-1_000_000_000, 0), IntervalYM::new(0, 12)] {
+        assert!(matches!(
+            oson::encode(&JsonValue::IntervalYM(it)),
+            Err(Error::InvalidOson(_))
+        ));
+    }
+    let ts = Timestamp::new(2024, 1, 1, 0, 0, 0).and_tz_offset(15, 0);
+    assert!(matches!(
+        oson::encode(&JsonValue::TimestampTz(ts)),
+        Err(Error::InvalidOson(_))
+    ));
+    let it = IntervalDS::new(-999_999_999, -23, -59, -59, -999_999_999);
+    let image = oson::encode(&JsonValue::IntervalDS(it)).unwrap();
+    assert_eq!(oson::decode(&image).unwrap(), JsonValue::IntervalDS(it));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_json_values() {
+    let value = obj(vec![
+        ("id", number("12345678901234567890")),
+        ("price", number("9.5")),
+        ("raw", JsonValue::Raw(vec![0x0a, 0xff])),
+        (
+            "at",
+            JsonValue::Timestamp(Timestamp::new(2024, 1, 2, 3, 4, 5)),
+        ),
+        ("tags", vec![JsonValue::from("a"), JsonValue::Null].into()),
+    ]);
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        r#"{"id":12345678901234567890,"price":9.5,"raw":"0AFF","at":"2024-01-02T03:04:05","tags":["a",null]}"#
+    );
+    assert!(
+        serde_json::to_string(&JsonValue::Vector(BinaryVector::new(vec![0x80]).into())).is_err()
+    );
+}
@@ -0,0 +1,182 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::qbe::{Cond, Expr, Filter, OrderBy, Value};
+use odpic::Error;
+
+#[test]
+fn comparison_operators() {
+    let filter = Filter::new()
+        .field("a", Cond::eq(1))
+        .field("b", Cond::ne("x"))
+        .field("c", Cond::gt(1.5))
+        .field("d", Cond::gte(true))
+        .field("e", Cond::lt(Value::Null))
+        .field("f", Cond::lte(-3i64));
+    assert_eq!(
+        filter.to_json().unwrap(),
+        r#"{"a":{"$eq":1},"b":{"$ne":"x"},"c":{"$gt":1.5},"d":{"$gte":true},"e":{"$lt":null},"f":{"$lte":-3}}"#
+    );
+}
+
+#[test]
+fn set_and_string_operators() {
+    let filter = Filter::new()
+        .field("tag", Cond::is_in(["a", "b"]))
+        .field("n", Cond::not_in([1, 2]))
+        .field("note", Cond::exists(false))
+        .field("name", Cond::starts_with("Jo"))
+        .field("code", Cond::regex("^[A-Z]{2}\\d+$"));
+    assert_eq!(
+        filter.to_json().unwrap(),
+        r#"{"tag":{"$in":["a","b"]},"n":{"$nin":[1,2]},"note":{"$exists":false},"name":{"$startsWith":"Jo"},"code":{"$regex":"^[A-Z]{2}\\d+$"}}"#
+    );
+}
+
+#[test]
+fn not_and_combined_conditions() {
+    let filter = Filter::new()
+        .field("qty", Cond::gt(1).and(Cond::lt(5)).and(Cond::ne(3)))
+        .field("status", Cond::is_in(["closed", "void"]).not());
+    assert_eq!(
+        filter.to_json().unwrap(),
+        r#"{"qty":{"$gt":1,"$lt":5,"$ne":3},"status":{"$not":{"$in":["closed","void"]}}}"#
+    );
+}
+
+#[test]
+fn logical_expressions() {
+    let filter = Filter::new()
+        .and([
+            Expr::field("address.city", Cond::eq("Tokyo")),
+            Expr::or([
+                Expr::field("items[*].qty", Cond::gte(10)),
+                Expr::field("items[0].sku", Cond::starts_with("X")),
+            ]),
+        ])
+        .nor([Expr::field("deleted", Cond::eq(true))]);
+    assert_eq!(
+        filter.to_json().unwrap(),
+        r#"{"$and":[{"address.city":{"$eq":"Tokyo"}},{"$or":[{"items[*].qty":{"$gte":10}},{"items[0].sku":{"$startsWith":"X"}}]}],"$nor":[{"deleted":{"$eq":true}}]}"#
+    );
+}
+
+#[test]
+fn ids() {
+    assert_eq!(
+        Filter::new().id(["k1"]).to_json().unwrap(),
+        r#"{"$id":"k1"}"#
+    );
+    assert_eq!(
+        Filter::new().id(["k1", "k2"]).to_json().unwrap(),
+        r#"{"$id":["k1","k2"]}"#
+    );
+}
+
+#[test]
+fn order_by() {
+    let filter = Filter::new()
+        .field("age", Cond::gt(20))
+        .order_by(OrderBy::asc("name").datatype("varchar2"))
+        .order_by(OrderBy::desc("age"));
+    assert_eq!(
+        filter.to_json().unwrap(),
+        r#"{"$query":{"age":{"$gt":20}},"$orderby":{"$fields":[{"path":"name","datatype":"varchar2","order":"asc"},{"path":"age","order":"desc"}]}}"#
+    );
+    assert_eq!(
+        Filter::new().order_by(OrderBy::asc("x")).to_json().unwrap(),
+        r#"{"$query":{},"$orderby":{"$fields":[{"path":"x","order":"asc"}]}}"#
+    );
+}
+
+#[test]
+fn values() {
+    assert_eq!(Filter::new().to_json().unwrap(), "{}");
+    let filter = Filter::new()
+        .field("s", Cond::eq("quote\" back\\ nl\n ctl\u{1} é"))
+        .field("arr", Cond::eq(vec![vec![1, 2], vec![3]]))
+        .field("opt", Cond::eq(None::<i32>))
+        .field("f", Cond::eq(2.0));
+    assert_eq!(
+        filter.to_json().unwrap(),
+        r#"{"s":{"$eq":"quote\" back\\ nl\n ctl\u0001 é"},"arr":{"$eq":[[1,2],[3]]},"opt":{"$eq":null},"f":{"$eq":2}}"#
+    );
+}
+
+#[test]
+fn non_finite_numbers() {
+    for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(matches!(
+            Filter::new().field("x", Cond::eq(value)).to_json(),
+            Err(Error::InvalidConversion(_))
+        ));
+        assert!(Filter::new()
+            .field("x", Cond::is_in([1.0, value]))
+            .to_json()
+            .is_err());
+    }
+}
+
+#[test]
+fn repeated_keys() {
+    assert_eq!(
+        Filter::new()
+            .field("a", Cond::gt(1))
+            .field("a", Cond::lt(5))
+            .field("b", Cond::eq(0))
+            .to_json()
+            .unwrap(),
+        r#"{"$and":[{"a":{"$gt":1}},{"a":{"$lt":5}},{"b":{"$eq":0}}]}"#
+    );
+    assert_eq!(
+        Filter::new()
+            .or([Expr::field("a", Cond::eq(1)), Expr::field("b", Cond::eq(1))])
+            .or([Expr::field("c", Cond::eq(1)), Expr::field("d", Cond::eq(1))])
+            .to_json()
+            .unwrap(),
+        r#"{"$and":[{"$or":[{"a":{"$eq":1}},{"b":{"$eq":1}}]},{"$or":[{"c":{"$eq":1}},{"d":{"$eq":1}}]}]}"#
+    );
+    assert_eq!(
+        Filter::new()
+            .field("a", Cond::eq(1).and(Cond::eq(2)))
+            .to_json()
+            .unwrap(),
+        r#"{"$and":[{"a":{"$eq":1}},{"a":{"$eq":2}}]}"#
+    );
+    // a split condition and an explicit `$and` share the key
+    assert_eq!(
+        Filter::new()
+            .field("a", Cond::gt(1).and(Cond::gt(2)).and(Cond::lt(9)))
+            .and([Expr::field("b", Cond::eq(1))])
+            .to_json()
+            .unwrap(),
+        r#"{"$and":[{"$and":[{"a":{"$gt":1}},{"a":{"$gt":2}},{"a":{"$lt":9}}]},{"$and":[{"b":{"$eq":1}}]}]}"#
+    );
+    assert_eq!(
+        Filter::new()
+            .field("a", Cond::ne(1).and(Cond::ne(2)).not())
+            .field("b", Cond::not(Cond::gt(1).and(Cond::lt(5))))
+            .to_json()
+            .unwrap(),
+        r#"{"$nor":[{"$and":[{"a":{"$ne":1}},{"a":{"$ne":2}}]}],"b":{"$not":{"$gt":1,"$lt":5}}}"#
+    );
+    assert_eq!(
+        Filter::new()
+            .field("a", Cond::eq(1).not().and(Cond::eq(2).not()))
+            .to_json()
+            .unwrap(),
+        r#"{"$and":[{"a":{"$not":{"$eq":1}}},{"a":{"$not":{"$eq":2}}}]}"#
+    );
+}
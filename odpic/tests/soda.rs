@@ -0,0 +1,225 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::soda::*;
+use odpic::JsonValue;
+
+#[test]
+fn index_specs() {
+    let spec: IndexSpec = BTreeIndex::new("IDX")
+        .field(IndexField::new("name").datatype(IndexDataType::String(Some(100))))
+        .field(IndexField::new("born").datatype(IndexDataType::Date).desc())
+        .field(IndexField::new("x"))
+        .scalar_required(true)
+        .lax(false)
+        .index_nulls(true)
+        .into();
+    assert_eq!(spec.name(), "IDX");
+    assert_eq!(
+        spec.to_json(),
+        r#"{"name":"IDX","fields":[{"path":"name","datatype":"string","maxLength":100,"order":"asc"},{"path":"born","datatype":"date","order":"desc"},{"path":"x","order":"asc"}],"scalarRequired":true,"lax":false,"indexNulls":true}"#
+    );
+
+    let spec: IndexSpec = FunctionalIndex::new(
+        "PRICE",
+        IndexField::new("price")
+            .datatype(IndexDataType::Number)
+            .desc(),
+    )
+    .into();
+    assert_eq!(spec.name(), "PRICE");
+    assert_eq!(
+        spec.to_json(),
+        r#"{"name":"PRICE","fields":[{"path":"price","datatype":"number","order":"desc"}]}"#
+    );
+
+    let spec: IndexSpec = MultivalueIndex::new(
+        "TAGS",
+        IndexField::new("items[*].tag")
+            .datatype(IndexDataType::String(None))
+            .desc(),
+    )
+    .into();
+    assert_eq!(spec.name(), "TAGS");
+    assert_eq!(
+        spec.to_json(),
+        r#"{"name":"TAGS","multivalue":true,"fields":[{"path":"items[*].tag","datatype":"string"}]}"#
+    );
+
+    let spec: IndexSpec = SearchIndex::new("SEARCH")
+        .dataguide(true)
+        .search_on(SearchOn::TextValue)
+        .language("english")
+        .into();
+    assert_eq!(
+        spec.to_json(),
+        r#"{"name":"SEARCH","dataguide":"on","search_on":"text_value","language":"english"}"#
+    );
+    assert_eq!(
+        IndexSpec::from(SearchIndex::new("S").dataguide(false)).to_json(),
+        r#"{"name":"S","dataguide":"off"}"#
+    );
+
+    let spec: IndexSpec = SpatialIndex::new("GEO", "location")
+        .scalar_required(true)
+        .into();
+    assert_eq!(
+        spec.to_json(),
+        r#"{"name":"GEO","spatial":"location","scalarRequired":true}"#
+    );
+}
+
+#[test]
+fn collection_metadata() {
+    assert_eq!(CollectionMetadata::new().to_json(), "{}");
+    let metadata = CollectionMetadata::new()
+        .schema_name("APP")
+        .table_name("DOCS")
+        .key_column(
+            KeyColumn::new()
+                .name("ID")
+                .sql_type(KeySqlType::Number)
+                .max_length(20)
+                .assignment(KeyAssignment::Sequence("DOCS_SEQ".into())),
+        )
+        .content_column(
+            ContentColumn::new()
+                .name("DOC")
+                .sql_type(ContentSqlType::Varchar2)
+                .max_length(4000)
+                .validation(Validation::Strict),
+        )
+        .version_column("VER", VersionMethod::Sha256)
+        .last_modified_column("MODIFIED")
+        .creation_time_column("CREATED")
+        .media_type_column("MEDIA")
+        .read_only(false);
+    assert_eq!(
+        metadata.to_json(),
+        concat!(
+            r#"{"schemaName":"APP","tableName":"DOCS","#,
+            r#""keyColumn":{"name":"ID","sqlType":"NUMBER","maxLength":20,"assignmentMethod":"SEQUENCE","sequenceName":"DOCS_SEQ"},"#,
+            r#""contentColumn":{"name":"DOC","sqlType":"VARCHAR2","maxLength":4000,"validation":"STRICT"},"#,
+            r#""versionColumn":{"name":"VER","method":"SHA256"},"#,
+            r#""lastModifiedColumn":{"name":"MODIFIED"},"creationTimeColumn":{"name":"CREATED"},"#,
+            r#""mediaTypeColumn":{"name":"MEDIA"},"readOnly":false}"#
+        )
+    );
+}
+
+fn obj(fields: Vec<(&str, JsonValue)>) -> JsonValue {
+    JsonValue::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+}
+
+fn string(s: &str) -> JsonValue {
+    JsonValue::String(s.into())
+}
+
+#[test]
+fn data_guide() {
+    let value = obj(vec![
+        ("type", string("object")),
+        ("o:length", JsonValue::Number("512".into())),
+        (
+            "properties",
+            obj(vec![
+                (
+                    "name",
+                    obj(vec![
+                        ("type", string("string")),
+                        ("o:length", JsonValue::Number("16".into())),
+                        ("o:preferred_column_name", string("DATA$name")),
+                        ("o:frequency", JsonValue::Number("100".into())),
+                    ]),
+                ),
+                (
+                    "items",
+                    obj(vec![
+                        ("type", string("array")),
+                        (
+                            "items",
+                            obj(vec![
+                                ("type", string("object")),
+                                (
+                                    "properties",
+                                    obj(vec![(
+                                        "qty",
+                                        obj(vec![(
+                                            "oneOf",
+                                            JsonValue::Array(vec![
+                                                obj(vec![("type", string("number"))]),
+                                                obj(vec![("type", string("string"))]),
+                                            ]),
+                                        )]),
+                                    )]),
+                                ),
+                            ]),
+                        ),
+                    ]),
+                ),
+                ("at", obj(vec![("type", string("timestamp"))])),
+            ]),
+        ),
+    ]);
+    let guide = DataGuide::from_json(&value).unwrap();
+    assert_eq!(guide.root.schema_type, SchemaType::Object);
+    assert_eq!(guide.root.length, Some(512));
+    let (name, node) = &guide.root.properties[0];
+    assert_eq!(name, "name");
+    assert_eq!(node.schema_type, SchemaType::String);
+    assert_eq!(node.preferred_column_name.as_deref(), Some("DATA$name"));
+    assert_eq!(node.frequency, Some(100.0));
+
+    let paths: Vec<_> = guide
+        .paths()
+        .into_iter()
+        .map(|(path, node)| (path, node.schema_type.clone()))
+        .collect();
+    let number = SchemaNode {
+        schema_type: SchemaType::Number,
+        length: None,
+        preferred_column_name: None,
+        frequency: None,
+        properties: Vec::new(),
+        items: None,
+    };
+    let string_node = SchemaNode {
+        schema_type: SchemaType::String,
+        ..number.clone()
+    };
+    assert_eq!(
+        paths,
+        vec![
+            ("name".into(), SchemaType::String),
+            ("items".into(), SchemaType::Array),
+            ("items[*]".into(), SchemaType::Object),
+            (
+                "items[*].qty".into(),
+                SchemaType::OneOf(vec![number, string_node])
+            ),
+            ("at".into(), SchemaType::Other("timestamp".into())),
+        ]
+    );
+
+    let err = DataGuide::from_json(&obj(vec![(
+        "properties",
+        obj(vec![("a", obj(vec![("o:length", JsonValue::Double(1.0))]))]),
+    )]))
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid conversion: data guide node at 'a' has neither type nor oneOf"
+    );
+}
@@ -0,0 +1,55 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::{FromSql, Timestamp, ToSql, ToSqlNull};
+use odpic_sys::*;
+
+#[test]
+fn timestamp_bind_types() {
+    let ts = Timestamp::new(2024, 1, 2, 3, 4, 5);
+    assert_eq!(ts.sql_type().oracle_type, DPI_ORACLE_TYPE_TIMESTAMP);
+    assert_eq!(
+        Timestamp::sql_null_type().oracle_type,
+        DPI_ORACLE_TYPE_TIMESTAMP
+    );
+    assert_eq!(
+        ts.and_tz_offset(9, 0).sql_type().oracle_type,
+        DPI_ORACLE_TYPE_TIMESTAMP_TZ
+    );
+    assert_eq!(
+        ts.and_tz_offset(0, -30).sql_type().oracle_type,
+        DPI_ORACLE_TYPE_TIMESTAMP_TZ
+    );
+    // UTC is a time zone.
+    let utc = ts.and_tz_offset(0, 0);
+    assert!(utc.has_tz);
+    assert_ne!(utc, ts);
+    assert_eq!(utc.sql_type().oracle_type, DPI_ORACLE_TYPE_TIMESTAMP_TZ);
+    assert_eq!(utc.without_tz(), ts);
+    assert_eq!(
+        <Timestamp as FromSql>::sql_type().oracle_type,
+        DPI_ORACLE_TYPE_TIMESTAMP_TZ
+    );
+}
+
+#[test]
+fn null_values() {
+    let ts = Timestamp::new(2024, 1, 2, 3, 4, 5);
+    assert!(!ts.is_null());
+    assert!(!Some(ts).is_null());
+    assert!(None::<Timestamp>.is_null());
+    assert!(ToSql::is_null(&&None::<i32>));
+    assert!(Some(None::<i32>).is_null());
+}
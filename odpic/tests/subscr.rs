@@ -0,0 +1,94 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::subscr::{EventType, MessageRow, OpCode, Qos, SubscrMessage};
+use odpic_sys::*;
+use std::os::raw::c_char;
+use std::ptr;
+
+#[test]
+fn decode_message() {
+    let rowid = "AAAR5jAAEAAAACXAAA";
+    let mut rows = [dpiSubscrMessageRow {
+        operation: DPI_OPCODE_UPDATE,
+        rowid: rowid.as_ptr() as *const c_char,
+        rowidLength: rowid.len() as u32,
+    }];
+    let table_name = "SCOTT.EMP";
+    let mut tables = [dpiSubscrMessageTable {
+        operation: DPI_OPCODE_UPDATE | DPI_OPCODE_INSERT,
+        name: table_name.as_ptr() as *const c_char,
+        nameLength: table_name.len() as u32,
+        rows: rows.as_mut_ptr(),
+        numRows: rows.len() as u32,
+    }];
+    let mut queries = [dpiSubscrMessageQuery {
+        id: 42,
+        operation: DPI_OPCODE_UPDATE,
+        tables: tables.as_mut_ptr(),
+        numTables: tables.len() as u32,
+    }];
+    let db_name = "ORCL";
+    let msg = dpiSubscrMessage {
+        eventType: DPI_EVENT_QUERYCHANGE,
+        dbName: db_name.as_ptr() as *const c_char,
+        dbNameLength: db_name.len() as u32,
+        tables: ptr::null_mut(),
+        numTables: 0,
+        queries: queries.as_mut_ptr(),
+        numQueries: queries.len() as u32,
+        errorInfo: ptr::null_mut(),
+        txId: [1u8, 2, 3].as_ptr() as *const _,
+        txIdLength: 3,
+        registered: 1,
+        queueName: ptr::null(),
+        queueNameLength: 0,
+        consumerName: ptr::null(),
+        consumerNameLength: 0,
+        aqMsgId: ptr::null(),
+        aqMsgIdLength: 0,
+    };
+    let msg = unsafe { SubscrMessage::from_dpi(&msg) };
+    assert_eq!(msg.event_type, EventType::QueryChange);
+    assert_eq!(msg.db_name, "ORCL");
+    assert!(msg.tables.is_empty());
+    assert!(msg.error.is_none());
+    assert_eq!(msg.tx_id, [1, 2, 3]);
+    assert!(msg.registered);
+    assert_eq!(msg.queries.len(), 1);
+    let query = &msg.queries[0];
+    assert_eq!(query.id, 42);
+    assert_eq!(query.tables[0].name, "SCOTT.EMP");
+    assert!(query.tables[0].operation.contains(OpCode::INSERT));
+    assert_eq!(
+        query.tables[0].rows,
+        [MessageRow {
+            operation: OpCode::UPDATE,
+            rowid: rowid.into(),
+        }]
+    );
+}
+
+#[test]
+fn flags() {
+    let qos = Qos::ROWIDS | Qos::QUERY;
+    assert_eq!(qos.0, DPI_SUBSCR_QOS_ROWIDS | DPI_SUBSCR_QOS_QUERY);
+    assert!(qos.contains(Qos::ROWIDS));
+    assert!(!qos.contains(Qos::RELIABLE));
+    assert_eq!(
+        format!("{:?}", OpCode::INSERT | OpCode::DELETE),
+        "OpCode(0xa: INSERT | DELETE)"
+    );
+}
@@ -0,0 +1,35 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::tpc::Xid;
+use odpic::Error;
+
+#[test]
+fn xid_validation() {
+    let xid = Xid::new(1, b"gtrid".to_vec(), b"".to_vec()).unwrap();
+    assert_eq!(xid.to_string(), "1.6774726964.");
+    let dpi_xid = xid.to_dpi();
+    assert_eq!(dpi_xid.formatId, 1);
+    assert_eq!(dpi_xid.globalTransactionIdLength, 5);
+    assert_eq!(dpi_xid.branchQualifierLength, 0);
+
+    assert!(Xid::new(1, vec![0; 64], vec![0; 64]).is_ok());
+    for (format_id, gtrid, bqual) in [(-1, 1, 0), (1, 0, 0), (1, 65, 0), (1, 1, 65)] {
+        assert!(matches!(
+            Xid::new(format_id, vec![0; gtrid], vec![0; bqual]),
+            Err(Error::InvalidXid(_))
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+// odpic - safe wrappers over odpic-sys
+//
+// URL: https://github.com/kubo/odpic-sys
+//
+//-----------------------------------------------------------------------------
+// Copyright (c) 2024-2026 Kubo Takehiro <kubo@jiubao.org>. All rights reserved.
+// This program is free software: you can modify it and/or redistribute it
+// under the terms of:
+//
+// (i)  the Universal Permissive License v 1.0 or at your option, any
+//      later version (http://oss.oracle.com/licenses/upl); and/or
+//
+// (ii) the Apache License v 2.0. (http://www.apache.org/licenses/LICENSE-2.0)
+//-----------------------------------------------------------------------------
+
+use odpic::vector::{BinaryVector, DenseVector, SparseVector, VectorType};
+use odpic::{Error, Vector};
+use odpic_sys::*;
+
+#[test]
+fn dpi_vector_info_round_trip() {
+    let vectors: Vec<Vector> = vec![
+        DenseVector::new(vec![1.0f32, 2.0, 3.0]).into(),
+        DenseVector::new(vec![-1.0f64]).into(),
+        DenseVector::<i8>::new(vec![]).into(),
+        BinaryVector::from_bits([true, false, false, false, false, false, false, true])
+            .unwrap()
+            .into(),
+        SparseVector::new(10, vec![0, 9], vec![1i8, -1])
+            .unwrap()
+            .into(),
+    ];
+    for vector in vectors {
+        let info = vector.to_dpi().unwrap();
+        let copied = unsafe { Vector::from_dpi(&*info.as_ptr()) }.unwrap();
+        assert_eq!(copied, vector);
+    }
+}
+
+#[test]
+fn typed_conversion() {
+    let vector: Vector = DenseVector::new(vec![1.0f32, 2.0]).into();
+    assert_eq!(vector.format(), DPI_VECTOR_FORMAT_FLOAT32);
+    assert!(DenseVector::<f64>::try_from(vector.clone()).is_err());
+    assert!(SparseVector::<f32>::try_from(vector.clone()).is_err());
+    let dense = DenseVector::<f32>::try_from(vector).unwrap();
+    assert_eq!(dense.as_slice(), &[1.0, 2.0]);
+
+    let binary = BinaryVector::new(vec![0x80, 0x01]);
+    assert_eq!(binary.num_dimensions(), 16);
+    assert_eq!(binary.get(0), Some(true));
+    assert_eq!(binary.get(1), Some(false));
+    assert_eq!(binary.get(15), Some(true));
+    assert_eq!(binary.get(16), None);
+    assert!(BinaryVector::from_bits([true; 7]).is_err());
+}
+
+#[test]
+fn sparse_vector_validation() {
+    assert!(SparseVector::new(5, vec![1, 3], vec![1.0f32]).is_err());
+    assert!(SparseVector::new(5, vec![3, 1], vec![1.0f32, 2.0]).is_err());
+    assert!(SparseVector::new(5, vec![1, 5], vec![1.0f32, 2.0]).is_err());
+    // ODPI-C would read dimensions of empty sparse vectors as dense ones.
+    assert!(matches!(
+        SparseVector::<f32>::new(5, vec![], vec![]),
+        Err(Error::InvalidVector(_))
+    ));
+    let vec = SparseVector::new(5, vec![1, 4], vec![1.0f32, 2.0]).unwrap();
+    assert_eq!(vec.get(4), Some(2.0));
+    assert_eq!(vec.get(2), None);
+}
+
+#[test]
+fn vector_type_validation() {
+    let info = dpiDataTypeInfo {
+        oracleTypeNum: DPI_ORACLE_TYPE_VECTOR,
+        vectorDimensions: 3,
+        vectorFormat: DPI_VECTOR_FORMAT_FLOAT32,
+        ..Default::default()
+    };
+    let fixed = VectorType::from_data_type_info(&info);
+    assert_eq!(fixed.flags(), 0);
+    assert!(fixed
+        .validate(&DenseVector::new(vec![1.0f32, 2.0, 3.0]).into())
+        .is_ok());
+    assert!(fixed
+        .validate(&DenseVector::new(vec![1.0f32, 2.0]).into())
+        .is_err());
+    assert!(fixed
+        .validate(&DenseVector::new(vec![1.0f64, 2.0, 3.0]).into())
+        .is_err());
+
+    let flexible = VectorType::from_data_type_info(&dpiDataTypeInfo {
+        vectorFormat: 0,
+        vectorFlags: DPI_VECTOR_FLAGS_FLEXIBLE_DIM | DPI_VECTOR_FLAGS_SPARSE,
+        ..info
+    });
+    assert_eq!(flexible.format, None);
+    assert_eq!(flexible.num_dimensions, None);
+    assert!(flexible
+        .validate(&SparseVector::new(100, vec![1], vec![1i8]).unwrap().into())
+        .is_ok());
+    assert!(flexible
+        .validate(&DenseVector::new(vec![1i8]).into())
+        .is_err());
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn ndarray_views() {
+    use ndarray::{arr1, s, Array1};
+
+    let dense: DenseVector<f64> = arr1(&[1.0, 2.0, 3.0, 4.0]).slice_move(s![1..3]).into();
+    assert_eq!(dense.as_slice(), &[2.0, 3.0]);
+    assert_eq!(dense.view(), arr1(&[2.0, 3.0]));
+    let vector: Vector = dense.clone().into();
+    let info = vector.to_dpi().unwrap();
+    let view = unsafe { odpic::vector::view_dpi::<f64>(&*info.as_ptr()) }.unwrap();
+    assert_eq!(view, arr1(&[2.0, 3.0]));
+    assert!(unsafe { odpic::vector::view_dpi::<f32>(&*info.as_ptr()) }.is_err());
+    assert_eq!(Array1::from(dense), arr1(&[2.0, 3.0]));
+}